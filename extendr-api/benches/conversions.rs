@@ -0,0 +1,126 @@
+//! Benchmarks of the conversions between Rust values and R vectors,
+//! strings and lists, to catch performance regressions in the `Robj` layer.
+//!
+//! Run with `cargo bench --bench conversions`. R runs embedded in the benchmark,
+//! and criterion compares each run to the previous one.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use extendr_api::bench_internals::{fixed_size_collect, str_to_character};
+use extendr_api::prelude::*;
+use extendr_api::wrapper::list::{list_from_entries, ListEntry};
+
+const LENS: [usize; 2] = [1_000, 100_000];
+
+fn vectors(c: &mut Criterion) {
+    extendr_engine::start_r();
+    let mut group = c.benchmark_group("vectors");
+    for len in LENS {
+        let doubles: Vec<f64> = (0..len).map(|i| i as f64).collect();
+        let integers: Vec<i32> = (0..len as i32).collect();
+        let robj = r!(doubles.clone());
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec<f64> to Robj", len),
+            &doubles,
+            |b, x| b.iter(|| Robj::from(black_box(x))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Vec<i32> to Robj", len),
+            &integers,
+            |b, x| b.iter(|| Robj::from(black_box(x))),
+        );
+        group.bench_with_input(BenchmarkId::new("collect_robj", len), &len, |b, &len| {
+            b.iter(|| (0..len).map(|i| i as f64 * 0.5).collect_robj())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("fixed_size_collect", len),
+            &len,
+            |b, &len| b.iter(|| fixed_size_collect((0..len).map(|i| i as f64 * 0.5), len)),
+        );
+        group.bench_with_input(BenchmarkId::new("Robj to Vec<f64>", len), &robj, |b, x| {
+            b.iter(|| Vec::<f64>::try_from(black_box(x)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("Robj to &[f64]", len), &robj, |b, x| {
+            b.iter(|| <&[f64]>::try_from(black_box(x)).unwrap().len())
+        });
+    }
+    group.finish();
+}
+
+fn strings(c: &mut Criterion) {
+    extendr_engine::start_r();
+    let mut group = c.benchmark_group("strings");
+    for len in LENS {
+        let distinct: Vec<String> = (0..len).map(|i| format!("s{}", i)).collect();
+        let repeated: Vec<&str> = (0..len).map(|i| ["low", "mid", "high"][i % 3]).collect();
+        let robj = r!(distinct.clone());
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec<String> to Robj", len),
+            &distinct,
+            |b, x| b.iter(|| Robj::from(black_box(x))),
+        );
+        group.bench_with_input(BenchmarkId::new("from_values", len), &repeated, |b, x| {
+            b.iter(|| Strings::from_values(black_box(x)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("from_repeated_values", len),
+            &repeated,
+            |b, x| b.iter(|| Strings::from_repeated_values(black_box(x))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("str_to_character", len),
+            &distinct,
+            |b, x| {
+                b.iter(|| {
+                    for s in x {
+                        black_box(str_to_character(s));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Robj to Vec<String>", len),
+            &robj,
+            |b, x| b.iter(|| Vec::<String>::try_from(black_box(x)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn lists(c: &mut Criterion) {
+    extendr_engine::start_r();
+    let mut group = c.benchmark_group("lists");
+    for len in LENS {
+        let values: Vec<Robj> = (0..len).map(|i| r!(i as i32)).collect();
+        let names: Vec<String> = (0..len).map(|i| format!("n{}", i)).collect();
+        let list = List::from_values(&values);
+
+        group.bench_with_input(BenchmarkId::new("from_values", len), &values, |b, x| {
+            b.iter(|| List::from_values(black_box(x)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("from_names_and_values", len),
+            &(&names, &values),
+            |b, (names, values)| {
+                b.iter(|| {
+                    List::from_names_and_values(black_box(*names), black_box(*values)).unwrap()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("list_from_entries", len), &list, |b, x| {
+            b.iter(|| {
+                list_from_entries(vec![
+                    ListEntry::Value("first", r!(0)),
+                    ListEntry::Splice(black_box(x).clone().into()),
+                ])
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("iter", len), &list, |b, x| {
+            b.iter(|| black_box(x).values().count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, vectors, strings, lists);
+criterion_main!(benches);
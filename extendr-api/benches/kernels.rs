@@ -0,0 +1,66 @@
+//! Compare the numeric kernels of `Robj` to the R functions.
+//!
+//! Run with `cargo bench --bench kernels`.
+use extendr_api::prelude::*;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 1_000_000;
+const ITERATIONS: u32 = 50;
+
+fn time<F: FnMut() -> Robj>(mut f: F) -> Duration {
+    // Warm up, e.g. to allocate the result vectors once.
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn report(name: &str, rust: Duration, r: Duration) {
+    println!(
+        "{:<12} rust {:>10.1?}   R {:>10.1?}   speedup {:>5.1}x",
+        name,
+        rust,
+        r,
+        r.as_secs_f64() / rust.as_secs_f64()
+    );
+}
+
+fn main() {
+    test! {
+        let doubles = eval_string(&format!("runif({})", LEN))?;
+        let integers = eval_string(&format!("sample.int(1000L, {}, replace = TRUE)", LEN))?;
+        let other = eval_string(&format!("runif({})", LEN))?;
+
+        let reductions: [(&str, &Robj, fn(&Robj) -> Robj, &str); 6] = [
+            ("sum dbl", &doubles, |x| x.sum(false).unwrap(), "sum"),
+            ("sum int", &integers, |x| x.sum(false).unwrap(), "sum"),
+            ("mean dbl", &doubles, |x| x.mean(false).unwrap(), "mean"),
+            ("mean int", &integers, |x| x.mean(false).unwrap(), "mean"),
+            ("min dbl", &doubles, |x| x.min(false).unwrap(), "min"),
+            ("max int", &integers, |x| x.max(true).unwrap(), "max"),
+        ];
+        for (name, x, rust, r_function) in reductions {
+            let r_function = eval_string(r_function)?;
+            report(
+                name,
+                time(|| rust(x)),
+                time(|| r_function.call(pairlist!(x)).unwrap()),
+            );
+        }
+
+        let plus = eval_string("`+`")?;
+        let times = eval_string("`*`")?;
+        report(
+            "dbl + dbl",
+            time(|| &doubles + &other),
+            time(|| plus.call(pairlist!(&doubles, &other)).unwrap()),
+        );
+        report(
+            "dbl * 2",
+            time(|| &doubles * 2.),
+            time(|| times.call(pairlist!(&doubles, 2.)).unwrap()),
+        );
+    }
+}
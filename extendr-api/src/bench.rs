@@ -0,0 +1,194 @@
+//! Micro-benchmarks of the argument conversion path of exported functions.
+//!
+//! With the `conversion-bench` feature, `#[extendr]` generates, for each exported
+//! function, a companion that only converts its arguments from [`Robj`], and
+//! `extendr_module!` generates `<mod>_conversion_benches()` listing them
+//! (including those of `use`d modules).
+//!
+//! Run them on representative inputs, save the report with [`ConversionReport::to_csv`]
+//! and compare it to a later run, e.g. after upgrading extendr:
+//!
+//! ```ignore
+//! use extendr_api::prelude::*;
+//! use extendr_api::bench::ConversionReport;
+//!
+//! test! {
+//!     let inputs = vec![("my_func", vec![r!([1.0, 2.0]), r!("abc")])];
+//!     let report = ConversionReport::run(&mymodule_conversion_benches(), &inputs, 10_000)?;
+//!     let baseline = std::fs::read_to_string("conversions.csv").unwrap();
+//!     for regression in report.compare(&baseline, 1.2) {
+//!         println!("{}", regression);
+//!     }
+//! }
+//! ```
+use crate::*;
+use std::time::{Duration, Instant};
+
+/// Signature of the generated argument conversion functions.
+pub type ConversionFn = fn(&[Robj]) -> Result<()>;
+
+/// The argument conversion path of one exported function.
+#[derive(Clone, Copy)]
+pub struct ConversionBench {
+    pub name: &'static str,
+    pub convert: ConversionFn,
+}
+
+impl ConversionBench {
+    /// Convert `args` `iterations` times and measure the elapsed time.
+    pub fn run(&self, args: &[Robj], iterations: usize) -> Result<ConversionTiming> {
+        // Fail early if the arguments do not convert.
+        (self.convert)(args)?;
+        let start = Instant::now();
+        for _ in 0..iterations {
+            (self.convert)(args)?;
+        }
+        Ok(ConversionTiming {
+            name: self.name.to_string(),
+            iterations,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+/// The time taken to convert the arguments of one function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionTiming {
+    pub name: String,
+    pub iterations: usize,
+    pub elapsed: Duration,
+}
+
+impl ConversionTiming {
+    /// Average time of one conversion in nanoseconds.
+    pub fn nanos_per_call(&self) -> f64 {
+        self.elapsed.as_nanos() as f64 / self.iterations.max(1) as f64
+    }
+}
+
+/// A function whose conversions became slower than in a baseline report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionRegression {
+    pub name: String,
+    pub baseline_nanos: f64,
+    pub current_nanos: f64,
+}
+
+impl ConversionRegression {
+    /// How many times slower than the baseline.
+    pub fn ratio(&self) -> f64 {
+        self.current_nanos / self.baseline_nanos
+    }
+}
+
+impl std::fmt::Display for ConversionRegression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {:.1}ns -> {:.1}ns ({:.2}x)",
+            self.name,
+            self.baseline_nanos,
+            self.current_nanos,
+            self.ratio()
+        )
+    }
+}
+
+/// Timings of a set of conversion benchmarks.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConversionReport {
+    pub timings: Vec<ConversionTiming>,
+}
+
+impl ConversionReport {
+    /// Benchmark each function that has an entry in `inputs`.
+    pub fn run(
+        benches: &[ConversionBench],
+        inputs: &[(&str, Vec<Robj>)],
+        iterations: usize,
+    ) -> Result<Self> {
+        let mut timings = Vec::new();
+        for bench in benches {
+            if let Some((_, args)) = inputs.iter().find(|(name, _)| *name == bench.name) {
+                timings.push(bench.run(args, iterations)?);
+            }
+        }
+        Ok(Self { timings })
+    }
+
+    /// Write the report as `name,iterations,nanos_per_call` lines.
+    pub fn to_csv(&self) -> String {
+        let mut res = String::from("name,iterations,nanos_per_call\n");
+        for t in &self.timings {
+            res.push_str(&format!(
+                "{},{},{:.3}\n",
+                t.name,
+                t.iterations,
+                t.nanos_per_call()
+            ));
+        }
+        res
+    }
+
+    /// Compare with a baseline written by [`ConversionReport::to_csv`].
+    ///
+    /// Returns the functions that are more than `threshold` times slower,
+    /// e.g. `1.2` for 20%. Functions missing from the baseline are ignored.
+    pub fn compare(&self, baseline_csv: &str, threshold: f64) -> Vec<ConversionRegression> {
+        let baseline: HashMap<&str, f64> = baseline_csv
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let mut fields = line.split(',');
+                let name = fields.next()?;
+                let nanos = fields.nth(1)?.trim().parse().ok()?;
+                Some((name, nanos))
+            })
+            .collect();
+
+        self.timings
+            .iter()
+            .filter_map(|t| {
+                let baseline_nanos = *baseline.get(t.name.as_str())?;
+                let current_nanos = t.nanos_per_call();
+                if current_nanos > baseline_nanos * threshold {
+                    Some(ConversionRegression {
+                        name: t.name.clone(),
+                        baseline_nanos,
+                        current_nanos,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(name: &str, nanos: u64) -> ConversionTiming {
+        ConversionTiming {
+            name: name.to_string(),
+            iterations: 10,
+            elapsed: Duration::from_nanos(nanos * 10),
+        }
+    }
+
+    #[test]
+    fn compare_with_baseline() {
+        let baseline = ConversionReport {
+            timings: vec![timing("a", 100), timing("b", 100)],
+        }
+        .to_csv();
+        let current = ConversionReport {
+            timings: vec![timing("a", 110), timing("b", 300), timing("c", 1000)],
+        };
+        let regressions = current.compare(&baseline, 1.2);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].name, "b");
+        assert_eq!(regressions[0].ratio(), 3.0);
+    }
+}
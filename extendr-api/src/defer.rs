@@ -0,0 +1,106 @@
+//! Cleanup actions that run when a scope is left, like `on.exit()` in R.
+use crate::*;
+
+enum Action {
+    Eval(Robj, Environment),
+    Call(Box<dyn FnOnce()>),
+}
+
+/// A guard that runs R expressions and Rust closures when it is dropped.
+///
+/// As with `on.exit(add = TRUE)`, actions run in the order they were
+/// added, and they run however the scope is left, including early returns
+/// with `?` and panics. Errors in R expressions and panics in closures
+/// are ignored so that the remaining actions still run.
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     fn with_temp_var() -> Result<Robj> {
+///         let mut defer = DeferR::new();
+///         defer.add_code("rm(extendr_tmp, envir = globalenv())")?;
+///         global_env().set_local(sym!(extendr_tmp), 1);
+///         R!("extendr_tmp + 1")
+///     }
+///     assert_eq!(with_temp_var()?, r!(2.));
+///     assert_eq!(R!("exists('extendr_tmp')")?, r!(false));
+/// }
+/// ```
+#[must_use = "the actions run when the guard is dropped"]
+#[derive(Default)]
+pub struct DeferR {
+    actions: Vec<Action>,
+}
+
+impl DeferR {
+    /// Make a guard with no actions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluate `expr` in `env` on drop.
+    pub fn add_expr<E: Into<Robj>>(&mut self, expr: E, env: &Environment) -> &mut Self {
+        self.actions.push(Action::Eval(expr.into(), env.clone()));
+        self
+    }
+
+    /// Evaluate the R code `code` in the global environment on drop.
+    ///
+    /// The code is parsed immediately, so syntax errors are returned here.
+    pub fn add_code(&mut self, code: &str) -> Result<&mut Self> {
+        let exprs = parse(code)?;
+        Ok(self.add_expr(exprs, &global_env()))
+    }
+
+    /// Call `f` on drop.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::{cell::RefCell, rc::Rc};
+    /// test! {
+    ///     let log = Rc::new(RefCell::new(Vec::new()));
+    ///     {
+    ///         let mut defer = DeferR::new();
+    ///         let first = log.clone();
+    ///         defer.add(move || first.borrow_mut().push("first"));
+    ///         let second = log.clone();
+    ///         defer.add(move || second.borrow_mut().push("second"));
+    ///     }
+    ///     assert_eq!(*log.borrow(), vec!["first", "second"]);
+    /// }
+    /// ```
+    pub fn add<F: FnOnce() + 'static>(&mut self, f: F) -> &mut Self {
+        self.actions.push(Action::Call(Box::new(f)));
+        self
+    }
+
+    /// Remove all actions without running them.
+    pub fn cancel(&mut self) {
+        self.actions.clear();
+    }
+}
+
+impl Drop for DeferR {
+    fn drop(&mut self) {
+        for action in self.actions.drain(..) {
+            match action {
+                Action::Eval(expr, env) => {
+                    let _ = match expr.as_expressions() {
+                        Some(exprs) => exprs.eval_all_with_env(&env),
+                        None => expr.eval_with_env(&env),
+                    };
+                }
+                Action::Call(f) => {
+                    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for DeferR {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferR")
+            .field("actions", &self.actions.len())
+            .finish()
+    }
+}
@@ -1,5 +1,6 @@
 //! Error handling in Rust called from R.
 
+use std::cell::Cell;
 use std::convert::Infallible;
 
 use crate::conversions::try_into_int::ConversionError;
@@ -17,6 +18,22 @@ pub fn unwrap_or_throw<T>(r: std::result::Result<T, &'static str>) -> T {
     }
 }
 
+thread_local! {
+    // Type name of the last `Err` turned into a panic by `From<Result<T, E>> for Robj`.
+    static PANIC_ERROR_TYPE: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+pub(crate) fn set_panic_error_type(type_name: &'static str) {
+    PANIC_ERROR_TYPE.with(|t| t.set(Some(type_name)));
+}
+
+/// Take the type name of the `Err` value that caused the last panic, if any.
+/// Used by the `#[extendr]` wrappers.
+#[doc(hidden)]
+pub fn take_panic_error_type() -> Option<&'static str> {
+    PANIC_ERROR_TYPE.with(|t| t.take())
+}
+
 #[doc(hidden)]
 pub fn unwrap_or_throw_error<T>(r: std::result::Result<T, Error>) -> T {
     match r {
@@ -33,6 +50,7 @@ pub enum Error {
     NotFound(Robj),
     EvalError(Robj),
     ParseError(Robj),
+    ParseErrorMessage(Robj, String),
     NamesLengthMismatch(Robj),
 
     ExpectedNull(Robj),
@@ -83,6 +101,8 @@ pub enum Error {
     ExpectedExternalNonNullPtr(Robj),
     ExpectedExternalPtrReference,
     Other(String),
+    /// The user interrupted R, see [`check_user_interrupt`](crate::check_user_interrupt).
+    Interrupted,
 
     #[cfg(feature = "ndarray")]
     NDArrayShapeError(ndarray::ShapeError),
@@ -100,6 +120,9 @@ impl std::fmt::Display for Error {
             Error::NotFound(robj) => write!(f, "Not found. {:?}", robj),
             Error::EvalError(robj) => write!(f, "Evaluation error in {:?}.", robj),
             Error::ParseError(code) => write!(f, "Parse error in {:?}.", code),
+            Error::ParseErrorMessage(code, message) => {
+                write!(f, "Parse error in {:?}: {}", code, message)
+            }
             Error::NamesLengthMismatch(robj) => {
                 write!(f, "Length of names does not match vector. {:?}", robj)
             }
@@ -175,6 +198,7 @@ impl std::fmt::Display for Error {
             // this is very unlikely to occur, and it would just say: Rust error: could not convert slice to array
             Error::TryFromSliceError(std_error) => write!(f, "Rust error: {}", std_error),
             Error::Other(str) => write!(f, "{}", str),
+            Error::Interrupted => write!(f, "Interrupted by the user."),
 
             Error::ExpectedWholeNumber(robj, conversion_error) => {
                 write!(
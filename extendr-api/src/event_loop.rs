@@ -0,0 +1,129 @@
+//! Run Rust closures on the R main thread.
+//!
+//! The R API may only be used from the thread that runs R. Worker threads
+//! can hand their results back with [`run_on_main_thread`], which queues
+//! a closure to be called on the main thread when R is idle.
+//!
+//! The queue is processed:
+//! - by the event loop of the [`later`](https://cran.r-project.org/package=later)
+//!   package, if it is installed, so closures run as soon as R is idle,
+//!   even while the user is not typing anything,
+//! - after each top-level R expression, using a task callback,
+//! - whenever [`run_pending`] is called, for example in a loop that waits
+//!   for worker threads.
+//!
+//! [`init`] must be called on the main thread before closures are queued,
+//! for example from a package's `.onLoad()` hook.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use std::sync::mpsc;
+//! test! {
+//!     extendr_api::event_loop::init()?;
+//!     let (tx, rx) = mpsc::channel();
+//!     std::thread::spawn(move || {
+//!         let answer = 6 * 7;
+//!         run_on_main_thread(move || {
+//!             // The R API can be used here.
+//!             global_env().set_local(sym!(answer), answer);
+//!             tx.send(()).unwrap();
+//!         });
+//!     })
+//!     .join()
+//!     .unwrap();
+//!     extendr_api::event_loop::run_pending();
+//!     rx.recv().unwrap();
+//!     assert_eq!(global_env().local(sym!(answer))?, r!(42));
+//! }
+//! ```
+use crate::task_callback::add_task_callback;
+use crate::*;
+use once_cell::sync::OnceCell;
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::sync::Mutex;
+use std::thread::ThreadId;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// `execLaterNative2()` from the C API of `later`, which can be called from any thread.
+type ExecLater =
+    unsafe extern "C" fn(Option<unsafe extern "C" fn(*mut c_void)>, *mut c_void, f64, c_int);
+
+// The global loop of `later`.
+const LATER_GLOBAL_LOOP: c_int = 0;
+
+const TASK_CALLBACK_NAME: &str = "extendr_event_loop";
+
+static QUEUE: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+static MAIN_THREAD: OnceCell<ThreadId> = OnceCell::new();
+static EXEC_LATER: OnceCell<Option<ExecLater>> = OnceCell::new();
+
+/// Prepare the main thread to run queued closures.
+///
+/// This records the calling thread as the R main thread, connects to the
+/// `later` event loop if the package is installed, and registers a task
+/// callback. Calling it again does nothing.
+pub fn init() -> Result<()> {
+    if MAIN_THREAD.get().is_some() {
+        return Ok(());
+    }
+    let _ = MAIN_THREAD.set(std::thread::current().id());
+    let _ = EXEC_LATER.set(find_exec_later());
+    add_task_callback(TASK_CALLBACK_NAME, |_| {
+        run_pending();
+        true
+    })?;
+    Ok(())
+}
+
+/// Queue `f` to be called on the R main thread.
+///
+/// This can be called from any thread. `f` is called after the current
+/// R code has finished, even if called from the main thread.
+/// Panics in `f` are caught and ignored.
+pub fn run_on_main_thread<F>(f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    QUEUE.lock().unwrap().push(Box::new(f));
+    if let Some(&Some(exec_later)) = EXEC_LATER.get() {
+        unsafe extern "C" fn drain(_: *mut c_void) {
+            run_pending();
+        }
+        unsafe { exec_later(Some(drain), std::ptr::null_mut(), 0.0, LATER_GLOBAL_LOOP) };
+    }
+}
+
+/// Call the queued closures and return how many were called.
+///
+/// This does nothing before [`init`] is called, or on threads other than
+/// the R main thread.
+pub fn run_pending() -> usize {
+    if MAIN_THREAD.get() != Some(&std::thread::current().id()) {
+        return 0;
+    }
+    let mut count = 0;
+    // Closures may queue more closures, so do not hold the lock while calling them.
+    loop {
+        let tasks = std::mem::take(&mut *QUEUE.lock().unwrap());
+        if tasks.is_empty() {
+            return count;
+        }
+        for task in tasks {
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(task));
+            count += 1;
+        }
+    }
+}
+
+fn find_exec_later() -> Option<ExecLater> {
+    let installed = eval_string("requireNamespace('later', quietly = TRUE)").ok()?;
+    if installed.as_bool() != Some(true) {
+        return None;
+    }
+    let package = CString::new("later").unwrap();
+    let name = CString::new("execLaterNative2").unwrap();
+    let fun = single_threaded(|| unsafe { R_GetCCallable(package.as_ptr(), name.as_ptr()) })?;
+    Some(unsafe { std::mem::transmute::<unsafe extern "C" fn() -> *mut c_void, ExecLater>(fun) })
+}
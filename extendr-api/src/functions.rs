@@ -194,11 +194,16 @@ pub fn blank_scalar_string() -> Robj {
 }
 
 /// Parse a string into an R executable object
+///
+/// On a syntax error, the error includes R's parse error message.
 /// ```
 /// use extendr_api::prelude::*;
 /// test! {
 ///    let expr = parse("1 + 2").unwrap();
 ///    assert!(expr.is_expressions());
+///
+///    let err = parse("1 +* 2").unwrap_err();
+///    assert!(err.to_string().contains("unexpected '*'"));
 /// }
 /// ```
 pub fn parse(code: &str) -> Result<Expressions> {
@@ -210,11 +215,23 @@ pub fn parse(code: &str) -> Result<Expressions> {
         let parsed = Robj::from_sexp(R_ParseVector(codeobj.get(), -1, status_ptr, R_NilValue));
         match status {
             ParseStatus::PARSE_OK => parsed.try_into(),
-            _ => Err(Error::ParseError(code.into())),
+            _ => Err(parse_error(codeobj)),
         }
     })
 }
 
+// R_ParseVector does not report the message, so parse again with base::parse().
+fn parse_error(code: Robj) -> Error {
+    let message = eval_string_with_params(
+        "tryCatch({ parse(text = param.0); '' }, error = conditionMessage)",
+        &[&code],
+    );
+    match message.as_ref().ok().and_then(|m| m.as_str()) {
+        Some(message) if !message.is_empty() => Error::ParseErrorMessage(code, message.to_string()),
+        _ => Error::ParseError(code),
+    }
+}
+
 /// Parse a string into an R executable object and run it.
 /// Used by the R! macro.
 /// ```
@@ -224,19 +241,76 @@ pub fn parse(code: &str) -> Result<Expressions> {
 ///    assert_eq!(res, r!(3.));
 /// }
 /// ```
+///
+/// See [`eval_string_with_env`] to evaluate in another environment.
 pub fn eval_string(code: &str) -> Result<Robj> {
+    eval_string_with_env(code, &global_env())
+}
+
+/// Parse a string into an R executable object and run it in `env`,
+/// returning the value of the last expression.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///    let env = Environment::new_with_parent(base_env());
+///    env.set_local(sym!(x), 2.);
+///    assert_eq!(eval_string_with_env("y <- x * 3; y + 1", &env)?, r!(7.));
+///    assert_eq!(env.local(sym!(y))?, r!(6.));
+/// }
+/// ```
+pub fn eval_string_with_env(code: &str, env: &Environment) -> Result<Robj> {
     single_threaded(|| {
         let expr = parse(code)?;
         let mut res = Robj::from(());
         if let Some(expr) = expr.as_expressions() {
             for lang in expr.values() {
-                res = lang.eval()?
+                res = lang.eval_with_env(env)?
             }
         }
         Ok(res)
     })
 }
 
+/// A new environment for evaluating user-supplied code, whose search path is
+/// the exports of `packages`, in that order, followed by the base functions.
+///
+/// The base functions are copied into an environment enclosed by the empty
+/// environment, rather than used from the base environment, whose enclosure is
+/// the global environment. So the global environment and attached packages
+/// that are not listed, such as `utils`, are not searched, and assignments go
+/// to the new environment.
+///
+/// This makes mistakes less likely, but is not a security boundary: base
+/// functions such as `system`, `get` or `::` are still available.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///    let env = sandbox_env(&["stats"])?;
+///    assert_eq!(eval_string_with_env("median(c(3, 1, 2))", &env)?, r!(2.));
+///    assert!(eval_string_with_env("head(1:10)", &env).is_err());
+///
+///    // Assignments stay in the sandbox, and the global environment is not searched.
+///    eval_string_with_env("z <- 1", &env)?;
+///    assert_eq!(env.local(sym!(z))?, r!(1.));
+///    assert_eq!(eval_string("exists('z')")?, r!(false));
+///    global_env().set_local(sym!(w), 1.);
+///    assert!(eval_string_with_env("w", &env).is_err());
+/// }
+/// ```
+pub fn sandbox_env(packages: &[&str]) -> Result<Environment> {
+    let mut parent: Environment =
+        eval_string("list2env(as.list(baseenv(), all.names = TRUE), parent = emptyenv())")?
+            .try_into()?;
+    for package in packages.iter().rev() {
+        let exports = eval_string_with_params(
+            "list2env(mget(getNamespaceExports(param.0), envir = asNamespace(param.0), inherits = TRUE), parent = param.1)",
+            &[&r!(*package), &r!(parent)],
+        )?;
+        parent = exports.try_into()?;
+    }
+    Ok(Environment::new_with_parent(parent))
+}
+
 /// Parse a string into an R executable object and run it using
 ///   parameters param.0, param.1, ...
 ///
@@ -268,6 +342,237 @@ pub fn eval_string_with_params(code: &str, values: &[&Robj]) -> Result<Robj> {
     })
 }
 
+/// Evaluate `body` in the global environment, calling `handler` with the
+/// condition object if a condition of one of `classes` is signalled.
+///
+/// This is the equivalent of `tryCatch(body, <class> = handler)` in R.
+/// As in R, evaluation of `body` stops when a condition is caught,
+/// and the result of `handler` is returned.
+///
+/// See [`try_catch_with_env`] to evaluate in another environment.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let body = lang!("stop", "oops");
+///     let message = try_catch(&body, &["error"], |cnd| cnd.dollar("message"))?;
+///     assert_eq!(message, r!("oops"));
+///
+///     let body = lang!("warning", "careful");
+///     let class = try_catch(&body, &["error", "warning"], |cnd| Ok(r!(cnd.class().unwrap().next())))?;
+///     assert_eq!(class, r!("simpleWarning"));
+///
+///     assert_eq!(try_catch(&lang!("+", 1, 2), &["error"], |_| Ok(r!(0)))?, r!(3));
+///     assert!(try_catch(&lang!("stop", "oops"), &["warning"], |_| Ok(r!(0))).is_err());
+/// }
+/// ```
+pub fn try_catch<F>(body: &Robj, classes: &[&str], handler: F) -> Result<Robj>
+where
+    F: FnOnce(Robj) -> Result<Robj>,
+{
+    try_catch_with_env(body, &global_env(), classes, handler)
+}
+
+/// Evaluate `body` in `env`, calling `handler` with the condition object
+/// if a condition of one of `classes` is signalled.
+///
+/// See [`try_catch`].
+pub fn try_catch_with_env<F>(
+    body: &Robj,
+    env: &Environment,
+    classes: &[&str],
+    handler: F,
+) -> Result<Robj>
+where
+    F: FnOnce(Robj) -> Result<Robj>,
+{
+    let classes = Robj::from(Strings::from_values(classes));
+    let result = eval_string_with_params(
+        "handler <- function(cnd) structure(list(cnd), class = 'extendr_caught')
+        handlers <- rep(list(handler), length(param.2))
+        names(handlers) <- param.2
+        do.call(tryCatch, c(list(quote(eval(param.0, param.1))), handlers))",
+        &[body, env.as_robj(), &classes],
+    )?;
+    if result.inherits("extendr_caught") {
+        handler(List::try_from(result)?.elt(0)?)
+    } else {
+        Ok(result)
+    }
+}
+
+/// Print an R object with R's `print()` generic, so that the print method
+/// of its class is used, e.g. for data frames, factors and S4 objects.
+///
+/// This is what [`rprint!`] uses for arguments other than a format string.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let (_, stdout, _) = with_output_captured(|| {
+///         print_robj(&factor!(["a", "b", "a"]))?;
+///         Ok(r!(()))
+///     })?;
+///     assert_eq!(stdout, "[1] a b a\nLevels: a b\n");
+/// }
+/// ```
+pub fn print_robj(robj: &Robj) -> Result<()> {
+    // The object is passed as a value, so a symbol or a call is not evaluated.
+    eval_string_with_params("invisible(print(param.0))", &[robj])?;
+    Ok(())
+}
+
+/// Convert `parts` to strings and concatenate them element-wise,
+/// like R's `paste0()`.
+///
+/// As in R, shorter vectors are recycled, and `NA` becomes `"NA"`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let labels = paste0(&[r!("x"), r!([1, 2, 3])])?;
+///     assert_eq!(labels.iter().collect::<Vec<_>>(), vec!["x1", "x2", "x3"]);
+/// }
+/// ```
+pub fn paste0(parts: &[Robj]) -> Result<Strings> {
+    paste(parts, "")
+}
+
+/// Convert `parts` to strings and concatenate them element-wise,
+/// separated by `sep`, like R's `paste(..., sep = sep)`.
+///
+/// Numbers are converted as by `as.character()`, with up to 15 significant digits.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let labels = paste(&[r!(["a", "b"]), r!([0.5, 1e-20])], " = ")?;
+///     assert_eq!(labels.iter().collect::<Vec<_>>(), vec!["a = 0.5", "b = 1e-20"]);
+///
+///     // To join the strings, use `join` on the result.
+///     let joined = labels.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ");
+///     assert_eq!(joined, "a = 0.5, b = 1e-20");
+/// }
+/// ```
+pub fn paste(parts: &[Robj], sep: &str) -> Result<Strings> {
+    let parts = List::from_values(parts);
+    let result = eval_string_with_params(
+        "do.call(paste, c(param.0, list(sep = param.1)), quote = TRUE)",
+        &[parts.as_robj(), &r!(sep)],
+    )?;
+    Strings::try_from(result)
+}
+
+/// Format numbers as R prints them, like R's `format(x, digits = digits)`.
+///
+/// This follows the rules of R: the numbers are shown with a common number
+/// of decimals and padded to a common width, and scientific notation is used
+/// when it is shorter, as adjusted by the `scipen` option. Without `digits`,
+/// the `digits` option is used, which is 7 by default.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let text = format_numbers(&r!([1., 10.5, 100.]), None)?;
+///     assert_eq!(text.iter().collect::<Vec<_>>(), vec!["  1.0", " 10.5", "100.0"]);
+///
+///     let text = format_numbers(&r!(std::f64::consts::PI), Some(3))?;
+///     assert_eq!(text.elt(0), "3.14");
+///
+///     assert_eq!(format_numbers(&r!(1e-10), None)?.elt(0), "1e-10");
+///     assert_eq!(format_numbers(&r!(123456789.), None)?.elt(0), "123456789");
+/// }
+/// ```
+pub fn format_numbers(x: &Robj, digits: Option<i32>) -> Result<Strings> {
+    if !x.is_number() {
+        return Err(Error::ExpectedNumeric(x.clone()));
+    }
+    let digits = digits.map_or_else(|| r!(()), |digits| r!(digits));
+    let result = eval_string_with_params("format(param.0, digits = param.1)", &[x, &digits])?;
+    Strings::try_from(result)
+}
+
+/// Call `f` and capture what it prints to the R console.
+///
+/// Returns the result of `f`, and the text written to `stdout` (e.g. by
+/// `print()` and [`rprintln!`]) and to `stderr` (e.g. by `message()`
+/// and [`reprintln!`]). Each captured line ends with a newline.
+///
+/// This uses `sink()`, so output written directly to the process's
+/// standard streams, e.g. by `println!`, is not captured.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let (value, stdout, stderr) = with_output_captured(|| {
+///         call!("print", "hello")?;
+///         call!("message", "careful")?;
+///         Ok(r!(1))
+///     })?;
+///     assert_eq!(value, r!(1));
+///     assert_eq!(stdout, "[1] \"hello\"\n");
+///     assert_eq!(stderr, "careful\n");
+/// }
+/// ```
+pub fn with_output_captured<F>(f: F) -> Result<(Robj, String, String)>
+where
+    F: FnOnce() -> Result<Robj>,
+{
+    // Removes the sinks even if `f` fails or panics.
+    struct Capture(Environment);
+
+    impl Drop for Capture {
+        fn drop(&mut self) {
+            let _ = eval_in(
+                "sink(type = 'message'); sink(); close(stdout_con); close(stderr_con)",
+                &self.0,
+            );
+        }
+    }
+
+    fn eval_in(code: &str, env: &Environment) -> Result<Robj> {
+        parse(code)?.eval_all_with_env(env)
+    }
+
+    fn lines(env: &Environment, name: &str) -> Result<String> {
+        let lines = Strings::try_from(env.local(Symbol::from_string(name))?)?;
+        Ok(lines
+            .iter()
+            .map(|line| format!("{}\n", line.as_str()))
+            .collect())
+    }
+
+    let env = Environment::new_with_parent(base_env());
+    eval_in(
+        "stdout_con <- textConnection('stdout_lines', 'w', local = TRUE)
+        stderr_con <- textConnection('stderr_lines', 'w', local = TRUE)
+        sink(stdout_con)
+        sink(stderr_con, type = 'message')",
+        &env,
+    )?;
+    let capture = Capture(env);
+    let value = f();
+    let env = capture.0.clone();
+    drop(capture);
+    Ok((
+        value?,
+        lines(&env, "stdout_lines")?,
+        lines(&env, "stderr_lines")?,
+    ))
+}
+
+/// Check if the user has asked to interrupt the computation,
+/// for example by pressing Ctrl-C.
+///
+/// Unlike `R_CheckUserInterrupt()`, this does not jump out of the Rust code
+/// but returns [`Error::Interrupted`], so that long-running loops can stop
+/// with `?` and clean up as usual.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     for _ in 0..1000 {
+///         check_user_interrupt()?;
+///     }
+/// }
+/// ```
+pub fn check_user_interrupt() -> Result<()> {
+    toplevel_exec(|| unsafe { R_CheckUserInterrupt() }).map_err(|_| Error::Interrupted)
+}
+
 /// Find a function or primitive that may be in a namespace.
 /// ```
 /// use extendr_api::prelude::*;
@@ -295,3 +600,186 @@ pub fn find_namespaced_function(name: &str) -> Result<Language> {
         _ => Err(Error::NotFound(r!(name))),
     }
 }
+
+/// The integers `1, 2, ..., n`, like R's `seq_len(n)`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///    assert_eq!(seq_len(3), r!([1, 2, 3]));
+///    assert_eq!(seq_len(0).len(), 0);
+/// }
+/// ```
+pub fn seq_len(n: usize) -> Robj {
+    match i32::try_from(n) {
+        Ok(n) => (1..=n).collect_robj(),
+        // As in R, long sequences are doubles.
+        Err(_) => (1..=n).map(|i| i as f64).collect_robj(),
+    }
+}
+
+/// The doubles from `from` to at most `to` in steps of `by`,
+/// like R's `seq(from, to, by = by)`.
+///
+/// As in R, `by` must have the sign of `to - from`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///    assert_eq!(seq(1., 10., 3.)?, r!([1., 4., 7., 10.]));
+///    assert_eq!(seq(0., 1., 0.25)?, r!([0., 0.25, 0.5, 0.75, 1.]));
+///    assert_eq!(seq(5., 1., -2.)?, r!([5., 3., 1.]));
+///    assert!(seq(1., 5., -1.).is_err());
+/// }
+/// ```
+pub fn seq(from: f64, to: f64, by: f64) -> Result<Robj> {
+    if !from.is_finite() || !to.is_finite() || !by.is_finite() {
+        return Err(Error::Other(
+            "'from', 'to' and 'by' must be finite numbers".into(),
+        ));
+    }
+    let delta = to - from;
+    if delta == 0. {
+        return Ok(r!(from));
+    }
+    let steps = delta / by;
+    if !steps.is_finite() {
+        return Err(Error::Other("invalid '(to - from)/by' in seq(.)".into()));
+    }
+    if steps < 0. {
+        return Err(Error::Other("wrong sign in 'by' argument".into()));
+    }
+    // The same tolerance as R, so that rounding errors do not drop the last value.
+    let n = (steps + 1e-10).floor() as usize;
+    Ok((0..=n).map(|i| from + i as f64 * by).collect_robj())
+}
+
+/// Repeat the elements of a vector or list `times` times, like R's `rep(x, times)`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///    assert_eq!(rep(r!([1, 2]), 3)?, r!([1, 2, 1, 2, 1, 2]));
+///    assert_eq!(rep("a", 2)?, r!(["a", "a"]));
+///    assert_eq!(rep(list!(1, "b"), 2)?, r!(list!(1, "b", 1, "b")));
+/// }
+/// ```
+pub fn rep<T: Into<Robj>>(x: T, times: usize) -> Result<Robj> {
+    eval_string_with_params("rep(param.0, times = param.1)", &[&x.into(), &r!(times)])
+}
+
+// Evaluate `sys.calls()` or `sys.frames()` in the frame of the innermost R
+// function, as R finds the call stack from the environment they are called in.
+fn call_stack(code: &str) -> Result<Vec<Robj>> {
+    let stack = eval_string_with_env(code, &current_env())?;
+    Ok(match stack.as_pairlist() {
+        Some(stack) => stack.values().collect(),
+        None => Vec::new(),
+    })
+}
+
+/// The calls of the active R functions, outermost first, like R's `sys.calls()`.
+/// This is empty when Rust is not called from an R function.
+///
+/// Together with [`Rinternals::deparse()`], this gives a traceback for errors:
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let traceback = sys_calls()?
+///         .iter()
+///         .enumerate()
+///         .map(|(i, call)| Ok(format!("{}. {}", i + 1, call.deparse()?)))
+///         .collect::<Result<Vec<String>>>()?;
+///     assert!(traceback.is_empty());
+/// }
+/// ```
+pub fn sys_calls() -> Result<Vec<Language>> {
+    call_stack("sys.calls()")?
+        .into_iter()
+        .map(Language::try_from)
+        .collect()
+}
+
+/// The environments of the active R functions, outermost first,
+/// like R's `sys.frames()`. Each matches the call of [`sys_calls()`]
+/// at the same position.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_eq!(sys_frames()?.len(), sys_calls()?.len());
+/// }
+/// ```
+pub fn sys_frames() -> Result<Vec<Environment>> {
+    call_stack("sys.frames()")?
+        .into_iter()
+        .map(Environment::try_from)
+        .collect()
+}
+
+/// Compare two strings in the collation order of the current locale,
+/// as R's `<` and `sort()` do, rather than byte by byte.
+///
+/// R uses ICU for this when it is available, and the `LC_COLLATE`
+/// locale otherwise.
+///
+/// Each comparison evaluates a call in R, which is much slower than comparing
+/// Rust strings. To sort many strings, use [`collation_order()`] or
+/// [`sort_collated()`], which call R once.
+/// ```
+/// use extendr_api::prelude::*;
+/// use std::cmp::Ordering;
+/// test! {
+///     let _locale = local_locale("LC_COLLATE", "C")?;
+///     assert_eq!(collate("B", "a")?, Ordering::Less);
+///     assert_eq!(collate("a", "a")?, Ordering::Equal);
+/// }
+/// ```
+pub fn collate(a: &str, b: &str) -> Result<std::cmp::Ordering> {
+    // `(a > b) - (a < b)`, built as a call so that no code is parsed.
+    let greater = lang!(">", a, b);
+    let less = lang!("<", a, b);
+    let sign = lang!("-", greater, less).eval_with_env(&base_env())?;
+    Ok(sign.as_integer().unwrap_or(0).cmp(&0))
+}
+
+/// The positions of `strings` in sorted order, counted from zero, as
+/// `order()` gives them in the current locale. Equal strings keep their order.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let _locale = local_locale("LC_COLLATE", "C")?;
+///     assert_eq!(collation_order(&["b", "B", "a"])?, vec![1, 2, 0]);
+/// }
+/// ```
+pub fn collation_order<S: AsRef<str>>(strings: &[S]) -> Result<Vec<usize>> {
+    let strings: Robj = strings.iter().map(|s| s.as_ref()).collect_robj();
+    let order = eval_string_with_params("order(param.0)", &[&strings])?;
+    Ok(order
+        .as_integer_slice()
+        .ok_or_else(|| Error::ExpectedInteger(order.clone()))?
+        .iter()
+        .map(|&i| i as usize - 1)
+        .collect())
+}
+
+/// Sort strings in the collation order of the current locale, so that they
+/// match R's `sort()` rather than Rust's byte-wise ordering.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let _locale = local_locale("LC_COLLATE", "C")?;
+///     let mut strings = vec!["b", "B", "a"];
+///     sort_collated(&mut strings)?;
+///     assert_eq!(strings, vec!["B", "a", "b"]);
+/// }
+/// ```
+pub fn sort_collated<S: AsRef<str>>(strings: &mut [S]) -> Result<()> {
+    let order = collation_order(strings)?;
+    // Apply the permutation in place: follow each source position through
+    // the swaps already made until it is at or after `i`.
+    for i in 0..order.len() {
+        let mut j = order[i];
+        while j < i {
+            j = order[j];
+        }
+        strings.swap(i, j);
+    }
+    Ok(())
+}
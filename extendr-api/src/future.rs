@@ -0,0 +1,126 @@
+//! Run Rust futures on the R event loop and return their results as R promises.
+//!
+//! This is what `#[extendr] async fn` uses: the exported function returns a
+//! promise object of the [`promises`](https://cran.r-project.org/package=promises)
+//! package straight away, and the future is polled on the R main thread
+//! whenever it is woken, see [`event_loop`](crate::event_loop).
+//! The promise is resolved with the output of the future, or rejected with
+//! an `extendr_error` condition if the future panics.
+//!
+//! ```no_run
+//! use extendr_api::prelude::*;
+//!
+//! #[extendr]
+//! async fn slow_add(a: i32, b: i32) -> i32 {
+//!     // e.g. wait for a reply from another thread or a network request
+//!     a + b
+//! }
+//! ```
+//!
+//! In R, the result is used like any other promise:
+//!
+//! ```r
+//! slow_add(1L, 2L) |> promises::then(print)
+//! ```
+//!
+//! Arguments of exported `async fn`s must be owned, as the future outlives
+//! the call from R. Futures are polled on the R main thread, so they can use
+//! the R API, but must not block it. Wakers may be called from any thread.
+use crate::*;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = Robj>>>,
+    // The `resolve` and `reject` functions of the promise.
+    resolve: Robj,
+    reject: Robj,
+}
+
+thread_local! {
+    static TASKS: RefCell<HashMap<usize, Task>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+struct TaskWaker {
+    id: usize,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        let id = self.id;
+        event_loop::run_on_main_thread(move || poll_task(id));
+    }
+}
+
+/// Make a `promises::promise()` that is settled with the output of `future`.
+///
+/// This must be called on the R main thread, and needs the `promises`
+/// package to be installed.
+pub fn spawn_promise<F>(future: F) -> Result<Robj>
+where
+    F: Future + 'static,
+    F::Output: Into<Robj>,
+{
+    event_loop::init()?;
+    let promise = eval_string(
+        "local({
+            settle <- new.env()
+            promise <- promises::promise(function(resolve, reject) {
+                settle$resolve <- resolve
+                settle$reject <- reject
+            })
+            list(promise, settle$resolve, settle$reject)
+        })",
+    )?;
+    let parts = List::try_from(promise)?;
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    let task = Task {
+        future: Box::pin(async move { future.await.into() }),
+        resolve: parts.elt(1)?,
+        reject: parts.elt(2)?,
+    };
+    TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+    // Poll for the first time once the caller has the promise.
+    event_loop::run_on_main_thread(move || poll_task(id));
+    parts.elt(0)
+}
+
+fn poll_task(id: usize) {
+    // The task is taken out while it is polled, as polling may spawn other tasks.
+    let Some(mut task) = TASKS.with(|tasks| tasks.borrow_mut().remove(&id)) else {
+        // The task has already completed.
+        return;
+    };
+    let waker = Waker::from(Arc::new(TaskWaker { id }));
+    let mut context = Context::from_waker(&waker);
+    let poll = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        task.future.as_mut().poll(&mut context)
+    }));
+    match poll {
+        Ok(Poll::Pending) => {
+            TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+        }
+        Ok(Poll::Ready(value)) => {
+            let _ = eval_string_with_params("param.0(param.1)", &[&task.resolve, &value]);
+        }
+        Err(_) => {
+            let message = match panic_hook::take_raw_report() {
+                Some(report) => format!("Future panicked\n{}", report),
+                None => "Future panicked".to_string(),
+            };
+            let rust_type = error::take_panic_error_type().unwrap_or("panic");
+            let condition =
+                error_condition(&message, rust_type, panic_hook::take_backtrace().as_deref());
+            let _ = eval_string_with_params("param.0(param.1)", &[&task.reject, &condition]);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Run R's garbage collector at every point where extendr allocates or converts.
+//!
+//! Objects that are not protected from the garbage collector, e.g. a raw
+//! `SEXP` kept while other R objects are created, usually only break when a
+//! collection happens to run at the wrong moment. With GC stress enabled,
+//! extendr runs a full collection (`R_gc()`) each time an R object is
+//! protected by an [`Robj`], and around the argument and return value
+//! conversions of exported functions, so such bugs surface deterministically
+//! in tests.
+//!
+//! GC stress is enabled by the `gc-stress` feature, or at runtime with
+//! [`set_gc_stress`] or by setting the `EXTENDR_GC_STRESS` environment
+//! variable to anything but `0` before the first R object is created.
+//! It makes code much slower, so it is meant for tests only.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use extendr_api::gc_stress;
+//! test! {
+//!     gc_stress::set_gc_stress(true);
+//!     let x = r!([1, 2, 3]);
+//!     let y = list!(x.clone(), "a");
+//!     gc_stress::set_gc_stress(false);
+//!     assert_eq!(y.len(), 2);
+//! }
+//! ```
+use crate::*;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static GC_STRESS: Lazy<AtomicBool> = Lazy::new(|| {
+    let from_env = std::env::var("EXTENDR_GC_STRESS").is_ok_and(|value| value != "0");
+    AtomicBool::new(cfg!(feature = "gc-stress") || from_env)
+});
+
+// Set while collecting, as finalizers run by the collection may create objects.
+static IN_GC: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable GC stress.
+pub fn set_gc_stress(enabled: bool) {
+    GC_STRESS.store(enabled, Ordering::Relaxed);
+}
+
+/// Return true if GC stress is enabled.
+pub fn gc_stress() -> bool {
+    GC_STRESS.load(Ordering::Relaxed)
+}
+
+/// Run a garbage collection if GC stress is enabled.
+///
+/// This is called by extendr and the wrappers of exported functions.
+#[doc(hidden)]
+pub fn gc_point() {
+    if gc_stress() && !IN_GC.swap(true, Ordering::Acquire) {
+        single_threaded(|| unsafe { R_gc() });
+        IN_GC.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,86 @@
+//! R connections backed by in-memory buffers, made with `rawConnection()`.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use extendr_api::io::RawConnection;
+//! test! {
+//!     let output = RawConnection::writer()?;
+//!     call!("saveRDS", list!(a = 1, b = "x"), output.robj())?;
+//!     let bytes = output.into_bytes()?;
+//!
+//!     let input = Robj::raw_connection(bytes)?;
+//!     assert_eq!(call!("readRDS", input.robj())?, list!(a = 1, b = "x"));
+//! }
+//! ```
+use crate as extendr_api;
+use crate::*;
+
+/// An R connection that reads from or writes to a buffer of bytes,
+/// without going through a file.
+///
+/// Pass [`robj()`](RawConnection::robj) to R functions that take a
+/// connection, such as `readBin()`, `writeLines()`, `readRDS()` or
+/// `saveRDS()`. The connection is closed when this is dropped, so it
+/// should not be kept by R beyond that.
+#[derive(Debug)]
+pub struct RawConnection {
+    robj: Robj,
+}
+
+impl RawConnection {
+    /// A connection open for writing. Get what was written with
+    /// [`bytes()`](RawConnection::bytes).
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use extendr_api::io::RawConnection;
+    /// test! {
+    ///     let output = RawConnection::writer()?;
+    ///     call!("writeBin", Raw::from_bytes(b"abc"), output.robj())?;
+    ///     assert_eq!(output.bytes()?, b"abc");
+    /// }
+    /// ```
+    pub fn writer() -> Result<Self> {
+        let robj = call!("rawConnection", Raw::new(0), open = "wb")?;
+        Ok(Self { robj })
+    }
+
+    /// The R connection object.
+    pub fn robj(&self) -> &Robj {
+        &self.robj
+    }
+
+    /// The bytes written so far to a connection open for writing.
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        let value = call!("rawConnectionValue", &self.robj)?;
+        let raw = Raw::try_from(value)?;
+        Ok(raw.as_slice().to_vec())
+    }
+
+    /// The bytes written, closing the connection.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        self.bytes()
+    }
+}
+
+impl Drop for RawConnection {
+    fn drop(&mut self) {
+        // The connection may have been closed by R already.
+        let _ = call!("close", &self.robj);
+    }
+}
+
+impl Robj {
+    /// A connection open for reading `bytes`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let input = Robj::raw_connection(b"line 1\nline 2\n".to_vec())?;
+    ///     assert_eq!(call!("readLines", input.robj())?, r!(["line 1", "line 2"]));
+    /// }
+    /// ```
+    pub fn raw_connection(bytes: Vec<u8>) -> Result<RawConnection> {
+        let raw = Raw::from_bytes(&bytes);
+        let robj = call!("rawConnection", raw, open = "rb")?;
+        Ok(RawConnection { robj })
+    }
+}
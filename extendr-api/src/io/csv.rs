@@ -0,0 +1,509 @@
+//! Read and write data frames as CSV or TSV text.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use extendr_api::io::{CsvOptions, ReadCsv, WriteCsv};
+//! test! {
+//!     let df = data_frame!(x = r!([1, 2]), y = r!(["a", "b,c"]));
+//!     let mut csv = Vec::new();
+//!     df.to_csv_writer(&mut csv, &CsvOptions::csv())?;
+//!     assert_eq!(String::from_utf8(csv.clone()).unwrap(), "x,y\n1,a\n2,\"b,c\"\n");
+//!
+//!     let read = Robj::from_csv_reader(csv.as_slice(), &CsvOptions::csv())?;
+//!     assert_eq!(read, df);
+//! }
+//! ```
+use crate as extendr_api;
+use crate::wrapper::symbol::row_names_symbol;
+use crate::*;
+use std::fmt::Write as _;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+// Buffered output is written out in pieces of about this size.
+const WRITE_BUFFER_SIZE: usize = 1 << 16;
+
+/// Options of [`WriteCsv`] and [`ReadCsv`].
+///
+/// The default is comma separated values with a header line,
+/// and `NA` for missing values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    delimiter: u8,
+    header: bool,
+    na: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            header: true,
+            na: "NA".to_string(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Comma separated values.
+    pub fn csv() -> Self {
+        Self::default()
+    }
+
+    /// Tab separated values.
+    pub fn tsv() -> Self {
+        Self::default().delimiter(b'\t')
+    }
+
+    /// Separate the values by `delimiter`, which must be an ASCII character.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether the first line has the column names.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// The text of missing values.
+    pub fn na<S: Into<String>>(mut self, na: S) -> Self {
+        self.na = na.into();
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if !self.delimiter.is_ascii() || matches!(self.delimiter, b'"' | b'\n' | b'\r') {
+            return Err(Error::Other(format!(
+                "invalid CSV delimiter {:?}",
+                self.delimiter as char
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn io_error(error: std::io::Error) -> Error {
+    Error::Other(format!("CSV input or output failed: {}", error))
+}
+
+// A column to write, borrowing the data of the R vector.
+enum Column<'a> {
+    Logicals(&'a [Rbool]),
+    Integers(&'a [i32]),
+    Doubles(&'a [f64]),
+    Strings(Vec<&'a str>),
+    Factor(&'a [i32], Vec<&'a str>),
+}
+
+impl<'a> Column<'a> {
+    fn new(robj: &'a Robj) -> Result<Self> {
+        if robj.is_factor() {
+            let levels = robj
+                .levels()
+                .map(|levels| levels.collect())
+                .unwrap_or_default();
+            let codes = robj.as_integer_slice().unwrap_or_default();
+            return Ok(Column::Factor(codes, levels));
+        }
+        match robj.sexptype() {
+            SEXPTYPE::LGLSXP => Ok(Column::Logicals(robj.as_logical_slice().unwrap())),
+            SEXPTYPE::INTSXP => Ok(Column::Integers(robj.as_integer_slice().unwrap())),
+            SEXPTYPE::REALSXP => Ok(Column::Doubles(robj.as_real_slice().unwrap())),
+            SEXPTYPE::STRSXP => Ok(Column::Strings(robj.as_str_iter().unwrap().collect())),
+            _ => Err(Error::Other(format!(
+                "can not write a column of type {:?} as CSV",
+                robj.rtype()
+            ))),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Column::Logicals(values) => values.len(),
+            Column::Integers(values) => values.len(),
+            Column::Doubles(values) => values.len(),
+            Column::Strings(values) => values.len(),
+            Column::Factor(codes, _) => codes.len(),
+        }
+    }
+
+    fn write(&self, row: usize, buf: &mut String, options: &CsvOptions) {
+        match self {
+            Column::Logicals(values) => {
+                let value = values[row];
+                if value.is_na() {
+                    buf.push_str(&options.na);
+                } else if value.is_true() {
+                    buf.push_str("TRUE");
+                } else {
+                    buf.push_str("FALSE");
+                }
+            }
+            Column::Integers(values) => match values[row] {
+                i32::MIN => buf.push_str(&options.na),
+                value => write!(buf, "{}", value).unwrap(),
+            },
+            Column::Doubles(values) => write_double(values[row], buf, options),
+            Column::Strings(values) => match values[row] {
+                value if value.is_na() => buf.push_str(&options.na),
+                value => write_string(value, buf, options),
+            },
+            Column::Factor(codes, levels) => {
+                match levels.get((codes[row] as usize).wrapping_sub(1)) {
+                    Some(level) if codes[row] != i32::MIN => write_string(level, buf, options),
+                    _ => buf.push_str(&options.na),
+                }
+            }
+        }
+    }
+}
+
+fn write_double(value: f64, buf: &mut String, options: &CsvOptions) {
+    if value.is_na() {
+        buf.push_str(&options.na);
+    } else if value.is_nan() {
+        buf.push_str("NaN");
+    } else if value.is_infinite() {
+        buf.push_str(if value > 0. { "Inf" } else { "-Inf" });
+    } else {
+        // The shortest text that reads back as the same value,
+        // with an exponent for very large and small values.
+        let start = buf.len();
+        write!(buf, "{:?}", value).unwrap();
+        if buf.ends_with(".0") && !buf[start..].contains('e') {
+            buf.truncate(buf.len() - 2);
+        }
+    }
+}
+
+fn write_string(value: &str, buf: &mut String, options: &CsvOptions) {
+    let needs_quotes = value.is_empty()
+        || value == options.na
+        || value
+            .bytes()
+            .any(|b| b == options.delimiter || matches!(b, b'"' | b'\n' | b'\r'));
+    if needs_quotes {
+        buf.push('"');
+        buf.push_str(&value.replace('"', "\"\""));
+        buf.push('"');
+    } else {
+        buf.push_str(value);
+    }
+}
+
+/// Write a data frame as CSV or TSV text.
+///
+/// Each element of a data frame or list is a column, and a vector is
+/// written as a single column `x`. Columns can be logical, integer, double,
+/// character or factor vectors. Other classes, such as dates, are written
+/// with `as.character()`. Row names are not written.
+///
+/// Doubles are written with the shortest text that reads back as the same
+/// value. Strings are quoted if they contain the delimiter, a quote or a
+/// newline, or if they are empty or the same as the text of `NA`.
+pub trait WriteCsv: GetSexp {
+    /// Write to the file at `path`, replacing it if it exists.
+    fn write_csv<P: AsRef<Path>>(&self, path: &P, options: &CsvOptions) -> Result<()> {
+        let mut file = std::fs::File::create(path.as_ref())
+            .map_err(|_| Error::Other(format!("could not create file {:?}", path.as_ref())))?;
+        self.to_csv_writer(&mut file, options)
+    }
+
+    /// Write to `writer`, which does not need to be buffered.
+    fn to_csv_writer<W: Write>(&self, writer: &mut W, options: &CsvOptions) -> Result<()> {
+        options.check()?;
+        let robj = self.as_robj();
+        let (names, values): (Vec<String>, Vec<Robj>) = if robj.is_list() {
+            let names: Vec<String> = match robj.names() {
+                Some(names) => names.map(String::from).collect(),
+                None => (1..=robj.len()).map(|i| format!("V{}", i)).collect(),
+            };
+            (names, List::try_from(robj)?.values().collect())
+        } else {
+            (vec!["x".to_string()], vec![robj.clone()])
+        };
+        // Columns of other classes, e.g. dates, are written as R shows them.
+        let values = values
+            .into_iter()
+            .map(|value| {
+                if value.is_object() && !value.is_factor() {
+                    call!("as.character", value)
+                } else {
+                    Ok(value)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let columns = values.iter().map(Column::new).collect::<Result<Vec<_>>>()?;
+        let rows = columns.first().map_or(0, Column::len);
+        if let Some(column) = columns.iter().position(|column| column.len() != rows) {
+            return Err(Error::Other(format!(
+                "column {} has {} values, expected {}",
+                names[column],
+                columns[column].len(),
+                rows
+            )));
+        }
+
+        let delimiter = options.delimiter as char;
+        let mut buf = String::with_capacity(WRITE_BUFFER_SIZE + 1024);
+        if options.header {
+            for (i, name) in names.iter().enumerate() {
+                if i > 0 {
+                    buf.push(delimiter);
+                }
+                write_string(name, &mut buf, options);
+            }
+            buf.push('\n');
+        }
+        for row in 0..rows {
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    buf.push(delimiter);
+                }
+                column.write(row, &mut buf, options);
+            }
+            buf.push('\n');
+            if buf.len() >= WRITE_BUFFER_SIZE {
+                writer.write_all(buf.as_bytes()).map_err(io_error)?;
+                buf.clear();
+            }
+        }
+        writer.write_all(buf.as_bytes()).map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }
+}
+
+impl<R: GetSexp> WriteCsv for R {}
+
+// A field of the input, and whether it was quoted.
+struct Field {
+    text: String,
+    quoted: bool,
+}
+
+// Split `text` into records of fields.
+fn parse_records(text: &str, delimiter: u8) -> Result<Vec<Vec<Field>>> {
+    let bytes = text.as_bytes();
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let mut field = Field {
+            text: String::new(),
+            quoted: bytes[pos] == b'"',
+        };
+        if field.quoted {
+            pos += 1;
+            loop {
+                let Some(end) = text[pos..].find('"').map(|end| pos + end) else {
+                    return Err(Error::Other(format!(
+                        "unterminated quoted field on line {} of the CSV input",
+                        line
+                    )));
+                };
+                field.text.push_str(&text[pos..end]);
+                line += text[pos..end].matches('\n').count();
+                pos = end + 1;
+                if bytes.get(pos) == Some(&b'"') {
+                    field.text.push('"');
+                    pos += 1;
+                } else {
+                    break;
+                }
+            }
+        } else {
+            let end = bytes[pos..]
+                .iter()
+                .position(|&b| b == delimiter || b == b'\n')
+                .map_or(bytes.len(), |end| pos + end);
+            field.text.push_str(text[pos..end].trim_end_matches('\r'));
+            pos = end;
+        }
+        record.push(field);
+        match bytes.get(pos) {
+            Some(&b) if b == delimiter => {
+                pos += 1;
+                // A delimiter at the end of the input is followed by an empty field.
+                if pos == bytes.len() {
+                    record.push(Field {
+                        text: String::new(),
+                        quoted: false,
+                    });
+                }
+            }
+            Some(b'\r') if bytes.get(pos + 1) == Some(&b'\n') => {
+                pos += 2;
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            }
+            Some(b'\n') | None => {
+                pos += 1;
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            }
+            Some(_) => {
+                return Err(Error::Other(format!(
+                    "unexpected text after a quoted field on line {} of the CSV input",
+                    line
+                )))
+            }
+        }
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+    // Skip empty lines.
+    records.retain(|record| !(record.len() == 1 && record[0].text.is_empty() && !record[0].quoted));
+    Ok(records)
+}
+
+fn parse_logical(text: &str) -> Option<bool> {
+    match text {
+        "TRUE" | "True" | "true" | "T" => Some(true),
+        "FALSE" | "False" | "false" | "F" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_double(text: &str) -> Option<f64> {
+    match text {
+        "Inf" | "+Inf" => Some(f64::INFINITY),
+        "-Inf" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        // Rust also accepts e.g. `inf` and `nan`, which R does not.
+        _ if text
+            .bytes()
+            .any(|b| b.is_ascii_alphabetic() && !matches!(b, b'e' | b'E')) =>
+        {
+            None
+        }
+        _ => text.parse().ok(),
+    }
+}
+
+fn parse_integer(text: &str) -> Option<i32> {
+    text.parse().ok().filter(|&value| value != i32::MIN)
+}
+
+// A missing value in a logical or numeric column.
+fn is_na(field: &Field, options: &CsvOptions) -> bool {
+    !field.quoted && (field.text == options.na || field.text.is_empty())
+}
+
+// Make a column from the fields, with the most specific type that fits all
+// values: logical, integer, double or character.
+fn make_column(fields: &[&Field], options: &CsvOptions) -> Robj {
+    fn parse_all<T>(
+        fields: &[&Field],
+        options: &CsvOptions,
+        parse: fn(&str) -> Option<T>,
+    ) -> Option<Vec<Option<T>>> {
+        fields
+            .iter()
+            .map(|field| {
+                if is_na(field, options) {
+                    Some(None)
+                } else {
+                    parse(&field.text).map(Some)
+                }
+            })
+            .collect()
+    }
+    if let Some(values) = parse_all(fields, options, parse_logical) {
+        return values.into_iter().collect_robj();
+    }
+    if let Some(values) = parse_all(fields, options, parse_integer) {
+        return values.into_iter().collect_robj();
+    }
+    if let Some(values) = parse_all(fields, options, parse_double) {
+        return values.into_iter().collect_robj();
+    }
+    // In a character column, only the text of `NA` is missing.
+    fields
+        .iter()
+        .map(|field| {
+            if !field.quoted && field.text == options.na {
+                None
+            } else {
+                Some(field.text.as_str())
+            }
+        })
+        .collect_robj()
+}
+
+/// Read CSV or TSV text into a data frame.
+///
+/// The type of each column is the most specific of logical, integer, double
+/// and character that fits all of its values. Unquoted empty fields and the
+/// text of `NA` are missing values, except that an empty field is an empty
+/// string in a character column. Without a header line, the columns are
+/// named `V1`, `V2`, etc., as in R.
+pub trait ReadCsv {
+    /// Read the file at `path`.
+    fn read_csv<P: AsRef<Path>>(path: &P, options: &CsvOptions) -> Result<Robj> {
+        let file = std::fs::File::open(path.as_ref())
+            .map_err(|_| Error::Other(format!("could not open file {:?}", path.as_ref())))?;
+        Self::from_csv_reader(std::io::BufReader::new(file), options)
+    }
+
+    /// Read from `reader`.
+    fn from_csv_reader<R: BufRead>(mut reader: R, options: &CsvOptions) -> Result<Robj> {
+        options.check()?;
+        let mut text = String::new();
+        reader.read_to_string(&mut text).map_err(io_error)?;
+        let mut records = parse_records(&text, options.delimiter)?.into_iter();
+        let names: Vec<String> = if options.header {
+            records
+                .next()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|field| field.text)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let records: Vec<Vec<Field>> = records.collect();
+        let ncols = if options.header {
+            names.len()
+        } else {
+            records.first().map_or(0, Vec::len)
+        };
+        let names = if options.header {
+            names
+        } else {
+            (1..=ncols).map(|i| format!("V{}", i)).collect()
+        };
+        let header_lines = options.header as usize;
+        if let Some(row) = records.iter().position(|record| record.len() != ncols) {
+            return Err(Error::Other(format!(
+                "record {} of the CSV input has {} fields, expected {}",
+                row + 1 + header_lines,
+                records[row].len(),
+                ncols
+            )));
+        }
+
+        let columns = (0..ncols).map(|col| {
+            let fields = records
+                .iter()
+                .map(|record| &record[col])
+                .collect::<Vec<_>>();
+            make_column(&fields, options)
+        });
+        let mut df = List::from_names_and_values(names.iter().map(String::as_str), columns)?;
+        df.set_class(["data.frame"])?;
+        let row_names = if records.is_empty() {
+            r!(Vec::<i32>::new())
+        } else {
+            // Compact row names, `1:n`.
+            r!([NA_INTEGER, Some(-(records.len() as i32))])
+        };
+        df.set_attrib(row_names_symbol(), row_names)?;
+        Ok(df.into())
+    }
+}
+
+impl ReadCsv for Robj {}
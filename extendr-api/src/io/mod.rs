@@ -1,7 +1,13 @@
 pub type PstreamFormat = libR_sys::R_pstream_format_t;
 
+mod connection;
+mod csv;
 mod load;
+mod rows;
 mod save;
 
+pub use connection::RawConnection;
+pub use csv::{CsvOptions, ReadCsv, WriteCsv};
 pub use load::Load;
+pub use rows::{Cell, ColumnType, DataFrameSink, RowSink};
 pub use save::Save;
@@ -0,0 +1,271 @@
+use crate::scalar::{Rfloat, Rint};
+use crate::wrapper::symbol::row_names_symbol;
+use crate::*;
+
+/// The type of a column of a [`DataFrameSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Logical,
+    Integer,
+    Double,
+    Character,
+}
+
+/// A value of a row, borrowed from e.g. a database cursor.
+///
+/// `Cell::Null` and `None` are `NA`. 64 bit integers are stored in
+/// integer columns if they fit and in double columns otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cell<'a> {
+    Null,
+    Logical(bool),
+    Integer(i64),
+    Double(f64),
+    Text(&'a str),
+}
+
+impl From<bool> for Cell<'_> {
+    fn from(value: bool) -> Self {
+        Cell::Logical(value)
+    }
+}
+
+impl From<i32> for Cell<'_> {
+    fn from(value: i32) -> Self {
+        Cell::Integer(value as i64)
+    }
+}
+
+impl From<i64> for Cell<'_> {
+    fn from(value: i64) -> Self {
+        Cell::Integer(value)
+    }
+}
+
+impl From<f64> for Cell<'_> {
+    fn from(value: f64) -> Self {
+        Cell::Double(value)
+    }
+}
+
+impl<'a> From<&'a str> for Cell<'a> {
+    fn from(value: &'a str) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl<'a, T: Into<Cell<'a>>> From<Option<T>> for Cell<'a> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Cell::Null, Into::into)
+    }
+}
+
+/// A destination for rows that are filled one value at a time, so that
+/// they can be streamed from a database without collecting them first.
+///
+/// Implement this for your own destinations, or use [`DataFrameSink`].
+pub trait RowSink {
+    /// Append the next value of the current row.
+    fn push_cell(&mut self, cell: Cell<'_>) -> Result<()>;
+
+    /// End the current row.
+    fn end_row(&mut self) -> Result<()>;
+
+    /// Append all the values of a row and end it.
+    fn push_row<'a, I>(&mut self, row: I) -> Result<()>
+    where
+        Self: Sized,
+        I: IntoIterator,
+        I::Item: Into<Cell<'a>>,
+    {
+        for cell in row {
+            self.push_cell(cell.into())?;
+        }
+        self.end_row()
+    }
+}
+
+enum Column {
+    Logical(RVectorBuilder<Rbool>),
+    Integer(RVectorBuilder<Rint>),
+    Double(RVectorBuilder<Rfloat>),
+    Character(RVectorBuilder<Text>),
+}
+
+/// A `CHARSXP` that is stored in a character column as it is.
+struct Text(Rstr);
+
+impl ToVectorValue for Text {
+    fn sexptype() -> SEXPTYPE {
+        SEXPTYPE::STRSXP
+    }
+
+    fn to_sexp(&self) -> SEXP {
+        unsafe { self.0.get() }
+    }
+}
+
+/// A [`RowSink`] that appends the values directly to growing R vectors,
+/// one per column, and returns them as a data frame.
+///
+/// Logicals are stored in integer and double columns as 0 and 1, and
+/// integers in logical columns as `value != 0` and in double columns as
+/// doubles. Other conversions, such as a double in an integer column,
+/// are an error rather than being truncated as `as.integer()` would.
+/// After an error, the columns may have different lengths, so the
+/// sink should be discarded.
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// use extendr_api::io::{Cell, ColumnType, DataFrameSink, RowSink};
+/// test! {
+///     let mut sink = DataFrameSink::new([
+///         ("id", ColumnType::Integer),
+///         ("name", ColumnType::Character),
+///         ("score", ColumnType::Double),
+///     ]);
+///     // e.g. the rows of a database query.
+///     let rows = [(1, Some("a"), 1.5), (2, None, 2.)];
+///     for (id, name, score) in rows {
+///         sink.push_cell(id.into())?;
+///         sink.push_cell(name.into())?;
+///         sink.push_cell(score.into())?;
+///         sink.end_row()?;
+///     }
+///     sink.push_row([Cell::Integer(3), Cell::Text("c"), Cell::Integer(3)])?;
+///     let df = sink.finish()?;
+///     assert_eq!(df, R!(r#"data.frame(id = 1:3, name = c("a", NA, "c"), score = c(1.5, 2, 3))"#)?);
+/// }
+/// ```
+pub struct DataFrameSink {
+    names: Vec<String>,
+    types: Vec<ColumnType>,
+    columns: Vec<Column>,
+    column: usize,
+    rows: usize,
+}
+
+impl DataFrameSink {
+    /// Create a sink with the given column names and types.
+    pub fn new<'a, I>(columns: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, ColumnType)>,
+    {
+        Self::with_capacity(columns, 0)
+    }
+
+    /// Create a sink with room for `rows` rows, e.g. if the number of rows of
+    /// a query is known.
+    pub fn with_capacity<'a, I>(columns: I, rows: usize) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, ColumnType)>,
+    {
+        let mut names = Vec::new();
+        let mut types = Vec::new();
+        let mut builders = Vec::new();
+        for (name, column_type) in columns {
+            names.push(name.to_string());
+            types.push(column_type);
+            builders.push(match column_type {
+                ColumnType::Logical => Column::Logical(RVectorBuilder::with_capacity(rows)),
+                ColumnType::Integer => Column::Integer(RVectorBuilder::with_capacity(rows)),
+                ColumnType::Double => Column::Double(RVectorBuilder::with_capacity(rows)),
+                ColumnType::Character => Column::Character(RVectorBuilder::with_capacity(rows)),
+            });
+        }
+        Self {
+            names,
+            types,
+            columns: builders,
+            column: 0,
+            rows: 0,
+        }
+    }
+
+    /// The number of complete rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Return the rows as a data frame. It is an error if the last row is
+    /// incomplete.
+    pub fn finish(self) -> Result<Robj> {
+        if self.column != 0 {
+            return Err(Error::Other(format!(
+                "the last row has {} of {} values",
+                self.column,
+                self.columns.len()
+            )));
+        }
+        let values = self
+            .columns
+            .into_iter()
+            .map(|column| match column {
+                Column::Logical(builder) => builder.finish(),
+                Column::Integer(builder) => builder.finish(),
+                Column::Double(builder) => builder.finish(),
+                Column::Character(builder) => builder.finish(),
+            })
+            .collect::<Vec<_>>();
+        let mut dataframe = List::from_names_and_values(&self.names, values)?;
+        dataframe.set_class(["data.frame"])?;
+        dataframe.set_attrib(
+            row_names_symbol(),
+            wrapper::dataframe::compact_row_names(self.rows),
+        )?;
+        Ok(dataframe.into())
+    }
+
+    fn mismatch(&self, cell: Cell<'_>) -> Error {
+        Error::Other(format!(
+            "can not store {:?} in the {:?} column {:?}",
+            cell, self.types[self.column], self.names[self.column]
+        ))
+    }
+}
+
+impl RowSink for DataFrameSink {
+    fn push_cell(&mut self, cell: Cell<'_>) -> Result<()> {
+        if self.column == self.columns.len() {
+            return Err(Error::Other(format!(
+                "a row has more than {} values",
+                self.columns.len()
+            )));
+        }
+        match (&mut self.columns[self.column], cell) {
+            (Column::Logical(builder), Cell::Null) => builder.push(Rbool::na()),
+            (Column::Logical(builder), Cell::Logical(value)) => builder.push(value.into()),
+            (Column::Logical(builder), Cell::Integer(value)) => builder.push((value != 0).into()),
+            (Column::Integer(builder), Cell::Null) => builder.push(Rint::na()),
+            (Column::Integer(builder), Cell::Logical(value)) => builder.push((value as i32).into()),
+            (Column::Integer(builder), Cell::Integer(value)) => match i32::try_from(value) {
+                Ok(value) if value != i32::MIN => builder.push(value.into()),
+                _ => return Err(self.mismatch(cell)),
+            },
+            (Column::Double(builder), Cell::Null) => builder.push(Rfloat::na()),
+            (Column::Double(builder), Cell::Logical(value)) => {
+                builder.push((value as i32 as f64).into())
+            }
+            (Column::Double(builder), Cell::Integer(value)) => builder.push((value as f64).into()),
+            (Column::Double(builder), Cell::Double(value)) => builder.push(value.into()),
+            (Column::Character(builder), Cell::Null) => builder.push(Text(Rstr::na())),
+            (Column::Character(builder), Cell::Text(value)) => builder.push(Text(value.into())),
+            _ => return Err(self.mismatch(cell)),
+        }
+        self.column += 1;
+        Ok(())
+    }
+
+    fn end_row(&mut self) -> Result<()> {
+        if self.column != self.columns.len() {
+            return Err(Error::Other(format!(
+                "a row has {} of {} values",
+                self.column,
+                self.columns.len()
+            )));
+        }
+        self.column = 0;
+        self.rows += 1;
+        Ok(())
+    }
+}
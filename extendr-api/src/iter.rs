@@ -64,21 +64,12 @@ pub(crate) fn str_from_strsxp<'a>(sexp: SEXP, index: usize) -> Option<&'a str> {
     })
 }
 
-impl Iterator for StrIter {
-    type Item = &'static str;
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len, Some(self.len))
-    }
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl StrIter {
+    // Get element `i`, which must be less than the length.
+    fn get(&self, i: usize) -> Option<&'static str> {
         unsafe {
-            let i = self.i;
-            self.i += 1;
             let vector = self.vector.get();
-            if i >= self.len {
-                None
-            } else if TYPEOF(vector) == SEXPTYPE::NILSXP {
+            if TYPEOF(vector) == SEXPTYPE::NILSXP {
                 None
             } else if TYPEOF(vector) == SEXPTYPE::STRSXP {
                 str_from_strsxp(vector, i)
@@ -89,8 +80,10 @@ impl Iterator for StrIter {
             } else if Rf_isFactor(vector).into() {
                 // factor support: factor is an integer, and we need
                 // the value of it, to retrieve the assigned label
-                let level_index = std::slice::from_raw_parts(INTEGER(vector), self.len as _);
-                let level_index = level_index.get(i)?;
+                let level_index = *INTEGER(vector).add(i);
+                if level_index.is_na() {
+                    return Some(<&str>::na());
+                }
                 let level_index = level_index
                     .checked_sub(1)
                     .expect("the factor integer has an invalid value in it");
@@ -100,19 +93,62 @@ impl Iterator for StrIter {
             }
         }
     }
+}
+
+impl Iterator for StrIter {
+    type Item = &'static str;
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.len {
+            return None;
+        }
+        let i = self.i;
+        self.i += 1;
+        self.get(i)
+    }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.i += n;
+        self.i = self.i.saturating_add(n).min(self.len);
         self.next()
     }
 }
 
+/// Iterate from the end, for example with `rev()`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let mut iter = r!(["a", "b", "c"]).as_str_iter().unwrap();
+///     assert_eq!(iter.next_back(), Some("c"));
+///     assert_eq!(iter.len(), 2);
+///     assert_eq!(iter.rev().collect::<Vec<_>>(), vec!["b", "a"]);
+///
+///     let factor = factor!(["x", "y", "x"]);
+///     assert_eq!(factor.as_str_iter().unwrap().rev().collect::<Vec<_>>(), vec!["x", "y", "x"]);
+/// }
+/// ```
+impl DoubleEndedIterator for StrIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.i >= self.len {
+            return None;
+        }
+        self.len -= 1;
+        self.get(self.len)
+    }
+}
+
 impl ExactSizeIterator for StrIter {
     fn len(&self) -> usize {
-        self.len - self.i
+        self.len.saturating_sub(self.i)
     }
 }
 
+impl std::iter::FusedIterator for StrIter {}
+
 macro_rules! impl_iter_debug {
     ($name: ty) => {
         impl std::fmt::Debug for $name {
@@ -221,6 +257,98 @@ pub trait AsStrIter: GetSexp + Types + Length + Attributes + Rinternals {
 
 impl AsStrIter for Robj {}
 
+/// Iterator over the names and elements of a vector or list.
+///
+/// Elements of atomic vectors are returned as length one vectors
+/// without attributes. Missing and empty names are `None`.
+///
+/// See [`Robj::pairs`].
+#[derive(Clone)]
+pub struct NamedPairIter {
+    vector: Robj,
+    names: Option<StrIter>,
+    i: usize,
+    len: usize,
+}
+
+impl NamedPairIter {
+    pub(crate) fn new(vector: &Robj) -> Self {
+        Self {
+            vector: vector.clone(),
+            names: vector.names(),
+            i: 0,
+            len: vector.len(),
+        }
+    }
+}
+
+impl Iterator for NamedPairIter {
+    type Item = (Option<&'static str>, Robj);
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len - self.i, Some(self.len - self.i))
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.len {
+            return None;
+        }
+        let i = self.i;
+        self.i += 1;
+        let name = self.names.as_mut().and_then(|names| names.next());
+        Some((non_empty_name(name), vector_elt(&self.vector, i)))
+    }
+}
+
+impl DoubleEndedIterator for NamedPairIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.i >= self.len {
+            return None;
+        }
+        self.len -= 1;
+        let name = self.names.as_mut().and_then(|names| names.next_back());
+        Some((non_empty_name(name), vector_elt(&self.vector, self.len)))
+    }
+}
+
+impl ExactSizeIterator for NamedPairIter {}
+
+impl std::iter::FusedIterator for NamedPairIter {}
+
+// Missing and empty names are `None`.
+fn non_empty_name(name: Option<&'static str>) -> Option<&'static str> {
+    name.filter(|name| !name.is_na() && !name.is_empty())
+}
+
+impl std::fmt::Debug for NamedPairIter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+// Get element `i` of a list, or a copy of element `i` of an atomic vector.
+fn vector_elt(vector: &Robj, i: usize) -> Robj {
+    single_threaded(|| unsafe {
+        let src = vector.get();
+        let sexptype = vector.sexptype();
+        if let SEXPTYPE::VECSXP | SEXPTYPE::EXPRSXP = sexptype {
+            return Robj::from_sexp(VECTOR_ELT(src, i as R_xlen_t));
+        }
+        let mut elt = Robj::alloc_vector(sexptype, 1);
+        let dest = elt.get_mut();
+        match sexptype {
+            SEXPTYPE::LGLSXP => *LOGICAL(dest) = *LOGICAL(src).add(i),
+            SEXPTYPE::INTSXP => *INTEGER(dest) = *INTEGER(src).add(i),
+            SEXPTYPE::REALSXP => *REAL(dest) = *REAL(src).add(i),
+            SEXPTYPE::CPLXSXP => *COMPLEX(dest) = *COMPLEX(src).add(i),
+            SEXPTYPE::RAWSXP => *RAW(dest) = *RAW(src).add(i),
+            SEXPTYPE::STRSXP => SET_STRING_ELT(dest, 0, STRING_ELT(src, i as R_xlen_t)),
+            _ => unreachable!(),
+        }
+        elt
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use extendr_engine::with_r;
@@ -295,6 +295,11 @@
 //! - `graphics`: provides the functionality to control or implement graphics devices.
 //! - `either`: provides implementation of type conversion traits for `Either<L, R>` from [`either`](https://docs.rs/either/latest/either/) if `L` and `R` both implement those traits.
 //! - `faer`: provides conversion between R's matrices and [`faer`](https://docs.rs/faer/latest/faer/).
+//! - `log`: provides a [`log`](https://docs.rs/log/latest/log/) backend that writes to the R console, see [`logger`].
+//! - `parquet`: reads and writes data frames as [Parquet](https://parquet.apache.org/) files, see [`optional::parquet`].
+//! - `polars`: provides conversion between R's data frames and [`polars`](https://docs.rs/polars/latest/polars/) data frames, see [`optional::polars`].
+//! - `gc-stress`: runs R's garbage collector whenever extendr allocates or converts, to find protection bugs in tests, see [`gc_stress`].
+//! - `conversion-bench`: generates micro-benchmarks of the argument conversions of exported functions, see [`bench`].
 //!
 //! extendr-api supports three ways of returning a Result<T,E> to R.
 //! Only one behavior feature can be enabled at a time.
@@ -308,14 +313,24 @@
     html_logo_url = "https://raw.githubusercontent.com/extendr/extendr/master/extendr-logo-256.png"
 )]
 
+pub mod defer;
 pub mod error;
+pub mod event_loop;
 pub mod functions;
+pub mod future;
+pub mod gc_stress;
 pub mod io;
 pub mod iter;
 pub mod lang_macros;
+pub mod local;
 pub mod metadata;
+pub mod options;
 pub mod ownership;
+pub mod package;
+pub mod panic_hook;
+pub mod parallel;
 pub mod prelude;
+pub mod progress;
 pub mod rmacros;
 
 #[cfg(feature = "serde")]
@@ -327,9 +342,24 @@ pub mod deserializer;
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
+#[cfg(feature = "conversion-bench")]
+pub mod bench;
+
+#[cfg(feature = "log")]
+pub mod logger;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
 pub mod robj;
 pub mod scalar;
+
+#[cfg(feature = "non-api")]
+pub mod stack;
+
+pub mod task_callback;
 pub mod thread_safety;
+pub mod version;
 pub mod wrapper;
 
 pub mod na;
@@ -338,6 +368,24 @@ pub mod optional;
 
 pub(crate) mod conversions;
 
+/// Crate internals, exposed for the benchmarks in `benches/` only.
+#[doc(hidden)]
+pub mod bench_internals {
+    use crate::*;
+
+    pub fn str_to_character(s: &str) -> SEXP {
+        robj::str_to_character(s)
+    }
+
+    pub fn fixed_size_collect<I>(iter: I, len: usize) -> Robj
+    where
+        I: Iterator,
+        I::Item: ToVectorValue,
+    {
+        robj::fixed_size_collect(iter, len)
+    }
+}
+
 pub use std::convert::{TryFrom, TryInto};
 pub use std::ops::Deref;
 pub use std::ops::DerefMut;
@@ -356,7 +404,10 @@ pub use functions::*;
 pub use lang_macros::*;
 pub use na::*;
 pub use robj::*;
-pub use thread_safety::{catch_r_error, handle_panic, single_threaded, throw_r_error};
+pub use thread_safety::{
+    catch_r_error, error_condition, handle_panic, single_threaded, throw_r_condition,
+    throw_r_error, toplevel_exec,
+};
 pub use wrapper::*;
 
 pub use extendr_macros::*;
@@ -405,6 +456,10 @@ pub use libR_sys::GetRNGstate;
 #[doc(hidden)]
 pub use libR_sys::PutRNGstate;
 
+/// This is used in `#[extendr(external)]` on `fn`-items.
+#[doc(hidden)]
+pub use libR_sys::CDR;
+
 #[doc(hidden)]
 pub use libR_sys::SEXP;
 
@@ -430,10 +485,16 @@ unsafe fn make_method_def(
     wrapped_name: &str,
 ) {
     cstrings.push(std::ffi::CString::new(wrapped_name).unwrap());
+    // `.External` routines take any number of arguments.
+    let num_args = if func.external {
+        -1
+    } else {
+        func.args.len() as i32
+    };
     rmethods.push(libR_sys::R_CallMethodDef {
         name: cstrings.last().unwrap().as_ptr(),
         fun: Some(std::mem::transmute(func.func_ptr)),
-        numArgs: func.args.len() as i32,
+        numArgs: num_args,
     });
 }
 
@@ -441,37 +502,63 @@ unsafe fn make_method_def(
 // This is called from the code generated by the #[extendr] attribute.
 #[doc(hidden)]
 pub unsafe fn register_call_methods(info: *mut libR_sys::DllInfo, metadata: Metadata) {
+    register_call_methods_with_options(info, metadata, false, false)
+}
+
+// As `register_call_methods`, with the `use_dynamic_symbols` and `force_symbols`
+// options of `extendr_module!`, which are passed on to `R_useDynamicSymbols`
+// and `R_forceSymbols`.
+#[doc(hidden)]
+pub unsafe fn register_call_methods_with_options(
+    info: *mut libR_sys::DllInfo,
+    metadata: Metadata,
+    use_dynamic_symbols: bool,
+    force_symbols: bool,
+) {
     let mut rmethods = Vec::new();
+    let mut external_methods = Vec::new();
     let mut cstrings = Vec::new();
     for func in metadata.functions {
-        let wrapped_name = format!("wrap__{}", func.mod_name);
-        make_method_def(&mut cstrings, &mut rmethods, &func, wrapped_name.as_str());
+        if func.external {
+            make_method_def(&mut cstrings, &mut external_methods, &func, func.symbol);
+        } else {
+            make_method_def(&mut cstrings, &mut rmethods, &func, func.symbol);
+        }
     }
 
     for imp in metadata.impls {
         for func in imp.methods {
-            let wrapped_name = format!("wrap__{}__{}", imp.name, func.mod_name);
-            make_method_def(&mut cstrings, &mut rmethods, &func, wrapped_name.as_str());
+            make_method_def(&mut cstrings, &mut rmethods, &func, func.symbol);
         }
     }
 
-    rmethods.push(libR_sys::R_CallMethodDef {
-        name: std::ptr::null(),
-        fun: None,
-        numArgs: 0,
-    });
+    for methods in [&mut rmethods, &mut external_methods] {
+        methods.push(libR_sys::R_CallMethodDef {
+            name: std::ptr::null(),
+            fun: None,
+            numArgs: 0,
+        });
+    }
 
     libR_sys::R_registerRoutines(
         info,
         std::ptr::null(),
         rmethods.as_ptr(),
         std::ptr::null(),
-        std::ptr::null(),
+        external_methods.as_ptr(),
     );
 
-    // This seems to allow both symbols and strings,
-    libR_sys::R_useDynamicSymbols(info, Rboolean::FALSE);
-    libR_sys::R_forceSymbols(info, Rboolean::FALSE);
+    // The defaults (both false) allow both symbols and strings, but only for
+    // registered routines.
+    let to_rboolean = |value: bool| {
+        if value {
+            Rboolean::TRUE
+        } else {
+            Rboolean::FALSE
+        }
+    };
+    libR_sys::R_useDynamicSymbols(info, to_rboolean(use_dynamic_symbols));
+    libR_sys::R_forceSymbols(info, to_rboolean(force_symbols));
 }
 
 /// Type of R objects used by [Robj::rtype].
@@ -0,0 +1,178 @@
+//! Temporary changes to R's global state, undone when a Rust scope is left.
+//!
+//! These follow the `local_*()` functions of the withr package. Each sets
+//! some state and returns a [`LocalGuard`] that restores the previous state
+//! when dropped, including on early returns with `?` and on panics.
+//! Options are set with [`local_option`](crate::options::local_option).
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! test! {
+//!     fn sample_dir() -> Result<Robj> {
+//!         let _seed = local_seed(42)?;
+//!         let _dir = local_dir(std::env::temp_dir())?;
+//!         R!("sample(10)")
+//!     }
+//!     let wd = R!("getwd()")?;
+//!     assert_eq!(sample_dir()?, sample_dir()?);
+//!     assert_eq!(R!("getwd()")?, wd);
+//! }
+//! ```
+use crate::defer::DeferR;
+use crate::*;
+use std::path::Path;
+
+/// Restores a part of R's global state when dropped.
+///
+/// Errors while restoring are ignored, as they can not be reported from drop.
+#[must_use = "the state is restored when the guard is dropped"]
+pub struct LocalGuard {
+    restore: DeferR,
+}
+
+impl LocalGuard {
+    fn new<F: FnOnce() + 'static>(restore: F) -> Self {
+        let mut defer = DeferR::new();
+        defer.add(restore);
+        Self { restore: defer }
+    }
+
+    /// Keep the new state instead of restoring the previous one.
+    pub fn keep(mut self) {
+        self.restore.cancel();
+    }
+}
+
+impl std::fmt::Debug for LocalGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalGuard").finish_non_exhaustive()
+    }
+}
+
+/// Set graphical parameters of the current device until the guard is dropped.
+///
+/// Equivalent to `withr::local_par()`. Like `par()`, this opens a device
+/// if none is open.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     R!("pdf(NULL)")?;
+///     {
+///         let _par = local_par([("mfrow", r!([2, 2])), ("cex", r!(0.5))])?;
+///         assert_eq!(R!("par('mfrow')")?, r!([2, 2]));
+///     }
+///     assert_eq!(R!("par('mfrow')")?, r!([1, 1]));
+///     R!("dev.off()")?;
+/// }
+/// ```
+pub fn local_par<N, V, I>(params: I) -> Result<LocalGuard>
+where
+    N: AsRef<str>,
+    V: Into<Robj>,
+    I: IntoIterator<Item = (N, V)>,
+{
+    let (names, values): (Vec<String>, Vec<Robj>) = params
+        .into_iter()
+        .map(|(name, value)| (name.as_ref().to_string(), value.into()))
+        .unzip();
+    let params = List::from_names_and_values(names, values)?;
+    let old = eval_string_with_params("graphics::par(param.0)", &[&Robj::from(params)])?;
+    Ok(LocalGuard::new(move || {
+        let _ = eval_string_with_params("graphics::par(param.0)", &[&old]);
+    }))
+}
+
+/// Seed the random number generator until the guard is dropped,
+/// then restore the previous random state.
+///
+/// Equivalent to `withr::local_seed()`. If there was no random state,
+/// `.Random.seed` is removed again.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     R!("set.seed(1)")?;
+///     let state = R!(".Random.seed")?;
+///     {
+///         let _seed = local_seed(42)?;
+///         let first = R!("runif(1)")?;
+///         R!("set.seed(42)")?;
+///         assert_eq!(R!("runif(1)")?, first);
+///     }
+///     assert_eq!(R!(".Random.seed")?, state);
+/// }
+/// ```
+pub fn local_seed(seed: i32) -> Result<LocalGuard> {
+    let old = eval_string(
+        "if (exists('.Random.seed', envir = globalenv(), inherits = FALSE)) \
+         get('.Random.seed', envir = globalenv()) else NULL",
+    )?;
+    eval_string_with_params("set.seed(param.0)", &[&r!(seed)])?;
+    Ok(LocalGuard::new(move || {
+        let _ = if old.is_null() {
+            eval_string("rm('.Random.seed', envir = globalenv())")
+        } else {
+            eval_string_with_params(
+                "assign('.Random.seed', param.0, envir = globalenv())",
+                &[&old],
+            )
+        };
+    }))
+}
+
+/// Change the working directory until the guard is dropped.
+///
+/// Equivalent to `withr::local_dir()`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let wd = R!("getwd()")?;
+///     let temp = std::env::temp_dir().canonicalize().unwrap();
+///     {
+///         let _dir = local_dir(&temp)?;
+///         assert_eq!(R!("normalizePath(getwd())")?, r!(temp.to_str().unwrap()));
+///     }
+///     assert_eq!(R!("getwd()")?, wd);
+/// }
+/// ```
+pub fn local_dir<P: AsRef<Path>>(path: P) -> Result<LocalGuard> {
+    let path = path.as_ref().to_string_lossy();
+    let old = eval_string_with_params("setwd(param.0)", &[&r!(&*path)])?;
+    Ok(LocalGuard::new(move || {
+        let _ = eval_string_with_params("setwd(param.0)", &[&old]);
+    }))
+}
+
+/// Set a locale category, such as `"LC_COLLATE"`, until the guard is dropped.
+///
+/// Equivalent to `withr::local_locale()`. Fails if the locale is not
+/// available on this system.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let collate = R!("Sys.getlocale('LC_COLLATE')")?;
+///     {
+///         let _locale = local_locale("LC_COLLATE", "C")?;
+///         assert_eq!(R!("sort(c('b', 'B', 'a'))")?, r!(["B", "a", "b"]));
+///     }
+///     assert_eq!(R!("Sys.getlocale('LC_COLLATE')")?, collate);
+///     assert!(local_locale("LC_COLLATE", "no_such_locale").is_err());
+/// }
+/// ```
+pub fn local_locale(category: &str, locale: &str) -> Result<LocalGuard> {
+    let category = r!(category);
+    let old = eval_string_with_params("Sys.getlocale(param.0)", &[&category])?;
+    let new = eval_string_with_params(
+        "suppressWarnings(Sys.setlocale(param.0, param.1))",
+        &[&category, &r!(locale)],
+    )?;
+    if new.as_str() == Some("") {
+        return Err(Error::Other(format!(
+            "can not set {} to locale {:?}",
+            category.as_str().unwrap_or_default(),
+            locale
+        )));
+    }
+    Ok(LocalGuard::new(move || {
+        let _ = eval_string_with_params("Sys.setlocale(param.0, param.1)", &[&category, &old]);
+    }))
+}
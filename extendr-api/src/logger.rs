@@ -0,0 +1,110 @@
+//! A [`log`](https://docs.rs/log/latest/log/) backend that writes to the R console.
+//!
+//! Call [`init`] once, for example from a package's `.onLoad()` hook,
+//! and messages from `log::info!`, `log::warn!` and friends, including
+//! those of dependencies, are printed in R. `error` and `warn` records go to
+//! `REprintf` (stderr) and the other levels to `Rprintf` (stdout).
+//!
+//! The most verbose level that is printed is read from the R option
+//! `extendr.log_level`, then the environment variable `EXTENDR_LOG`, and is
+//! `info` otherwise. Valid levels are `off`, `error`, `warn`, `info`, `debug`
+//! and `trace`, in any case.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! test! {
+//!     let _guard = local_option("extendr.log_level", "debug")?;
+//!     extendr_api::logger::init()?;
+//!     assert_eq!(log::max_level(), log::LevelFilter::Debug);
+//!     log::debug!("printed in the R console");
+//!     log::trace!("not printed");
+//! }
+//! ```
+//!
+//! The R console must only be written to from the R main thread, so records
+//! logged from other threads than the one that called [`init`] are dropped.
+use crate::*;
+use log::{LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use std::str::FromStr;
+use std::thread::ThreadId;
+
+/// The name of the R option with the log level.
+pub const LEVEL_OPTION: &str = "extendr.log_level";
+
+/// The name of the environment variable with the log level.
+pub const LEVEL_ENV_VAR: &str = "EXTENDR_LOG";
+
+static LOGGER: RLogger = RLogger;
+
+/// The thread that installed the logger, which is assumed to be R's main thread.
+static R_THREAD: OnceCell<ThreadId> = OnceCell::new();
+
+/// The [`Log`] implementation installed by [`init`].
+#[derive(Debug)]
+pub struct RLogger;
+
+impl Log for RLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) || R_THREAD.get() != Some(&std::thread::current().id())
+        {
+            return;
+        }
+        let line = format!(
+            "{} [{}] {}\n",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        // Interior NUL bytes would make the CString conversion fail.
+        let line = line.replace('\0', "\\0");
+        if record.level() <= log::Level::Warn {
+            print_r_error(line);
+        } else {
+            print_r_output(line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install [`RLogger`] as the global logger and set the log level.
+///
+/// Calling this again only re-reads the level, so it can be used to pick up
+/// a change to the `extendr.log_level` option.
+/// It is an error if another logger has already been installed, or if the
+/// level is not valid.
+pub fn init() -> Result<()> {
+    let level = level_from_settings()?;
+    if R_THREAD.get().is_none() {
+        log::set_logger(&LOGGER).map_err(|err| Error::Other(err.to_string()))?;
+        let _ = R_THREAD.set(std::thread::current().id());
+    }
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Set the most verbose level that is printed, overriding the settings
+/// read by [`init`].
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+fn level_from_settings() -> Result<LevelFilter> {
+    if let Some(level) = get_option::<String>(LEVEL_OPTION)? {
+        return parse_level(&level);
+    }
+    match std::env::var(LEVEL_ENV_VAR) {
+        Ok(level) => parse_level(&level),
+        Err(_) => Ok(LevelFilter::Info),
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter> {
+    LevelFilter::from_str(level.trim())
+        .map_err(|_| Error::Other(format!("invalid log level '{}'", level)))
+}
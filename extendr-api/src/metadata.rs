@@ -11,6 +11,14 @@ pub struct Arg {
     pub name: &'static str,
     pub arg_type: &'static str,
     pub default: Option<&'static str>,
+    /// The kind of R object the argument must be, checked by the R wrapper.
+    /// This is set by `#[extendr(check_args = true)]` to one of `"number"`,
+    /// `"flag"` or `"string"` for scalars, `"numeric"`, `"logical"`, `"character"`
+    /// or `"raw"` for vectors, or `"list"`, `"function"` or `"environment"`.
+    pub check: Option<&'static str>,
+    /// An R condition on the argument that must be `TRUE`, checked by the R wrapper.
+    /// This is set by `#[extendr(validate(x = "x > 0"))]`.
+    pub validate: Option<&'static str>,
 }
 
 /// Metadata function.
@@ -20,10 +28,42 @@ pub struct Func {
     pub rust_name: &'static str,
     pub mod_name: &'static str,
     pub r_name: &'static str,
+    /// The name of the C wrapper, `wrap__{mod_name}` for functions and
+    /// `wrap__{Type}__{mod_name}` for methods unless set with
+    /// `#[extendr(symbol = "...")]` or `#[extendr(symbol_prefix = "...")]`.
+    pub symbol: &'static str,
     pub args: Vec<Arg>,
     pub return_type: &'static str,
     pub func_ptr: *const u8,
     pub hidden: bool,
+    /// Called with `.External` rather than `.Call`.
+    pub external: bool,
+    /// Set for the instances of a generic function with `#[extendr(instances(...))]`.
+    pub dispatch: Option<Dispatch>,
+    /// Set for functions registered as S4 methods with `#[extendr(s4_method(...))]`.
+    pub s4_method: Option<S4Method>,
+}
+
+/// An S4 method of a generic function, which the R wrappers register with
+/// `methods::setMethod()`.
+#[derive(Debug, PartialEq)]
+pub struct S4Method {
+    /// The name of the generic function, e.g. `"show"`.
+    pub generic: &'static str,
+    /// The classes of the arguments that select the method, separated by commas.
+    pub signature: &'static str,
+}
+
+/// An instance of a generic function, which is called by an R function that
+/// dispatches on the type of one of its arguments.
+#[derive(Debug, PartialEq)]
+pub struct Dispatch {
+    /// The R name of the generic function.
+    pub generic: &'static str,
+    /// The argument whose type selects the instance.
+    pub arg: &'static str,
+    /// The type of that argument, as given by `typeof()` in R.
+    pub r_type: &'static str,
 }
 
 /// Metadata Impl.
@@ -46,6 +86,8 @@ pub struct Metadata {
 struct RArg {
     name: String,
     default: Option<&'static str>,
+    check: Option<&'static str>,
+    validate: Option<&'static str>,
 }
 
 impl RArg {
@@ -63,6 +105,34 @@ impl RArg {
             None => self.name.clone(),
         }
     }
+
+    /// An R condition that is true if the argument is valid, and what it must be.
+    fn to_check(&self) -> Option<(String, &'static str)> {
+        let x = &self.name;
+        let (condition, expected) = match self.check? {
+            "number" => (
+                format!("is.numeric({x}) && length({x}) == 1L"),
+                "a single number",
+            ),
+            "flag" => (
+                format!("is.logical({x}) && length({x}) == 1L"),
+                "TRUE or FALSE",
+            ),
+            "string" => (
+                format!("is.character({x}) && length({x}) == 1L"),
+                "a single string",
+            ),
+            "numeric" => (format!("is.numeric({x})"), "a numeric vector"),
+            "logical" => (format!("is.logical({x})"), "a logical vector"),
+            "character" => (format!("is.character({x})"), "a character vector"),
+            "raw" => (format!("is.raw({x})"), "a raw vector"),
+            "list" => (format!("is.list({x})"), "a list"),
+            "function" => (format!("is.function({x})"), "a function"),
+            "environment" => (format!("is.environment({x})"), "an environment"),
+            _ => return None,
+        };
+        Some((condition, expected))
+    }
 }
 
 impl From<&Arg> for RArg {
@@ -70,13 +140,14 @@ impl From<&Arg> for RArg {
         Self {
             name: sanitize_identifier(arg.name),
             default: arg.default,
+            check: arg.check,
+            validate: arg.validate,
         }
     }
 }
 
 impl From<Arg> for Robj {
     fn from(val: Arg) -> Self {
-        use crate as extendr_api;
         let mut result = List::from_values(&[r!(val.name), r!(val.arg_type)]);
         result
             .set_names(&["name", "arg_type"])
@@ -87,7 +158,6 @@ impl From<Arg> for Robj {
 
 impl From<Func> for Robj {
     fn from(val: Func) -> Self {
-        use crate as extendr_api;
         let mut result = List::from_values(&[
             r!(val.doc),
             r!(val.rust_name),
@@ -114,7 +184,6 @@ impl From<Func> for Robj {
 
 impl From<Impl> for Robj {
     fn from(val: Impl) -> Self {
-        use crate as extendr_api;
         let mut result = List::from_values(&[
             r!(val.doc),
             r!(val.name),
@@ -129,7 +198,6 @@ impl From<Impl> for Robj {
 
 impl From<Metadata> for Robj {
     fn from(val: Metadata) -> Self {
-        use crate as extendr_api;
         let mut result = List::from_values(&[
             r!(val.name),
             r!(List::from_values(val.functions)),
@@ -142,6 +210,53 @@ impl From<Metadata> for Robj {
     }
 }
 
+/// Add the functions and impls of a `use`d module to those of a module.
+///
+/// Items that are already present, e.g. when two submodules `use` the same
+/// module, are only added once, so that they are registered once.
+///
+/// Returns an error naming the functions that have the same R name as a
+/// different function that is already present, e.g. from another crate,
+/// as only one of them could be called from R, and likewise the impls with
+/// the same name but different methods. These are not added.
+#[doc(hidden)]
+pub fn merge(functions: &mut Vec<Func>, impls: &mut Vec<Impl>, module: Metadata) -> Result<()> {
+    let mut conflicts = Vec::new();
+    for func in module.functions {
+        if functions.iter().any(|f| f.func_ptr == func.func_ptr) {
+            continue;
+        }
+        match functions.iter().find(|f| f.r_name == func.r_name) {
+            Some(other) => conflicts.push(format!(
+                "`{}` (`{}` and `{}`)",
+                func.r_name, other.symbol, func.symbol
+            )),
+            None => functions.push(func),
+        }
+    }
+    for imp in module.impls {
+        match impls.iter().find(|i| i.name == imp.name) {
+            Some(other) if same_methods(other, &imp) => {}
+            Some(_) => conflicts.push(format!("impl `{}`", imp.name)),
+            None => impls.push(imp),
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Other(format!(
+            "items of module `{}` have the same R name as other items: {}",
+            module.name,
+            conflicts.join(", ")
+        )))
+    }
+}
+
+fn same_methods(a: &Impl, b: &Impl) -> bool {
+    let ptrs = |imp: &Impl| imp.methods.iter().map(|m| m.func_ptr).collect::<Vec<_>>();
+    ptrs(a) == ptrs(b)
+}
+
 fn write_doc(w: &mut Vec<u8>, doc: &str) -> std::io::Result<()> {
     if !doc.is_empty() {
         write!(w, "#'")?;
@@ -169,6 +284,53 @@ fn sanitize_identifier(name: &str) -> String {
     }
 }
 
+/// Open the body of a wrapper with a check of each argument that has one,
+/// so that wrong arguments give an R error before calling Rust.
+/// The kinds of all arguments are checked before their conditions.
+/// Returns true if the body needs to be closed with `}`.
+fn write_arg_checks(w: &mut Vec<u8>, r_args: &[RArg]) -> std::io::Result<bool> {
+    let checks = r_args.iter().filter_map(|arg| Some((arg, arg.to_check()?)));
+    let mut any = false;
+    for (arg, (condition, expected)) in checks {
+        if !any {
+            writeln!(w, "{{")?;
+            any = true;
+        }
+        writeln!(
+            w,
+            "  if (!({})) stop(\"`{}` must be {}, not \", class({})[[1L]], \" of length \", length({}))",
+            condition,
+            arg.name.trim_matches('`'),
+            expected,
+            arg.name,
+            arg.name
+        )?;
+    }
+    for arg in r_args {
+        let condition = match arg.validate {
+            Some(condition) => condition,
+            None => continue,
+        };
+        if !any {
+            writeln!(w, "{{")?;
+            any = true;
+        }
+        // The condition is quoted in the message as an R string.
+        let quoted = condition.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(
+            w,
+            "  if (!isTRUE({})) stop(\"`{}` must satisfy `{}`\")",
+            condition,
+            arg.name.trim_matches('`'),
+            quoted
+        )?;
+    }
+    if any {
+        write!(w, "  ")?;
+    }
+    Ok(any)
+}
+
 fn join_str(input: impl Iterator<Item = String>, sep: &str) -> String {
     input.collect::<Vec<String>>().join(sep)
 }
@@ -184,32 +346,37 @@ fn write_function_wrapper(
         return Ok(());
     }
 
-    write_doc(w, func.doc)?;
+    // The documentation of a generic function belongs to its dispatcher.
+    if func.dispatch.is_none() {
+        write_doc(w, func.doc)?;
+    }
+
+    if func.external {
+        return write_external_wrapper(w, func, package_name, use_symbols);
+    }
 
     let r_args: Vec<RArg> = func.args.iter().map(Into::into).collect();
     let actual_args = r_args.iter().map(|a| a.to_actual_arg());
     let formal_args = r_args.iter().map(|a| a.to_formal_arg());
 
+    write!(
+        w,
+        "{} <- function({}) ",
+        sanitize_identifier(func.r_name),
+        join_str(formal_args, ", ")
+    )?;
+    let checked = write_arg_checks(w, &r_args)?;
+
     if func.return_type == "()" {
-        write!(
-            w,
-            "{} <- function({}) invisible(.Call(",
-            sanitize_identifier(func.r_name),
-            join_str(formal_args, ", ")
-        )?;
+        write!(w, "invisible(.Call(")?;
     } else {
-        write!(
-            w,
-            "{} <- function({}) .Call(",
-            sanitize_identifier(func.r_name),
-            join_str(formal_args, ", ")
-        )?;
+        write!(w, ".Call(")?;
     }
 
     if use_symbols {
-        write!(w, "wrap__{}", func.mod_name)?;
+        write!(w, "{}", func.symbol)?;
     } else {
-        write!(w, "\"wrap__{}\"", func.mod_name)?;
+        write!(w, "\"{}\"", func.symbol)?;
     }
 
     if !func.args.is_empty() {
@@ -220,6 +387,121 @@ fn write_function_wrapper(
         write!(w, ", PACKAGE = \"{}\"", package_name)?;
     }
 
+    if func.return_type == "()" {
+        write!(w, "))")?;
+    } else {
+        write!(w, ")")?;
+    }
+
+    if checked {
+        write!(w, "\n}}")?;
+    }
+    writeln!(w, "\n")?;
+
+    Ok(())
+}
+
+/// Register the R function `r_name` as an S4 method. Classes of the signature
+/// that are not defined, such as those of extendr types, are declared as S3
+/// classes with `setOldClass()` so that S4 dispatch finds them.
+fn write_s4_method(w: &mut Vec<u8>, r_name: &str, method: &S4Method) -> std::io::Result<()> {
+    let classes: Vec<&str> = method.signature.split(',').map(str::trim).collect();
+    for class in &classes {
+        writeln!(
+            w,
+            "if (!methods::isClass(\"{class}\")) methods::setOldClass(\"{class}\")"
+        )?;
+    }
+    let signature = match classes.as_slice() {
+        [class] => format!("\"{}\"", class),
+        classes => format!(
+            "c({})",
+            join_str(classes.iter().map(|class| format!("\"{}\"", class)), ", ")
+        ),
+    };
+    writeln!(
+        w,
+        "methods::setMethod(\"{}\", {}, {})\n",
+        method.generic,
+        signature,
+        sanitize_identifier(r_name)
+    )
+}
+
+/// Generate the R function of a generic function, which calls the instance
+/// for the type of the dispatch argument. `instances` are the instances of
+/// `generic`, which all have the same arguments.
+fn write_dispatcher(w: &mut Vec<u8>, generic: &str, instances: &[&Func]) -> std::io::Result<()> {
+    let (first, dispatch) = match instances.first() {
+        Some(first) => (first, first.dispatch.as_ref().unwrap()),
+        None => return Ok(()),
+    };
+    write_doc(w, first.doc)?;
+
+    let r_args: Vec<RArg> = first.args.iter().map(Into::into).collect();
+    let formal_args = join_str(r_args.iter().map(|a| a.to_formal_arg()), ", ");
+    let actual_args = join_str(r_args.iter().map(|a| a.to_actual_arg()), ", ");
+    let arg = sanitize_identifier(dispatch.arg);
+    writeln!(
+        w,
+        "{} <- function({}) switch(typeof({}),",
+        sanitize_identifier(generic),
+        formal_args,
+        arg
+    )?;
+    let mut r_types = Vec::new();
+    for instance in instances {
+        let r_type = instance.dispatch.as_ref().unwrap().r_type;
+        r_types.push(r_type);
+        writeln!(
+            w,
+            "  {} = {}({}),",
+            r_type,
+            sanitize_identifier(instance.r_name),
+            actual_args
+        )?;
+    }
+    writeln!(
+        w,
+        "  stop(\"`{}` must be of type {}, not \", typeof({}))\n)\n",
+        dispatch.arg.trim_start_matches("r#"),
+        r_types.join(" or "),
+        arg
+    )?;
+    Ok(())
+}
+
+/// Generate a variadic wrapper for a `.External` function.
+fn write_external_wrapper(
+    w: &mut Vec<u8>,
+    func: &Func,
+    package_name: &str,
+    use_symbols: bool,
+) -> std::io::Result<()> {
+    if func.return_type == "()" {
+        write!(
+            w,
+            "{} <- function(...) invisible(.External(",
+            sanitize_identifier(func.r_name)
+        )?;
+    } else {
+        write!(
+            w,
+            "{} <- function(...) .External(",
+            sanitize_identifier(func.r_name)
+        )?;
+    }
+
+    if use_symbols {
+        write!(w, "{}, ...", func.symbol)?;
+    } else {
+        write!(
+            w,
+            "\"{}\", ..., PACKAGE = \"{}\"",
+            func.symbol, package_name
+        )?;
+    }
+
     if func.return_type == "()" {
         writeln!(w, "))\n")?;
     } else {
@@ -253,29 +535,26 @@ fn write_method_wrapper(
 
     // Both `class_name` and `func.name` should be processed
     // because they are exposed to R
+    write!(
+        w,
+        "{}${} <- function({}) ",
+        sanitize_identifier(class_name),
+        sanitize_identifier(func.r_name),
+        join_str(formal_args, ", ")
+    )?;
+    let checked = write_arg_checks(w, &r_args)?;
+
     if func.return_type == "()" {
-        write!(
-            w,
-            "{}${} <- function({}) invisible(.Call(",
-            sanitize_identifier(class_name),
-            sanitize_identifier(func.r_name),
-            join_str(formal_args, ", ")
-        )?;
+        write!(w, "invisible(.Call(")?;
     } else {
-        write!(
-            w,
-            "{}${} <- function({}) .Call(",
-            sanitize_identifier(class_name),
-            sanitize_identifier(func.r_name),
-            join_str(formal_args, ", ")
-        )?;
+        write!(w, ".Call(")?;
     }
 
-    // Here no processing is needed because of `wrap__` prefix
+    // The symbol is a C identifier, so no processing is needed
     if use_symbols {
-        write!(w, "wrap__{}__{}", class_name, func.mod_name)?;
+        write!(w, "{}", func.symbol)?;
     } else {
-        write!(w, "\"wrap__{}__{}\"", class_name, func.mod_name)?;
+        write!(w, "\"{}\"", func.symbol)?;
     }
 
     if actual_args.len() != 0 {
@@ -287,11 +566,16 @@ fn write_method_wrapper(
     }
 
     if func.return_type == "()" {
-        writeln!(w, "))\n")?;
+        write!(w, "))")?;
     } else {
-        writeln!(w, ")\n")?;
+        write!(w, ")")?;
     }
 
+    if checked {
+        write!(w, "\n}}")?;
+    }
+    writeln!(w, "\n")?;
+
     Ok(())
 }
 
@@ -374,9 +658,32 @@ impl Metadata {
             write_function_wrapper(&mut w, func, package_name, use_symbols)?;
         }
 
+        // Generic functions, in the order of their first instance.
+        let mut generics: Vec<(&str, Vec<&Func>)> = Vec::new();
+        for func in self.functions.iter().filter(|func| !func.hidden) {
+            if let Some(dispatch) = func.dispatch.as_ref() {
+                match generics
+                    .iter_mut()
+                    .find(|(name, _)| *name == dispatch.generic)
+                {
+                    Some((_, instances)) => instances.push(func),
+                    None => generics.push((dispatch.generic, vec![func])),
+                }
+            }
+        }
+        for (generic, instances) in &generics {
+            write_dispatcher(&mut w, generic, instances)?;
+        }
+
         for imp in &self.impls {
             write_impl_wrapper(&mut w, imp, package_name, use_symbols)?;
         }
+
+        for func in self.functions.iter().filter(|func| !func.hidden) {
+            if let Some(method) = func.s4_method.as_ref() {
+                write_s4_method(&mut w, func.r_name, method)?;
+            }
+        }
         unsafe { Ok(String::from_utf8_unchecked(w)) }
     }
 }
@@ -7,3 +7,7 @@ pub mod either;
 mod faer;
 #[cfg(feature = "ndarray")]
 pub mod ndarray;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "polars")]
+pub mod polars;
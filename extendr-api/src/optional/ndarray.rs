@@ -26,6 +26,7 @@ Specifically, extendr supports the following conversions:
         println!("This R matrix has shape {:?}", matrix.dim())
     }
     ```
+* [`Robj` → `ArrayViewD`], for R arrays with any number of dimensions. [`RArrayN`] can also be viewed as an `ArrayViewD`.
 * [`ArrayBase` → `Robj`](Robj#impl-TryFrom<ArrayBase<S%2C%20D>>-for-Robj), for when you want to return a reference to an [`ndarray`] Array from Rust back to R.
     ```rust
     use extendr_api::prelude::*;
@@ -114,6 +115,46 @@ macro_rules! make_array_view_2 {
         }
     };
 }
+macro_rules! make_array_view_d {
+    ($type: ty, $error_fn: expr) => {
+        impl<'a> TryFrom<&'_ Robj> for ArrayViewD<'a, $type> {
+            type Error = crate::Error;
+
+            /// Arrays of any dimension; vectors without `dim` are one-dimensional.
+            fn try_from(robj: &Robj) -> Result<Self> {
+                if let Some(v) = robj.as_typed_slice() {
+                    let dim: Vec<usize> = match robj.dim() {
+                        Some(dim) => dim.iter().map(|d| d.inner() as usize).collect(),
+                        None => vec![robj.len()],
+                    };
+                    // use fortran order.
+                    let shape = IxDyn(&dim).f();
+                    ArrayViewD::from_shape(shape, v).map_err(|err| Error::NDArrayShapeError(err))
+                } else {
+                    Err($error_fn(robj.clone()))
+                }
+            }
+        }
+
+        impl<'a> TryFrom<Robj> for ArrayViewD<'a, $type> {
+            type Error = crate::Error;
+
+            fn try_from(robj: Robj) -> Result<Self> {
+                Self::try_from(&robj)
+            }
+        }
+
+        impl<'a> TryFrom<&'a RArrayN<$type>> for ArrayViewD<'a, $type> {
+            type Error = crate::Error;
+
+            fn try_from(array: &'a RArrayN<$type>) -> Result<Self> {
+                ArrayViewD::from_shape(IxDyn(array.dim()).f(), array.data())
+                    .map_err(|err| Error::NDArrayShapeError(err))
+            }
+        }
+    };
+}
+
 make_array_view_1!(Rbool, Error::ExpectedLogical);
 make_array_view_1!(Rint, Error::ExpectedInteger);
 make_array_view_1!(i32, Error::ExpectedInteger);
@@ -136,6 +177,15 @@ make_array_view_2!(
 make_array_view_2!(c64, "Not a complex number matrix.", Error::ExpectedComplex);
 make_array_view_2!(Rstr, "Not a string matrix.", Error::ExpectedString);
 
+make_array_view_d!(Rbool, Error::ExpectedLogical);
+make_array_view_d!(Rint, Error::ExpectedInteger);
+make_array_view_d!(i32, Error::ExpectedInteger);
+make_array_view_d!(Rfloat, Error::ExpectedReal);
+make_array_view_d!(f64, Error::ExpectedReal);
+make_array_view_d!(Rcplx, Error::ExpectedComplex);
+make_array_view_d!(c64, Error::ExpectedComplex);
+make_array_view_d!(Rstr, Error::ExpectedString);
+
 impl<A, S, D> TryFrom<&ArrayBase<S, D>> for Robj
 where
     S: Data<Elem = A>,
@@ -327,4 +377,20 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_round_trip_dyn() {
+        test! {
+            let rval = R!("array(1:24, c(2, 3, 4))").unwrap();
+            let rust_arr = <ArrayViewD<i32>>::try_from(&rval).unwrap();
+            assert_eq!(rust_arr.shape(), &[2, 3, 4]);
+            assert_eq!(rust_arr[[1, 2, 3]], 24);
+            let r_arr: Robj = (&rust_arr).try_into().unwrap();
+            assert_eq!(rval, r_arr);
+
+            let array: RArrayN<i32> = rval.try_into().unwrap();
+            let view = <ArrayViewD<i32>>::try_from(&array).unwrap();
+            assert_eq!(view, rust_arr);
+        }
+    }
 }
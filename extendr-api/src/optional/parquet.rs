@@ -0,0 +1,441 @@
+//! Read and write data frames as [Parquet](https://parquet.apache.org/) files,
+//! using the [`parquet`](https://docs.rs/parquet) and
+//! [`arrow`](https://docs.rs/arrow) crates instead of the arrow R package.
+//!
+//! The columns are converted as follows:
+//!
+//! | R                  | Parquet (Arrow type)                                  |
+//! |--------------------|-------------------------------------------------------|
+//! | logical            | `Boolean`                                             |
+//! | integer            | `Int32`, read from `Int8`, `Int16`, `UInt8`, `UInt16` |
+//! | double             | `Float64`, read from `Float32`, `Int64`, `UInt32`, `UInt64` |
+//! | character          | `Utf8`, read from `LargeUtf8`                         |
+//! | factor             | `Dictionary` of `Utf8`                                |
+//! | `Date`             | `Date32`                                              |
+//! | `POSIXct`          | `Timestamp` in microseconds, with the time zone       |
+//!
+//! `NA` is a null value. Other classes are written as their underlying type.
+//! Row names are not written.
+//!
+//! The levels of a factor, including unused ones, are also stored in the
+//! metadata of its field, so that they are read back in the same order.
+//! Otherwise the values of the dictionary are the levels.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use extendr_api::optional::parquet::{read_parquet, write_parquet};
+//! test! {
+//!     let df = R!("data.frame(x = c(1.5, NA), y = factor(c('a', 'b'), levels = c('b', 'c', 'a')))")?;
+//!     let path = std::env::temp_dir().join("extendr-doc-example.parquet");
+//!     write_parquet(&Dataframe::<()>::try_from(&df)?, &path)?;
+//!     assert_eq!(read_parquet(&path)?, df);
+//! #   std::fs::remove_file(&path).unwrap();
+//! }
+//! ```
+use crate::*;
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, Date32Array, DictionaryArray, Float64Array, Int32Array,
+    RecordBatch, RecordBatchOptions, StringArray, TimestampMicrosecondArray,
+};
+use arrow_schema::{DataType, Field, FieldRef, Schema, TimeUnit};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::file::reader::ChunkReader;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+// The field metadata key of the levels of a factor column.
+const LEVELS_KEY: &str = "extendr.levels";
+
+fn parquet_error<E: std::fmt::Display>(error: E) -> Error {
+    Error::Other(format!("Parquet input or output failed: {}", error))
+}
+
+// Encode levels as `<length>:<level>` for each level, which is unambiguous
+// whatever the levels contain.
+fn encode_levels<'a>(levels: impl Iterator<Item = &'a str>) -> String {
+    levels
+        .map(|level| format!("{}:{}", level.len(), level))
+        .collect()
+}
+
+fn decode_levels(mut encoded: &str) -> Option<Vec<String>> {
+    let mut levels = Vec::new();
+    while !encoded.is_empty() {
+        let (length, rest) = encoded.split_once(':')?;
+        let length: usize = length.parse().ok()?;
+        levels.push(rest.get(..length)?.to_string());
+        encoded = &rest[length..];
+    }
+    Some(levels)
+}
+
+/// Write `dataframe` to the Parquet file at `path`, replacing it if it exists.
+pub fn write_parquet<T, P: AsRef<Path>>(dataframe: &Dataframe<T>, path: P) -> Result<()> {
+    let file = std::fs::File::create(path.as_ref())
+        .map_err(|_| Error::Other(format!("could not create file {:?}", path.as_ref())))?;
+    write_parquet_to(dataframe, file)
+}
+
+/// Write `dataframe` in the Parquet format to `writer`.
+pub fn write_parquet_to<T, W>(dataframe: &Dataframe<T>, writer: W) -> Result<()>
+where
+    W: std::io::Write + Send,
+{
+    let list = dataframe
+        .as_robj()
+        .as_list()
+        .ok_or_else(|| Error::ExpectedList(dataframe.as_robj().clone()))?;
+    let mut fields = Vec::with_capacity(list.len());
+    let mut arrays = Vec::with_capacity(list.len());
+    for (name, column) in list.iter() {
+        let array = to_arrow(&column)?;
+        let mut field = Field::new(name, array.data_type().clone(), true);
+        if column.is_factor() {
+            let levels = encode_levels(column.levels().into_iter().flatten());
+            field = field.with_metadata(HashMap::from([(LEVELS_KEY.to_string(), levels)]));
+        }
+        fields.push(field);
+        arrays.push(array);
+    }
+    let rows = arrays.first().map_or(0, |array| array.len());
+    let schema = Arc::new(Schema::new(fields));
+    let options = RecordBatchOptions::new().with_row_count(Some(rows));
+    let batch = RecordBatch::try_new_with_options(schema.clone(), arrays, &options)
+        .map_err(parquet_error)?;
+    let mut writer = ArrowWriter::try_new(writer, schema, None).map_err(parquet_error)?;
+    writer.write(&batch).map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+// Convert a column of a data frame to an Arrow array.
+fn to_arrow(column: &Robj) -> Result<ArrayRef> {
+    if column.is_factor() {
+        let codes = column.as_integer_slice().unwrap_or_default();
+        let keys = Int32Array::from_iter(
+            codes
+                .iter()
+                .map(|&code| (code != i32::MIN).then_some(code - 1)),
+        );
+        let levels = StringArray::from_iter_values(column.levels().into_iter().flatten());
+        let dictionary =
+            DictionaryArray::<Int32Type>::try_new(keys, Arc::new(levels)).map_err(parquet_error)?;
+        return Ok(Arc::new(dictionary));
+    }
+    if column.inherits("Date") || column.inherits("POSIXct") {
+        let values: Vec<Option<f64>> = match column.sexptype() {
+            SEXPTYPE::INTSXP => column
+                .as_integer_slice()
+                .unwrap()
+                .iter()
+                .map(|&value| (value != i32::MIN).then_some(value as f64))
+                .collect(),
+            SEXPTYPE::REALSXP => column
+                .as_real_slice()
+                .unwrap()
+                .iter()
+                .map(|&value| (!value.is_nan()).then_some(value))
+                .collect(),
+            _ => return Err(Error::ExpectedNumeric(column.clone())),
+        };
+        if column.inherits("Date") {
+            let days = values
+                .iter()
+                .map(|value| value.map(|days| days.floor() as i32));
+            return Ok(Arc::new(Date32Array::from_iter(days)));
+        }
+        let micros = values
+            .iter()
+            .map(|value| value.map(|seconds| (seconds * 1e6).round() as i64));
+        let array = TimestampMicrosecondArray::from_iter(micros);
+        let time_zone = column
+            .get_attrib("tzone")
+            .and_then(|tzone| tzone.as_str_iter()?.next())
+            .filter(|tzone| !tzone.is_empty() && !tzone.is_na())
+            .map(String::from);
+        return Ok(Arc::new(array.with_timezone_opt(time_zone)));
+    }
+    match column.sexptype() {
+        SEXPTYPE::LGLSXP => {
+            let values = column.as_logical_slice().unwrap();
+            Ok(Arc::new(BooleanArray::from_iter(
+                values
+                    .iter()
+                    .map(|value| (!value.is_na()).then_some(value.is_true())),
+            )))
+        }
+        SEXPTYPE::INTSXP => {
+            let values = column.as_integer_slice().unwrap();
+            Ok(Arc::new(Int32Array::from_iter(
+                values
+                    .iter()
+                    .map(|&value| (value != i32::MIN).then_some(value)),
+            )))
+        }
+        SEXPTYPE::REALSXP => {
+            let values = column.as_real_slice().unwrap();
+            Ok(Arc::new(Float64Array::from_iter(
+                values
+                    .iter()
+                    .map(|&value| (!value.is_na()).then_some(value)),
+            )))
+        }
+        SEXPTYPE::STRSXP => {
+            let values = column.as_str_iter().unwrap();
+            Ok(Arc::new(StringArray::from_iter(
+                values.map(|value| (!value.is_na()).then_some(value)),
+            )))
+        }
+        _ => Err(Error::Other(format!(
+            "can not write a column of type {:?} to Parquet",
+            column.rtype()
+        ))),
+    }
+}
+
+/// Read the Parquet file at `path` into a data frame.
+pub fn read_parquet<P: AsRef<Path>>(path: P) -> Result<Robj> {
+    let file = std::fs::File::open(path.as_ref())
+        .map_err(|_| Error::Other(format!("could not open file {:?}", path.as_ref())))?;
+    read_parquet_from(file)
+}
+
+/// Read Parquet data from `reader`, e.g. a file, into a data frame.
+pub fn read_parquet_from<R: ChunkReader + 'static>(reader: R) -> Result<Robj> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(reader).map_err(parquet_error)?;
+    let schema = builder.schema().clone();
+    let mut columns = schema
+        .fields()
+        .iter()
+        .map(ColumnBuilder::new)
+        .collect::<Result<Vec<_>>>()?;
+    let mut rows = 0;
+    for batch in builder.build().map_err(parquet_error)? {
+        let batch = batch.map_err(parquet_error)?;
+        rows += batch.num_rows();
+        for (column, array) in columns.iter_mut().zip(batch.columns()) {
+            column.append(array.as_ref());
+        }
+    }
+    let names = schema.fields().iter().map(|field| field.name().as_str());
+    let values = columns
+        .into_iter()
+        .map(ColumnBuilder::finish)
+        .collect::<Result<Vec<_>>>()?;
+    let mut dataframe = List::from_names_and_values(names.collect::<Vec<_>>(), values)?;
+    dataframe.set_class(["data.frame"])?;
+    dataframe.set_attrib(
+        row_names_symbol(),
+        wrapper::dataframe::compact_row_names(rows),
+    )?;
+    Ok(dataframe.into())
+}
+
+// The values of a column, collected from the record batches.
+enum ColumnBuilder {
+    Logicals(Vec<Option<bool>>),
+    Integers(Vec<Option<i32>>),
+    Doubles(Vec<Option<f64>>),
+    Strings(Vec<Option<String>>),
+    Factor {
+        levels: Vec<String>,
+        codes: Vec<Option<i32>>,
+        lookup: HashMap<String, i32>,
+    },
+    Date(Vec<Option<f64>>),
+    Timestamp(Vec<Option<f64>>, Option<String>),
+}
+
+impl ColumnBuilder {
+    fn new(field: &FieldRef) -> Result<Self> {
+        use DataType::*;
+        let data_type = field.data_type();
+        match data_type {
+            Boolean => Ok(Self::Logicals(Vec::new())),
+            Int8 | Int16 | Int32 | UInt8 | UInt16 => Ok(Self::Integers(Vec::new())),
+            Int64 | UInt32 | UInt64 | Float32 | Float64 => Ok(Self::Doubles(Vec::new())),
+            Utf8 | LargeUtf8 => Ok(Self::Strings(Vec::new())),
+            Dictionary(_, values) if matches!(**values, Utf8 | LargeUtf8) => {
+                // Start with the levels written by `write_parquet`, if any.
+                let levels = field
+                    .metadata()
+                    .get(LEVELS_KEY)
+                    .and_then(|levels| decode_levels(levels))
+                    .unwrap_or_default();
+                let lookup = levels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, level)| (level.clone(), i as i32 + 1))
+                    .collect();
+                Ok(Self::Factor {
+                    levels,
+                    codes: Vec::new(),
+                    lookup,
+                })
+            }
+            Date32 => Ok(Self::Date(Vec::new())),
+            Timestamp(_, time_zone) => Ok(Self::Timestamp(
+                Vec::new(),
+                time_zone.as_ref().map(|tz| tz.to_string()),
+            )),
+            _ => Err(Error::Other(format!(
+                "can not read a Parquet column of type {}",
+                data_type
+            ))),
+        }
+    }
+
+    // Append the values of `array`, which has the type given to `new`.
+    fn append(&mut self, array: &dyn Array) {
+        match self {
+            Self::Logicals(values) => values.extend(array.as_boolean().iter()),
+            Self::Integers(values) => match array.data_type() {
+                DataType::Int8 => values.extend(integers::<Int8Type>(array)),
+                DataType::Int16 => values.extend(integers::<Int16Type>(array)),
+                DataType::UInt8 => values.extend(integers::<UInt8Type>(array)),
+                DataType::UInt16 => values.extend(integers::<UInt16Type>(array)),
+                _ => values.extend(array.as_primitive::<Int32Type>().iter()),
+            },
+            Self::Doubles(values) => match array.data_type() {
+                DataType::Int64 => values.extend(doubles::<Int64Type>(array, 1.)),
+                DataType::UInt32 => values.extend(doubles::<UInt32Type>(array, 1.)),
+                DataType::UInt64 => values.extend(doubles::<UInt64Type>(array, 1.)),
+                DataType::Float32 => values.extend(doubles::<Float32Type>(array, 1.)),
+                _ => values.extend(array.as_primitive::<Float64Type>().iter()),
+            },
+            Self::Strings(values) => match array.data_type() {
+                DataType::LargeUtf8 => values.extend(
+                    array
+                        .as_string::<i64>()
+                        .iter()
+                        .map(|value| value.map(String::from)),
+                ),
+                _ => values.extend(
+                    array
+                        .as_string::<i32>()
+                        .iter()
+                        .map(|value| value.map(String::from)),
+                ),
+            },
+            Self::Factor {
+                levels,
+                codes,
+                lookup,
+            } => {
+                let dictionary = array.as_any_dictionary();
+                let words: Vec<Option<&str>> = match dictionary.values().data_type() {
+                    DataType::LargeUtf8 => dictionary.values().as_string::<i64>().iter().collect(),
+                    _ => dictionary.values().as_string::<i32>().iter().collect(),
+                };
+                // The codes of the words of this batch's dictionary in the levels.
+                let word_codes: Vec<Option<i32>> = words
+                    .into_iter()
+                    .map(|word| {
+                        let word = word?;
+                        let code = *lookup.entry(word.to_string()).or_insert_with(|| {
+                            levels.push(word.to_string());
+                            levels.len() as i32
+                        });
+                        Some(code)
+                    })
+                    .collect();
+                let keys = dictionary.keys();
+                codes.extend(dictionary.normalized_keys().into_iter().enumerate().map(
+                    |(i, key)| {
+                        if keys.is_null(i) {
+                            None
+                        } else {
+                            word_codes.get(key).copied().flatten()
+                        }
+                    },
+                ));
+            }
+            Self::Date(values) => values.extend(doubles::<Date32Type>(array, 1.)),
+            Self::Timestamp(values, _) => match array.data_type() {
+                DataType::Timestamp(TimeUnit::Second, _) => {
+                    values.extend(doubles::<TimestampSecondType>(array, 1.))
+                }
+                DataType::Timestamp(TimeUnit::Millisecond, _) => {
+                    values.extend(doubles::<TimestampMillisecondType>(array, 1e3))
+                }
+                DataType::Timestamp(TimeUnit::Microsecond, _) => {
+                    values.extend(doubles::<TimestampMicrosecondType>(array, 1e6))
+                }
+                _ => values.extend(doubles::<TimestampNanosecondType>(array, 1e9)),
+            },
+        }
+    }
+
+    fn finish(self) -> Result<Robj> {
+        Ok(match self {
+            Self::Logicals(values) => values.into_iter().collect_robj(),
+            Self::Integers(values) => values.into_iter().collect_robj(),
+            Self::Doubles(values) => values.into_iter().collect_robj(),
+            Self::Strings(values) => values.into_iter().collect_robj(),
+            Self::Factor { levels, codes, .. } => {
+                let mut factor = codes.into_iter().collect_robj();
+                factor.set_attrib(levels_symbol(), levels.into_iter().collect_robj())?;
+                factor.set_class(["factor"])?;
+                factor
+            }
+            Self::Date(values) => {
+                let mut date = values.into_iter().collect_robj();
+                date.set_class(["Date"])?;
+                date
+            }
+            Self::Timestamp(values, time_zone) => {
+                let mut time = values.into_iter().collect_robj();
+                time.set_class(["POSIXct", "POSIXt"])?;
+                time.set_attrib("tzone", time_zone.unwrap_or_default())?;
+                time
+            }
+        })
+    }
+}
+
+fn integers<T>(array: &dyn Array) -> impl Iterator<Item = Option<i32>> + '_
+where
+    T: ArrowPrimitiveType,
+    T::Native: Into<i32>,
+{
+    array
+        .as_primitive::<T>()
+        .iter()
+        .map(|value| value.map(Into::into))
+}
+
+// The values divided by `scale`, e.g. to convert milliseconds to seconds.
+fn doubles<T>(array: &dyn Array, scale: f64) -> impl Iterator<Item = Option<f64>> + '_
+where
+    T: ArrowPrimitiveType,
+    T::Native: ToF64,
+{
+    array
+        .as_primitive::<T>()
+        .iter()
+        .map(move |value| value.map(|value| value.to_f64() / scale))
+}
+
+// The native Arrow types read as doubles. 64 bit integers may lose
+// precision, as R has no 64 bit integers.
+trait ToF64 {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_to_f64 {
+    ($($t: ty),*) => {
+        $(
+            impl ToF64 for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_to_f64!(i32, i64, u32, u64, f32);
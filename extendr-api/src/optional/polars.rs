@@ -0,0 +1,375 @@
+/*!
+Conversions between R data frames and [`polars`](https://docs.rs/polars/latest/polars/) data frames.
+
+To enable these conversions, you must first enable the `polars` feature for extendr:
+```toml
+[dependencies]
+extendr-api = { version = "0.7", features = ["polars"] }
+```
+
+A [`DataFrame`] is converted with [`Robj::try_from`], and an R data frame with
+`DataFrame::try_from(&robj)`:
+```rust
+use extendr_api::prelude::*;
+use polars::prelude::DataFrame;
+
+#[extendr]
+fn head_rows(df: Robj, n: usize) -> Result<Robj> {
+    let df = DataFrame::try_from(&df)?;
+    Robj::try_from(df.head(Some(n)))
+}
+```
+
+Each column is converted as follows, with `NA` as null:
+
+| R         | polars                                                          |
+|-----------|-----------------------------------------------------------------|
+| logical   | `Boolean`                                                       |
+| integer   | `Int32`, from `Int8`, `Int16`, `UInt8`, `UInt16`                |
+| double    | `Float64`, from `Float32`, `Int64`, `UInt32`, `UInt64`          |
+| character | `String`                                                        |
+| factor    | `Categorical`, from `Categorical` and `Enum`                    |
+| `Date`    | `Date`                                                          |
+| `POSIXct` | `Datetime` in microseconds with the time zone, from any unit    |
+
+The levels of a factor are the categories of the polars column, in the same
+order and including unused ones. 64 bit integers lose precision beyond 2^53,
+as R has no 64 bit integers.
+Other classes are converted as their underlying type, and other polars
+types are an error. Row names are not converted.
+*/
+use crate::*;
+use polars::prelude::{DataFrame, DataType, NamedFrom, PolarsError, Series, TimeUnit};
+use std::collections::HashMap;
+
+fn polars_error(error: PolarsError) -> Error {
+    Error::Other(format!("polars conversion failed: {}", error))
+}
+
+impl TryFrom<&Robj> for DataFrame {
+    type Error = Error;
+
+    /// Convert an R data frame to a polars data frame.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if !robj.inherits("data.frame") {
+            return Err(Error::ExpectedDataframe(robj.clone()));
+        }
+        let list = robj
+            .as_list()
+            .ok_or_else(|| Error::ExpectedList(robj.clone()))?;
+        let columns = list
+            .iter()
+            .map(|(name, column)| to_series(name, &column))
+            .collect::<Result<Vec<_>>>()?;
+        DataFrame::new(columns).map_err(polars_error)
+    }
+}
+
+impl TryFrom<Robj> for DataFrame {
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl TryFrom<&DataFrame> for Robj {
+    type Error = Error;
+
+    /// Convert a polars data frame to an R data frame.
+    fn try_from(dataframe: &DataFrame) -> Result<Self> {
+        let names = dataframe
+            .get_column_names()
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        let columns = dataframe
+            .get_columns()
+            .iter()
+            .map(from_series)
+            .collect::<Result<Vec<_>>>()?;
+        let mut list = List::from_names_and_values(&names, columns)?;
+        list.set_class(["data.frame"])?;
+        list.set_attrib(
+            row_names_symbol(),
+            wrapper::dataframe::compact_row_names(dataframe.height()),
+        )?;
+        Ok(list.into())
+    }
+}
+
+impl TryFrom<DataFrame> for Robj {
+    type Error = Error;
+
+    fn try_from(dataframe: DataFrame) -> Result<Self> {
+        Self::try_from(&dataframe)
+    }
+}
+
+// The values of a `Date` or `POSIXct` column, which may be integers or doubles.
+fn time_values(column: &Robj) -> Result<Vec<Option<f64>>> {
+    match column.sexptype() {
+        SEXPTYPE::INTSXP => Ok(column
+            .as_integer_slice()
+            .unwrap()
+            .iter()
+            .map(|&value| (!value.is_na()).then_some(value as f64))
+            .collect()),
+        SEXPTYPE::REALSXP => Ok(column
+            .as_real_slice()
+            .unwrap()
+            .iter()
+            .map(|&value| (!value.is_nan()).then_some(value))
+            .collect()),
+        _ => Err(Error::ExpectedNumeric(column.clone())),
+    }
+}
+
+fn to_series(name: &str, column: &Robj) -> Result<Series> {
+    let cast = |series: Series, data_type: &DataType| series.cast(data_type).map_err(polars_error);
+    if column.is_factor() {
+        let levels = column.levels().unwrap().collect::<Vec<_>>();
+        let values = column
+            .as_integer_slice()
+            .unwrap()
+            .iter()
+            .map(|&code| {
+                if code.is_na() {
+                    return Ok(None);
+                }
+                let level = usize::try_from(code - 1).ok().and_then(|i| levels.get(i));
+                match level {
+                    Some(&level) => Ok(Some(level)),
+                    None => Err(Error::Other(format!(
+                        "the factor column {:?} has the code {} without a level",
+                        name, code
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // The categories are numbered in the order they first appear, so
+        // put the levels first, to keep their order and the unused ones.
+        let n_levels = levels.len();
+        let rows = values.len();
+        let series = Series::new(
+            name.into(),
+            levels
+                .into_iter()
+                .map(Some)
+                .chain(values)
+                .collect::<Vec<_>>(),
+        );
+        let series = cast(series, &DataType::Categorical(None, Default::default()))?;
+        return Ok(series.slice(n_levels as i64, rows));
+    }
+    if column.inherits("Date") {
+        let days = time_values(column)?
+            .into_iter()
+            .map(|value| value.map(|days| days.floor() as i32))
+            .collect::<Vec<_>>();
+        return cast(Series::new(name.into(), days), &DataType::Date);
+    }
+    if column.inherits("POSIXct") {
+        let micros = time_values(column)?
+            .into_iter()
+            .map(|value| value.map(|seconds| (seconds * 1e6).round() as i64))
+            .collect::<Vec<_>>();
+        let time_zone = column
+            .get_attrib("tzone")
+            .and_then(|tzone| tzone.as_str_iter()?.next())
+            .filter(|tzone| !tzone.is_empty() && !tzone.is_na())
+            .map(|tzone| tzone.into());
+        let data_type = DataType::Datetime(TimeUnit::Microseconds, time_zone);
+        return cast(Series::new(name.into(), micros), &data_type);
+    }
+    let series = match column.sexptype() {
+        SEXPTYPE::LGLSXP => {
+            let values = column.as_logical_slice().unwrap();
+            let values = values
+                .iter()
+                .map(|value| (!value.is_na()).then(|| value.is_true()))
+                .collect::<Vec<_>>();
+            Series::new(name.into(), values)
+        }
+        SEXPTYPE::INTSXP => {
+            let values = column.as_integer_slice().unwrap();
+            let values = values
+                .iter()
+                .map(|&value| (!value.is_na()).then_some(value))
+                .collect::<Vec<_>>();
+            Series::new(name.into(), values)
+        }
+        SEXPTYPE::REALSXP => {
+            let values = column.as_real_slice().unwrap();
+            let values = values
+                .iter()
+                .map(|&value| (!value.is_na()).then_some(value))
+                .collect::<Vec<_>>();
+            Series::new(name.into(), values)
+        }
+        SEXPTYPE::STRSXP => {
+            let values = column
+                .as_str_iter()
+                .unwrap()
+                .map(|value| (!value.is_na()).then_some(value))
+                .collect::<Vec<_>>();
+            Series::new(name.into(), values)
+        }
+        _ => {
+            return Err(Error::Other(format!(
+                "can not convert the column {:?} of type {:?} to polars",
+                name,
+                column.rtype()
+            )))
+        }
+    };
+    Ok(series)
+}
+
+fn from_series(series: &Series) -> Result<Robj> {
+    let cast = |data_type: &DataType| series.cast(data_type).map_err(polars_error);
+    let robj = match series.dtype() {
+        DataType::Boolean => series
+            .bool()
+            .map_err(polars_error)?
+            .into_iter()
+            .collect_robj(),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::UInt8 | DataType::UInt16 => {
+            cast(&DataType::Int32)?
+                .i32()
+                .map_err(polars_error)?
+                .into_iter()
+                .collect_robj()
+        }
+        DataType::Int64
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64 => cast(&DataType::Float64)?
+            .f64()
+            .map_err(polars_error)?
+            .into_iter()
+            .collect_robj(),
+        DataType::String => series
+            .str()
+            .map_err(polars_error)?
+            .into_iter()
+            .collect_robj(),
+        DataType::Categorical(..) | DataType::Enum(..) => {
+            // The levels are the categories of the reverse mapping, or of the
+            // `Enum`, including unused ones.
+            let categorical = series.categorical().map_err(polars_error)?;
+            let levels = categorical
+                .get_rev_map()
+                .get_categories()
+                .values_iter()
+                .collect::<Vec<_>>();
+            let lookup = levels
+                .iter()
+                .enumerate()
+                .map(|(i, &level)| (level, i as i32 + 1))
+                .collect::<HashMap<_, _>>();
+            let strings = cast(&DataType::String)?;
+            let codes = strings
+                .str()
+                .map_err(polars_error)?
+                .into_iter()
+                .map(|value| value.and_then(|value| lookup.get(value).copied()))
+                .collect::<Vec<_>>();
+            let mut factor = codes.into_iter().collect_robj();
+            factor.set_attrib(levels_symbol(), levels.into_iter().collect_robj())?;
+            factor.set_class(["factor"])?;
+            factor
+        }
+        DataType::Date => {
+            let days = cast(&DataType::Int32)?;
+            let mut date = days
+                .i32()
+                .map_err(polars_error)?
+                .into_iter()
+                .map(|value| value.map(f64::from))
+                .collect_robj();
+            date.set_class(["Date"])?;
+            date
+        }
+        DataType::Datetime(unit, time_zone) => {
+            let per_second = match unit {
+                TimeUnit::Nanoseconds => 1e9,
+                TimeUnit::Microseconds => 1e6,
+                TimeUnit::Milliseconds => 1e3,
+            };
+            let times = cast(&DataType::Int64)?;
+            let mut time = times
+                .i64()
+                .map_err(polars_error)?
+                .into_iter()
+                .map(|value| value.map(|value| value as f64 / per_second))
+                .collect_robj();
+            time.set_class(["POSIXct", "POSIXt"])?;
+            let time_zone = time_zone.as_ref().map_or("", |tz| tz.as_str());
+            time.set_attrib("tzone", time_zone)?;
+            time
+        }
+        data_type => {
+            return Err(Error::Other(format!(
+                "can not convert the polars column {:?} of type {} to R",
+                series.name().to_string(),
+                data_type
+            )))
+        }
+    };
+    Ok(robj)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate as extendr_api;
+
+    #[test]
+    fn test_polars_round_trip() {
+        test! {
+            let df = R!(r#"data.frame(
+                lgl = c(TRUE, NA, FALSE),
+                int = c(1L, NA, 3L),
+                dbl = c(1.5, NA, -2),
+                chr = c("a", NA, "c"),
+                fct = factor(c("x", NA, "y"), levels = c("y", "unused", "x")),
+                date = as.Date(c("2024-01-31", NA, "1960-05-01")),
+                time = as.POSIXct(c(0, NA, 1.5), tz = "UTC", origin = "1970-01-01")
+            )"#)?;
+            let polars_df = DataFrame::try_from(&df)?;
+            assert_eq!(polars_df.shape(), (3, 7));
+            assert_eq!(polars_df.column("int").unwrap().null_count(), 1);
+            assert!(matches!(polars_df.column("fct").unwrap().dtype(), DataType::Categorical(..)));
+            assert_eq!(polars_df.column("fct").unwrap().null_count(), 1);
+            assert_eq!(
+                polars_df.column("time").unwrap().dtype(),
+                &DataType::Datetime(TimeUnit::Microseconds, Some("UTC".into()))
+            );
+
+            assert_eq!(Robj::try_from(&polars_df)?, df);
+        }
+    }
+
+    #[test]
+    fn test_polars_types() {
+        test! {
+            let polars_df = DataFrame::new(vec![
+                Series::new("small".into(), [1_i8, 2, 3]),
+                Series::new("big".into(), [Some(1_i64 << 40), None, Some(-1)]),
+                Series::new("float".into(), [0.5_f32, 1., 2.]),
+            ])
+            .unwrap();
+            let df = Robj::try_from(polars_df)?;
+            assert_eq!(df, R!("data.frame(small = 1:3, big = c(2^40, NA, -1), float = c(0.5, 1, 2))")?);
+
+            assert!(DataFrame::try_from(r!([1, 2])).is_err());
+            assert!(DataFrame::try_from(R!("data.frame(x = I(list(1, 2)))")?).is_err());
+
+            // A factor code without a level is an error, not a panic.
+            let bad = R!("structure(list(x = structure(c(1L, 3L), levels = c('a', 'b'), class = 'factor')), class = 'data.frame', row.names = 1:2)")?;
+            assert!(DataFrame::try_from(&bad).is_err());
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Access to R's global options, as set by `options()` and read by `getOption()`.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! test! {
+//!     let digits: Option<i32> = get_option("digits")?;
+//!     assert_eq!(digits, Some(7));
+//!     {
+//!         let _guard = local_option("digits", 3)?;
+//!         assert_eq!(get_option::<i32>("digits")?, Some(3));
+//!     }
+//!     assert_eq!(get_option::<i32>("digits")?, Some(7));
+//! }
+//! ```
+use crate as extendr_api;
+use crate::*;
+
+/// Get the value of an option, or `None` if it is not set.
+///
+/// Equivalent to `getOption(name)` in R.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_eq!(get_option::<String>("OutDec")?, Some(".".to_string()));
+///     assert_eq!(get_option::<Robj>("extendr.no.such.option")?, None);
+///     assert!(get_option::<i32>("OutDec").is_err());
+/// }
+/// ```
+pub fn get_option<T>(name: &str) -> Result<Option<T>>
+where
+    T: TryFrom<Robj>,
+    Error: From<T::Error>,
+{
+    let value = call!("getOption", name)?;
+    if value.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some(T::try_from(value)?))
+    }
+}
+
+/// Set an option and return its previous value, which is `NULL` if it was not set.
+///
+/// Setting an option to `NULL` removes it.
+///
+/// Equivalent to `options(name = value)` in R.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_eq!(set_option("extendr.test.set", "a")?, r!(NULL));
+///     assert_eq!(set_option("extendr.test.set", r!(NULL))?, r!("a"));
+///     assert_eq!(get_option::<Robj>("extendr.test.set")?, None);
+/// }
+/// ```
+pub fn set_option<V: Into<Robj>>(name: &str, value: V) -> Result<Robj> {
+    let options = global_function(sym!(options))?;
+    let old = options.call(Pairlist::from_pairs([(name, value.into())]))?;
+    List::try_from(old)?.elt(0)
+}
+
+/// Set an option until the returned guard is dropped, then restore the
+/// previous value.
+///
+/// This is the equivalent of `withr::local_options()` and is useful to
+/// override an option for the duration of a function, including early
+/// returns with `?`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     fn format_pi() -> Result<Robj> {
+///         let _guard = local_option("digits", 3)?;
+///         call!("format", std::f64::consts::PI)
+///     }
+///     assert_eq!(format_pi()?, r!("3.14"));
+///     assert_eq!(get_option::<i32>("digits")?, Some(7));
+/// }
+/// ```
+pub fn local_option<V: Into<Robj>>(name: &str, value: V) -> Result<OptionGuard> {
+    let old = set_option(name, value)?;
+    Ok(OptionGuard {
+        name: name.to_string(),
+        old,
+    })
+}
+
+/// Restores an option to its previous value when dropped.
+///
+/// See [`local_option`].
+#[must_use = "the option is restored when the guard is dropped"]
+#[derive(Debug)]
+pub struct OptionGuard {
+    name: String,
+    old: Robj,
+}
+
+impl Drop for OptionGuard {
+    fn drop(&mut self) {
+        // Errors can not be reported from drop.
+        let _ = set_option(&self.name, self.old.clone());
+    }
+}
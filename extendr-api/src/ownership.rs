@@ -54,6 +54,9 @@ static OWNERSHIP: Lazy<Mutex<Ownership>> = Lazy::new(|| Mutex::new(Ownership::ne
 pub(crate) unsafe fn protect(sexp: SEXP) {
     let mut own = OWNERSHIP.lock().expect("protect failed");
     own.protect(sexp);
+    // Collect after releasing the lock, as finalizers may unprotect objects.
+    drop(own);
+    crate::gc_stress::gc_point();
 }
 
 pub(crate) unsafe fn unprotect(sexp: SEXP) {
@@ -0,0 +1,96 @@
+//! State of a package that persists across calls, kept in its namespace.
+//!
+//! Rather than in `static mut` variables, Rust code can keep R objects,
+//! including `ExternalPtr`s to Rust values, in an environment bound in the
+//! namespace of the package. This is created when the package is loaded and
+//! dropped with the namespace when it is unloaded, so that reloading the
+//! package starts afresh even if the shared library stays loaded. Saving an
+//! object that refers to the namespace does not save the state, as R
+//! serializes namespaces by name.
+//!
+//! ```ignore
+//! use extendr_api::prelude::*;
+//!
+//! #[extendr]
+//! fn count_calls() -> Result<i32> {
+//!     let calls = get_state::<Option<i32>>("calls")?.unwrap_or(0) + 1;
+//!     set_state("calls", calls)?;
+//!     Ok(calls)
+//! }
+//! ```
+use crate::*;
+use once_cell::sync::OnceCell;
+
+/// The name of the package, which is that of its `extendr_module!`.
+static PACKAGE_NAME: OnceCell<String> = OnceCell::new();
+
+/// The name of the binding of the state environment in the namespace.
+const STATE_NAME: &str = ".__extendr_state__";
+
+/// Record the name of the package and add the state environment to its
+/// namespace. Called by the init function of `extendr_module!`, when R
+/// loads the shared library of the package.
+#[doc(hidden)]
+pub fn init_package(name: &str) {
+    // A module that is loaded again keeps its name.
+    let _ = PACKAGE_NAME.set(name.to_string());
+
+    // R locks the namespace once its shared libraries are loaded, so the state
+    // environment is bound now. There is no namespace if the library is
+    // loaded with `dyn.load()`.
+    if let Ok(namespace) = find_namespace(name) {
+        let locked: bool = unsafe { R_EnvironmentIsLocked(namespace.get()).into() };
+        if !locked {
+            let state = Environment::new_with_parent(empty_env());
+            namespace.set_local(Symbol::from_string(STATE_NAME), state);
+        }
+    }
+}
+
+/// The namespace of the package, whose name is that of its `extendr_module!`.
+pub fn package_env() -> Result<Environment> {
+    match PACKAGE_NAME.get() {
+        Some(name) => find_namespace(name.as_str()),
+        None => Err(Error::Other(
+            "the package has not been loaded by `extendr_module!`".into(),
+        )),
+    }
+}
+
+/// The environment in the namespace of the package that keeps its state.
+/// It is an error if the namespace was already locked when the shared
+/// library of the package was loaded, as the state could not be added.
+pub fn state_env() -> Result<Environment> {
+    let state = package_env()?.local(Symbol::from_string(STATE_NAME))?;
+    if state.is_unbound_value() {
+        return Err(Error::Other(
+            "the namespace of the package was locked before its shared library was loaded, \
+             so it has no state environment"
+                .into(),
+        ));
+    }
+    state
+        .try_into()
+        .map_err(|_| Error::Other("the namespace of the package has no state environment".into()))
+}
+
+/// Get a value of the state of the package, converted to `T`.
+/// A value that has not been set is `NULL`, which converts to `None`
+/// if `T` is an `Option`.
+pub fn get_state<T>(name: &str) -> Result<T>
+where
+    T: TryFrom<Robj, Error = Error>,
+{
+    let value = state_env()?.local(Symbol::from_string(name))?;
+    if value.is_unbound_value() {
+        T::try_from(Robj::from(()))
+    } else {
+        T::try_from(value)
+    }
+}
+
+/// Set a value of the state of the package.
+pub fn set_state<V: Into<Robj>>(name: &str, value: V) -> Result<()> {
+    state_env()?.set_local(Symbol::from_string(name), value);
+    Ok(())
+}
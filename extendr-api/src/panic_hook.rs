@@ -0,0 +1,252 @@
+//! A panic hook that reports panics in exported functions to R.
+//!
+//! By default, a panic in an `#[extendr]` function prints to stderr, which
+//! users of R GUIs never see, and R only reports `User function panicked`.
+//!
+//! Once [`install`]ed (e.g. with `panic_hook = true;` in `extendr_module!`),
+//! panics inside exported functions are formatted with the function name,
+//! a summary of its arguments and the panic location, and the report becomes
+//! part of the R error. If the `EXTENDR_PANIC_LOG` environment variable
+//! (or [`set_log_file`]) names a file, the report is also appended there,
+//! together with a Rust backtrace.
+//!
+//! If the `EXTENDR_BACKTRACE` environment variable is set to anything but `0`
+//! (or [`set_capture_backtrace`] is called), a backtrace is captured at panic
+//! time, trimmed to the frames between the exported function and the panic,
+//! and stored in the `backtrace` field of the `extendr_error` condition.
+//!
+//! Panics outside of exported functions are passed on to the previous hook.
+use crate::*;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static CAPTURE_BACKTRACE: AtomicBool = AtomicBool::new(false);
+static LOG_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+thread_local! {
+    // Exported functions currently being called, innermost last.
+    static CALL_STACK: RefCell<Vec<CallRecord>> = RefCell::new(Vec::new());
+    // The report of the last panic in an exported function.
+    static LAST_REPORT: RefCell<Option<String>> = RefCell::new(None);
+    // The trimmed backtrace of the last panic in an exported function.
+    static LAST_BACKTRACE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+struct CallRecord {
+    name: &'static str,
+    args: Vec<(&'static str, SEXP)>,
+}
+
+/// Install the extendr panic hook. Calling this more than once has no effect.
+///
+/// The log file is taken from the `EXTENDR_PANIC_LOG` environment variable, if set,
+/// and backtraces are captured if `EXTENDR_BACKTRACE` is set to anything but `0`.
+pub fn install() {
+    if INSTALLED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(path) = std::env::var_os("EXTENDR_PANIC_LOG") {
+        set_log_file(Some(path.into()));
+    }
+    if std::env::var_os("EXTENDR_BACKTRACE").is_some_and(|value| value != "0") {
+        set_capture_backtrace(true);
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let in_call = CALL_STACK.with(|stack| !stack.borrow().is_empty());
+        if in_call {
+            record_panic(info.payload(), info.location());
+        } else {
+            previous(info);
+        }
+    }));
+}
+
+/// Return true if the extendr panic hook is installed.
+pub fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Set or clear the file that panic reports and backtraces are appended to.
+pub fn set_log_file(path: Option<PathBuf>) {
+    *LOG_FILE.lock().unwrap() = path;
+}
+
+/// Enable or disable capturing backtraces of panics in exported functions.
+pub fn set_capture_backtrace(capture: bool) {
+    CAPTURE_BACKTRACE.store(capture, Ordering::Relaxed);
+}
+
+/// Marks a call of an exported function while it is alive.
+/// Used by the `#[extendr]` wrappers.
+#[doc(hidden)]
+pub struct CallGuard {
+    active: bool,
+}
+
+impl CallGuard {
+    #[doc(hidden)]
+    pub fn enter(name: &'static str, args: &[(&'static str, SEXP)]) -> Self {
+        if !is_installed() {
+            return Self { active: false };
+        }
+        CALL_STACK.with(|stack| {
+            stack.borrow_mut().push(CallRecord {
+                name,
+                args: args.to_vec(),
+            })
+        });
+        Self { active: true }
+    }
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        if self.active {
+            CALL_STACK.with(|stack| stack.borrow_mut().pop());
+        }
+    }
+}
+
+/// Take the report of the last panic in an exported function,
+/// escaped for use in an R error message.
+#[doc(hidden)]
+pub fn take_report() -> Option<String> {
+    take_raw_report().map(|report| report.replace('%', "%%"))
+}
+
+/// Take the report of the last panic in an exported function, unescaped,
+/// for use in an R condition object.
+#[doc(hidden)]
+pub fn take_raw_report() -> Option<String> {
+    LAST_REPORT.with(|report| report.borrow_mut().take())
+}
+
+/// The message of a panic, if its payload is a string.
+#[doc(hidden)]
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> Option<&str> {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        Some(s)
+    } else {
+        payload.downcast_ref::<String>().map(String::as_str)
+    }
+}
+
+/// Take the trimmed backtrace of the last panic in an exported function,
+/// if backtraces are captured.
+#[doc(hidden)]
+pub fn take_backtrace() -> Option<String> {
+    LAST_BACKTRACE.with(|backtrace| backtrace.borrow_mut().take())
+}
+
+fn record_panic(payload: &(dyn std::any::Any + Send), location: Option<&std::panic::Location>) {
+    let message = panic_message(payload).unwrap_or("Box<dyn Any>");
+    let location = location
+        .map(|l| format!(" at {}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_default();
+
+    let call = CALL_STACK.with(|stack| {
+        let stack = stack.borrow();
+        let record = stack.last().unwrap();
+        let args = record
+            .args
+            .iter()
+            .map(|(name, sexp)| format!("{} = {}", name, summarize(*sexp)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{}({})", record.name, args)
+    });
+
+    let mut report = format!("in {}: {}{}", call, message, location);
+
+    let log_file = LOG_FILE.lock().unwrap().clone();
+    let capture = CAPTURE_BACKTRACE.load(Ordering::Relaxed);
+    let backtrace = (capture || log_file.is_some()).then(Backtrace::force_capture);
+    if capture {
+        let trimmed = trim_backtrace(&backtrace.as_ref().unwrap().to_string());
+        LAST_BACKTRACE.with(|last| *last.borrow_mut() = Some(trimmed));
+    }
+
+    if let (Some(path), Some(backtrace)) = (log_file, backtrace) {
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "panic {}\n{}\n", report, backtrace));
+        if written.is_ok() {
+            report.push_str(&format!("\nBacktrace written to {}", path.display()));
+        }
+    }
+
+    LAST_REPORT.with(|last| *last.borrow_mut() = Some(report));
+}
+
+// Keep the frames between the panic machinery and the `catch_unwind`
+// of the wrapper, which are the ones in user code.
+fn trim_backtrace(backtrace: &str) -> String {
+    // Each frame starts with a line like "  12: path::to::function".
+    let mut frames: Vec<Vec<&str>> = Vec::new();
+    for line in backtrace.lines() {
+        let is_frame_start = line
+            .trim_start()
+            .split_once(": ")
+            .is_some_and(|(index, _)| index.chars().all(|c| c.is_ascii_digit()));
+        match frames.last_mut() {
+            Some(frame) if !is_frame_start => frame.push(line),
+            _ => frames.push(vec![line]),
+        }
+    }
+    let end = frames
+        .iter()
+        .position(|frame| {
+            ["panicking::try", "catch_unwind", "wrap__"]
+                .iter()
+                .any(|marker| frame[0].contains(marker))
+        })
+        .unwrap_or(frames.len());
+    let start = frames[..end]
+        .iter()
+        .rposition(|frame| {
+            [
+                "std::backtrace",
+                "std::sys::",
+                "std::panicking",
+                "core::panicking",
+                "rust_begin_unwind",
+                "unwrap_failed",
+                "extendr_api::panic_hook",
+            ]
+            .iter()
+            .any(|marker| frame[0].contains(marker))
+        })
+        .map_or(0, |i| i + 1);
+    frames[start..end]
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Describe an argument by type and length without allocating in R.
+fn summarize(sexp: SEXP) -> String {
+    unsafe {
+        let rtype = sxp_to_rtype(TYPEOF(sexp));
+        match rtype {
+            Rtype::Null => "NULL".to_string(),
+            Rtype::Logicals
+            | Rtype::Integers
+            | Rtype::Doubles
+            | Rtype::Complexes
+            | Rtype::Strings
+            | Rtype::List
+            | Rtype::Raw => format!("{:?}[{}]", rtype, Rf_xlength(sexp)),
+            _ => format!("{:?}", rtype),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Run pure Rust work on a thread pool and collect the results on the R thread.
+//!
+//! The R API must only be used from the R main thread. Closures given to
+//! [`spawn_blocking`] and values sent through a [`channel`] have to be
+//! [`Send`], which [`Robj`] and the other R wrappers are not, so it is a
+//! compile error to use R objects on a worker thread:
+//!
+//! ```compile_fail
+//! use extendr_api::prelude::*;
+//! test! {
+//!     let x = r!(1);
+//!     spawn_blocking(move || x.len());
+//! }
+//! ```
+//!
+//! Instead, convert the inputs to Rust values on the R thread, and the
+//! results back to R objects once they are received:
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! test! {
+//!     let x: Vec<f64> = r!([1., 2., 3.]).try_into()?;
+//!     let handle = spawn_blocking(move || x.iter().map(|v| v * 2.).sum::<f64>());
+//!     assert_eq!(r!(handle.join()?), r!(12.));
+//! }
+//! ```
+use crate::*;
+use once_cell::sync::Lazy;
+use std::marker::PhantomData;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// How long to wait for a result between two checks for a user interrupt.
+const INTERRUPT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+static POOL: Lazy<Mutex<mpsc::Sender<Job>>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let receiver = std::sync::Arc::new(Mutex::new(receiver));
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    for i in 0..threads {
+        let receiver = receiver.clone();
+        std::thread::Builder::new()
+            .name(format!("extendr-worker-{}", i))
+            .spawn(move || loop {
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    Ok(job) => job(),
+                    Err(_) => break,
+                }
+            })
+            .expect("failed to spawn a worker thread");
+    }
+    Mutex::new(sender)
+});
+
+/// Run `f` on the thread pool and return a handle to wait for its result.
+///
+/// The pool has one thread per available CPU and is started on first use.
+pub fn spawn_blocking<F, T>(f: F) -> WorkerHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = channel();
+    let job = Box::new(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        // The handle may have been dropped.
+        let _ = sender.send(result);
+    });
+    POOL.lock()
+        .unwrap()
+        .send(job)
+        .expect("the thread pool has stopped");
+    WorkerHandle { receiver }
+}
+
+/// A handle to the result of [`spawn_blocking`].
+#[derive(Debug)]
+pub struct WorkerHandle<T> {
+    receiver: Receiver<std::thread::Result<T>>,
+}
+
+impl<T> WorkerHandle<T> {
+    /// Wait for the result.
+    ///
+    /// Returns [`Error::Interrupted`] if the user interrupts R while waiting,
+    /// and an error if the closure panicked.
+    pub fn join(self) -> Result<T> {
+        self.receiver.recv()?.map_err(worker_panicked)
+    }
+
+    /// Get the result if it is ready.
+    pub fn try_join(&self) -> Option<Result<T>> {
+        self.receiver
+            .try_recv()
+            .map(|result| result.map_err(worker_panicked))
+    }
+}
+
+fn worker_panicked(payload: Box<dyn std::any::Any + Send>) -> Error {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    Error::Other(format!("worker thread panicked: {}", message))
+}
+
+/// Make a channel to send values from worker threads to the R thread.
+///
+/// The [`Sender`](mpsc::Sender) can be cloned and moved to other threads.
+/// The [`Receiver`] stays on the thread that made it.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let (sender, receiver) = extendr_api::parallel::channel();
+///     for i in 0..4 {
+///         let sender = sender.clone();
+///         spawn_blocking(move || sender.send(i * i).unwrap());
+///     }
+///     drop(sender);
+///     let mut squares = receiver.iter().collect::<Result<Vec<i32>>>()?;
+///     squares.sort();
+///     assert_eq!(squares, vec![0, 1, 4, 9]);
+/// }
+/// ```
+pub fn channel<T: Send>() -> (mpsc::Sender<T>, Receiver<T>) {
+    let (sender, receiver) = mpsc::channel();
+    (
+        sender,
+        Receiver {
+            receiver,
+            _not_send: PhantomData,
+        },
+    )
+}
+
+/// The receiving end of a [`channel`], which is drained on the R thread.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    receiver: mpsc::Receiver<T>,
+    // The receiver stays on the R thread, where it can check for interrupts.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T> Receiver<T> {
+    /// Wait for the next value.
+    ///
+    /// Returns [`Error::Interrupted`] if the user interrupts R while waiting,
+    /// and an error if all senders have been dropped.
+    pub fn recv(&self) -> Result<T> {
+        loop {
+            match self.receiver.recv_timeout(INTERRUPT_CHECK_INTERVAL) {
+                Ok(value) => return Ok(value),
+                Err(mpsc::RecvTimeoutError::Timeout) => check_user_interrupt()?,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Other("all senders have been dropped".into()))
+                }
+            }
+        }
+    }
+
+    /// Get the next value if there is one.
+    pub fn try_recv(&self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Iterate over the values sent until all senders have been dropped.
+    /// Each item is an error if the user interrupted R.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        std::iter::from_fn(move || match self.recv() {
+            Ok(value) => Some(Ok(value)),
+            Err(Error::Interrupted) => Some(Err(Error::Interrupted)),
+            Err(_) => None,
+        })
+    }
+
+    /// Iterate over the values that have already been sent, without waiting.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.receiver.try_iter()
+    }
+}
@@ -8,17 +8,36 @@ pub use super::{
     NA_STRING, NULL, TRUE,
 };
 
+pub use super::defer::DeferR;
+
 pub use super::error::{Error, Result};
 
+pub use super::event_loop::run_on_main_thread;
+
 pub use super::functions::{
-    base_env, base_namespace, blank_scalar_string, blank_string, current_env, empty_env,
-    eval_string, eval_string_with_params, find_namespace, find_namespaced_function, global_env,
-    global_function, na_string, namespace_registry, new_env, nil_value, parse, srcref,
+    base_env, base_namespace, blank_scalar_string, blank_string, check_user_interrupt, collate,
+    collation_order, current_env, empty_env, eval_string, eval_string_with_env,
+    eval_string_with_params, find_namespace, find_namespaced_function, format_numbers, global_env,
+    global_function, na_string, namespace_registry, new_env, nil_value, parse, paste, paste0,
+    print_robj, rep, sandbox_env, seq, seq_len, sort_collated, srcref, sys_calls, sys_frames,
+    try_catch, try_catch_with_env, with_output_captured,
 };
 
 #[cfg(feature = "non-api")]
 pub use super::functions::{global_var, local_var};
 
+pub use super::local::{local_dir, local_locale, local_par, local_seed, LocalGuard};
+
+pub use super::options::{get_option, local_option, set_option, OptionGuard};
+
+pub use super::package::{get_state, package_env, set_state, state_env};
+
+pub use super::parallel::{spawn_blocking, WorkerHandle};
+
+pub use super::progress::{Progress, ProgressStyle};
+
+pub use super::task_callback::{add_task_callback, remove_task_callback, TaskCallback, TaskInfo};
+
 pub use super::wrapper::symbol::{
     base_symbol, brace_symbol, bracket_2_symbol, bracket_symbol, class_symbol, device_symbol,
     dim_symbol, dimnames_symbol, dollar_symbol, dot_defined, dot_method, dot_package_name,
@@ -33,12 +52,13 @@ pub use crate::{append, append_lang, append_with_name, args, lang, make_lang};
 
 // Exported macros have crate scope.
 pub use crate::{
-    data_frame, factor, global, list, r, reprint, reprintln, rprint, rprintln, sym, test, var,
+    c, data_frame, factor, global, list, matrix, r, reprint, reprintln, rprint, rprintln, sym,
+    test, var,
 };
 
 pub use super::wrapper::{
     AltComplexImpl, AltIntegerImpl, AltLogicalImpl, AltRawImpl, AltRealImpl, AltStringImpl, Altrep,
-    AltrepImpl, RArray, RColumn, RMatrix, RMatrix3D,
+    AltrepImpl, RArray, RArrayN, RColumn, RMatrix, RMatrix3D,
 };
 
 #[cfg(use_r_altlist)]
@@ -49,21 +69,26 @@ pub use super::wrapper::s4::S4;
 pub use super::wrapper::{Conversions, MatrixConversions};
 
 pub use super::robj::{
-    AsStrIter, Attributes, Eval, GetSexp, IntoRobj, Length, Operators, Rinternals, Robj,
-    RobjItertools, Slices, Types,
+    AsStrIter, Attributes, Distinct, Eval, GetSexp, IntoRobj, Length, Operators, RVectorBuilder,
+    Reductions, Rinternals, Robj, RobjBuilder, RobjItertools, Slices, Types,
 };
 
-pub use super::thread_safety::{catch_r_error, handle_panic, single_threaded, throw_r_error};
+pub use super::thread_safety::{
+    catch_r_error, error_condition, handle_panic, single_threaded, throw_r_condition,
+    throw_r_error, toplevel_exec,
+};
 
 pub use super::wrapper::{
-    Complexes, Dataframe, Doubles, EnvIter, Environment, Expressions, ExternalPtr, FromList,
-    Function, Integers, IntoDataFrameRow, Language, List, ListIter, Logicals, Nullable, Pairlist,
-    Primitive, Promise, Raw, Rstr, Strings, Symbol,
+    CallArg, Complexes, Dataframe, Difftime, DifftimeUnits, Doubles, EnvBindings, EnvIter,
+    Environment, Expressions, ExternalPtr, FromList, Function, Integers, IntoDataFrameRow,
+    Language, List, ListIter, Logicals, NameIndex, Nullable, OnRRelease, Pairlist, PosixLt,
+    Primitive, Promise, Raw, Rstr, SparseLayout, SparseMatrix, Strings, Symbol, WeakRef,
+    WithAttributes,
 };
 
 pub use extendr_macros::{call, extendr, extendr_module, pairlist, IntoDataFrameRow, Rraw, R};
 
-pub use super::iter::StrIter;
+pub use super::iter::{NamedPairIter, StrIter};
 
 pub use std::convert::{TryFrom, TryInto};
 
@@ -0,0 +1,192 @@
+//! Progress output for long-running Rust loops.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! test! {
+//!     let mut progress = Progress::new(100)?;
+//!     for _ in 0..100 {
+//!         // ... some work ...
+//!         progress.inc(1)?;
+//!     }
+//!     progress.finish()?;
+//! }
+//! ```
+use crate as extendr_api;
+use crate::*;
+use std::time::{Duration, Instant};
+
+/// How a [`Progress`] is shown in R.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressStyle {
+    /// A line printed with `Rprintf`, such as `label 42% (42/100)`.
+    #[default]
+    Plain,
+    /// A bar from `utils::txtProgressBar()`.
+    TxtProgressBar,
+    /// A bar from `cli::cli_progress_bar()`. This needs the `cli` package.
+    Cli,
+}
+
+/// Reports the progress of a computation with a known number of steps.
+///
+/// Updates are throttled: the output is redrawn at most once every
+/// [`min_interval`](Progress::set_min_interval), which is 100 ms by default,
+/// so it is cheap to call [`inc`](Progress::inc) in a tight loop.
+/// Each redraw also checks for a user interrupt and returns
+/// [`Error::Interrupted`] if there was one.
+///
+/// The output is closed by [`finish`](Progress::finish), or when the
+/// `Progress` is dropped.
+#[derive(Debug)]
+pub struct Progress {
+    style: ProgressStyle,
+    label: String,
+    total: usize,
+    current: usize,
+    min_interval: Duration,
+    last_update: Option<Instant>,
+    // The `txtProgressBar` object, or the environment with the `cli` bar id.
+    bar: Option<Robj>,
+    finished: bool,
+}
+
+impl Progress {
+    /// Start reporting progress of `total` steps as a plain line of text.
+    pub fn new(total: usize) -> Result<Self> {
+        Self::with_style(total, ProgressStyle::Plain, "")
+    }
+
+    /// Start reporting progress of `total` steps using `style`,
+    /// with a `label` in front of the plain and `cli` outputs.
+    ///
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let mut progress = Progress::with_style(10, ProgressStyle::TxtProgressBar, "")?;
+    ///     progress.set(5)?;
+    ///     assert_eq!(progress.position(), 5);
+    /// }
+    /// ```
+    pub fn with_style(total: usize, style: ProgressStyle, label: &str) -> Result<Self> {
+        let total_robj = Robj::from(total as f64);
+        let bar = match style {
+            ProgressStyle::Plain => None,
+            ProgressStyle::TxtProgressBar => Some(eval_string_with_params(
+                "utils::txtProgressBar(min = 0, max = param.0, style = 3)",
+                &[&total_robj],
+            )?),
+            ProgressStyle::Cli => Some(eval_string_with_params(
+                "if (!requireNamespace('cli', quietly = TRUE)) stop('the cli package is not installed')
+                env <- new.env()
+                env$id <- cli::cli_progress_bar(name = param.1, total = param.0,
+                    .auto_close = FALSE, .envir = env)
+                env",
+                &[&total_robj, &Robj::from(label)],
+            )?),
+        };
+        let mut progress = Self {
+            style,
+            label: label.to_string(),
+            total,
+            current: 0,
+            min_interval: Duration::from_millis(100),
+            last_update: None,
+            bar,
+            finished: false,
+        };
+        progress.redraw()?;
+        Ok(progress)
+    }
+
+    /// Set the minimum time between two redraws.
+    pub fn set_min_interval(&mut self, min_interval: Duration) -> &mut Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// The number of completed steps.
+    pub fn position(&self) -> usize {
+        self.current
+    }
+
+    /// The total number of steps.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Add `steps` completed steps.
+    pub fn inc(&mut self, steps: usize) -> Result<()> {
+        self.set(self.current.saturating_add(steps))
+    }
+
+    /// Set the number of completed steps, which is capped at the total.
+    pub fn set(&mut self, position: usize) -> Result<()> {
+        self.current = position.min(self.total);
+        let due = match self.last_update {
+            Some(last) => last.elapsed() >= self.min_interval,
+            None => true,
+        };
+        if due {
+            check_user_interrupt()?;
+            self.redraw()?;
+        }
+        Ok(())
+    }
+
+    /// Show the final position and close the output.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        self.redraw()?;
+        match (self.style, &self.bar) {
+            (ProgressStyle::Plain, _) => print_r_output("\n"),
+            (ProgressStyle::TxtProgressBar, Some(bar)) => {
+                call!("close", bar)?;
+            }
+            (ProgressStyle::Cli, Some(env)) => {
+                eval_string_with_params("cli::cli_progress_done(id = param.0$id)", &[env])?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn redraw(&mut self) -> Result<()> {
+        self.last_update = Some(Instant::now());
+        let position = Robj::from(self.current as f64);
+        match (self.style, &self.bar) {
+            (ProgressStyle::Plain, _) => {
+                let percent = (self.current * 100).checked_div(self.total).unwrap_or(100);
+                let label = if self.label.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} ", self.label)
+                };
+                print_r_output(format!(
+                    "\r{}{}% ({}/{})",
+                    label, percent, self.current, self.total
+                ));
+            }
+            (ProgressStyle::TxtProgressBar, Some(bar)) => {
+                call!("utils::setTxtProgressBar", bar, position)?;
+            }
+            (ProgressStyle::Cli, Some(env)) => {
+                eval_string_with_params(
+                    "cli::cli_progress_update(set = param.1, id = param.0$id, force = TRUE)",
+                    &[env, &position],
+                )?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        // Errors can not be reported from drop.
+        let _ = self.finish();
+    }
+}
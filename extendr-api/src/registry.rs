@@ -0,0 +1,58 @@
+//! Registration of exported functions from every crate linked into a package.
+//!
+//! When the Rust side of a package is a workspace of several crates, each
+//! crate can define `#[extendr]` functions and impls without its own
+//! `extendr_module!`. With the `registry` feature, `#[extendr]` adds them to
+//! distributed slices (see [`linkme`]), and a module in the package crate
+//! registers all of them with `registry = true;`:
+//!
+//! ```ignore
+//! // in the package crate, which depends on `parsers` and `models`
+//! use parsers as _;
+//! use models as _;
+//!
+//! extendr_module! {
+//!     mod mypackage;
+//!     registry = true;
+//! }
+//! ```
+//!
+//! A crate is only linked if it is used, so crates that are not otherwise
+//! used must be named with `use crate_name as _;`. Items that are also listed
+//! in the module, or that come from a `use`d module, are registered once.
+use crate::metadata::{merge, Func, Impl, Metadata};
+
+pub use linkme;
+
+/// The metadata functions of `#[extendr]` functions.
+#[linkme::distributed_slice]
+pub static FUNCTIONS: [fn(&mut Vec<Func>)] = [..];
+
+/// The metadata functions of `#[extendr]` impls.
+#[linkme::distributed_slice]
+pub static IMPLS: [fn(&mut Vec<Impl>)] = [..];
+
+/// The metadata of all `#[extendr]` functions and impls in the registry.
+pub fn metadata(name: &'static str) -> Metadata {
+    let mut functions = Vec::new();
+    let mut impls = Vec::new();
+    for meta in FUNCTIONS {
+        meta(&mut functions);
+    }
+    for meta in IMPLS {
+        meta(&mut impls);
+    }
+    Metadata {
+        name,
+        functions,
+        impls,
+    }
+}
+
+/// Add the functions and impls in the registry to those of a module.
+///
+/// Returns an error if functions of different crates have the same R name.
+#[doc(hidden)]
+pub fn merge_into(functions: &mut Vec<Func>, impls: &mut Vec<Impl>) -> crate::Result<()> {
+    merge(functions, impls, metadata("registry"))
+}
@@ -29,7 +29,7 @@
 #[macro_export]
 macro_rules! r {
     ($e: expr) => {
-        extendr_api::Robj::from($e)
+        $crate::Robj::from($e)
     };
 }
 
@@ -72,7 +72,33 @@ macro_rules! sym {
     };
 }
 
-/// Create a dataframe.
+/// Create a matrix, with the arguments of R's `matrix()`:
+/// the values, then `nrow`, `ncol`, `byrow` and `dimnames`.
+///
+/// Example:
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let m = matrix!(r!([1, 2, 3, 4, 5, 6]), nrow = 2);
+///     assert_eq!(m.dim().unwrap().iter().collect::<Vec<_>>(), vec![Rint::from(2), Rint::from(3)]);
+///     let m = RMatrix::<i32>::try_from(m)?;
+///     assert_eq!(m[[1, 0]], 2);
+///
+///     let by_row = RMatrix::<f64>::try_from(matrix!(r!([1., 2., 3., 4.]), ncol = 2, byrow = true))?;
+///     assert_eq!(by_row[[0, 1]], 2.);
+/// }
+/// ```
+///
+/// Panics on error.
+#[macro_export]
+macro_rules! matrix {
+    ($($rest: tt)*) => {
+        call!("matrix", $($rest)*).unwrap()
+    };
+}
+
+/// Create a dataframe, with the arguments of R's `data.frame()`:
+/// usually named columns, which are recycled to the same length.
 ///
 /// Example:
 /// ```
@@ -80,6 +106,10 @@ macro_rules! sym {
 /// test! {
 ///     let mydata = data_frame!(x=1, y=2);
 ///     assert_eq!(mydata.inherits("data.frame"), true);
+///
+///     let mydata = data_frame!(id = r!([1, 2, 3]), group = "a");
+///     assert_eq!(mydata.names().unwrap().collect::<Vec<_>>(), vec!["id", "group"]);
+///     assert_eq!(call!("nrow", &mydata)?, r!(3));
 ///     //assert_eq!(mydata, r!(List::from_pairs(vec![("x", r!(1)), ("y", r!(2))])).set_class(&["data.frame"])?);
 /// }
 /// ```
@@ -105,6 +135,11 @@ macro_rules! data_frame {
 ///     assert_eq!(factor.levels().unwrap().collect::<Vec<_>>(), vec!["abcd", "def", "fg"]);
 ///     assert_eq!(factor.as_integer_vector().unwrap(), vec![1, 2, 3, 3]);
 ///     assert_eq!(factor.as_str_iter().unwrap().collect::<Vec<_>>(), vec!["abcd", "def", "fg", "fg"]);
+///
+///     // `levels` sets the order of the levels, as in R's `factor()`.
+///     let sizes = factor!(r!(["small", "large"]), levels = r!(["small", "medium", "large"]));
+///     assert_eq!(sizes.levels().unwrap().collect::<Vec<_>>(), vec!["small", "medium", "large"]);
+///     assert_eq!(sizes.as_integer_vector().unwrap(), vec![1, 3]);
 /// }
 /// ```
 ///
@@ -116,16 +151,85 @@ macro_rules! factor {
     };
 }
 
+/// Combine values into a vector, like `c()` in R.
+///
+/// As in R, the result has the most general type of the values
+/// (logical, integer, double, complex, character), or is a list if one
+/// of the values is a list. Vectors are spliced in, and names are kept,
+/// with `name = value` naming an element or prefixing the names of a vector.
+///
+/// Example:
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_eq!(c!(1, 2, 3), r!([1, 2, 3]));
+///     assert_eq!(c!(true, 2, r!([3.5, 4.5])), r!([1., 2., 3.5, 4.5]));
+///     assert_eq!(c!(1, "a"), r!(["1", "a"]));
+///
+///     let named = c!(a = 1, b = r!([2, 3]));
+///     assert_eq!(named.names().unwrap().collect::<Vec<_>>(), vec!["a", "b1", "b2"]);
+///
+///     let mixed = c!(1, list!(2, "x"));
+///     assert!(mixed.is_list());
+///     assert_eq!(mixed.len(), 3);
+///
+///     assert!(c!().is_null());
+/// }
+/// ```
+///
+/// Panics on error.
+#[macro_export]
+macro_rules! c {
+    () => {
+        extendr_api::Robj::from(())
+    };
+    ($($rest: tt)*) => {
+        // `quote = TRUE` keeps symbols and calls among the values from being evaluated.
+        extendr_api::eval_string_with_params(
+            "do.call(c, param.0, quote = TRUE)",
+            &[&extendr_api::Robj::from(extendr_api::list!($($rest)*))],
+        )
+        .unwrap()
+    };
+}
+
 /// Print via the R output stream.
 ///
-/// Works like [`print!`] but integrates with R and respects
-/// redirection with functions like `sink()` and `capture.output()`
+/// With a format string, this works like [`print!`] but integrates with R and
+/// respects redirection with functions like `sink()` and `capture.output()`.
+///
+/// Otherwise, each argument is converted to an [`Robj`](crate::Robj) and
+/// printed with R's `print()` generic, like [`print_robj`](crate::print_robj),
+/// so data frames, factors and S4 objects are shown by their print methods.
+/// Arguments are taken by reference.
+///
+/// Example:
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let v = r!([1, 2]);
+///     let l = list!(a = 1);
+///     let (_, stdout, _) = with_output_captured(|| {
+///         rprint!("{} values\n", 2);
+///         rprint!(v, l);
+///         Ok(r!(()))
+///     })?;
+///     assert_eq!(stdout, "2 values\n[1] 1 2\n$a\n[1] 1\n\n");
+/// }
+/// ```
+///
+/// Panics if printing fails.
 #[macro_export]
 macro_rules! rprint {
     () => {
     };
-    ($($rest: tt)*) => {
-        print_r_output(format!($($rest)*));
+    ($fmt: literal $($rest: tt)*) => {
+        print_r_output(format!($fmt $($rest)*));
+    };
+    ($($value: expr),+ $(,)?) => {
+        $(
+            extendr_api::print_robj(&extendr_api::Robj::from(&$value)).unwrap();
+        )+
     };
 }
 
@@ -200,3 +304,48 @@ macro_rules! test {
         }
     };
 }
+
+/// Assert that two R objects are identical, like `assert_eq!`.
+///
+/// Both sides are converted to [`Robj`](crate::Robj). On failure, the panic
+/// message lists the differences in type, length, attributes and elements
+/// found by [`Robj::diff`](crate::Robj::diff), rather than the two values.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_robj_eq!(r!([1, 2, 3]), R!("1:3")?);
+///     assert_robj_eq!(list!(a = "x"), R!("list(a = 'x')")?, "lists should match");
+///
+///     let result = std::panic::catch_unwind(|| assert_robj_eq!(r!([1, 2]), r!([1, 3])));
+///     assert!(result.is_err());
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_robj_eq {
+    ($left: expr, $right: expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left = extendr_api::Robj::from(left.clone());
+                let right = extendr_api::Robj::from(right.clone());
+                if let Some(diff) = left.diff(&right) {
+                    panic!("assertion `left == right` failed\n{}", diff);
+                }
+            }
+        }
+    };
+    ($left: expr, $right: expr, $($arg: tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let left = extendr_api::Robj::from(left.clone());
+                let right = extendr_api::Robj::from(right.clone());
+                if let Some(diff) = left.diff(&right) {
+                    panic!(
+                        "assertion `left == right` failed: {}\n{}",
+                        format_args!($($arg)+),
+                        diff
+                    );
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,273 @@
+use super::*;
+use std::marker::PhantomData;
+
+/// Build an R vector of unknown length one element at a time.
+///
+/// The underlying R vector grows geometrically when full, so that
+/// `push()` is amortized constant time, and is truncated to the
+/// number of elements by [`RVectorBuilder::finish`].
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let mut builder = RVectorBuilder::new();
+///     for line in "1\n2\n3".lines() {
+///         builder.push(line.parse::<i32>().unwrap());
+///     }
+///     assert_eq!(builder.finish(), r!([1, 2, 3]));
+///
+///     let mut builder = RVectorBuilder::with_capacity(2);
+///     builder.extend(["a", "b", "c"]);
+///     assert_eq!(builder.finish(), r!(["a", "b", "c"]));
+/// }
+/// ```
+pub struct RVectorBuilder<T> {
+    robj: Robj,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T: ToVectorValue> Default for RVectorBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ToVectorValue> RVectorBuilder<T> {
+    const MIN_CAPACITY: usize = 16;
+
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::with_capacity(Self::MIN_CAPACITY)
+    }
+
+    /// Create an empty builder with room for `capacity` elements.
+    ///
+    /// Panics if `T` is not stored in an atomic vector.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let sexptype = T::sexptype();
+        assert!(
+            matches!(
+                sexptype,
+                SEXPTYPE::REALSXP
+                    | SEXPTYPE::CPLXSXP
+                    | SEXPTYPE::INTSXP
+                    | SEXPTYPE::LGLSXP
+                    | SEXPTYPE::STRSXP
+                    | SEXPTYPE::RAWSXP
+            ),
+            "unexpected SEXPTYPE in RVectorBuilder"
+        );
+        Self {
+            robj: Robj::alloc_vector(sexptype, capacity),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// The number of elements pushed so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no elements have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of elements that fit without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.robj.len()
+    }
+
+    /// Append an element, growing the vector if needed.
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            let new_capacity = (self.capacity() * 2).max(Self::MIN_CAPACITY);
+            self.robj = self.robj.xlengthgets(new_capacity).unwrap();
+        }
+        let i = self.len;
+        single_threaded(|| unsafe {
+            let sexp = self.robj.get_mut();
+            match T::sexptype() {
+                SEXPTYPE::REALSXP => *REAL(sexp).add(i) = value.to_real(),
+                SEXPTYPE::CPLXSXP => *COMPLEX(sexp).add(i) = value.to_complex(),
+                SEXPTYPE::INTSXP => *INTEGER(sexp).add(i) = value.to_integer(),
+                SEXPTYPE::LGLSXP => *LOGICAL(sexp).add(i) = value.to_logical(),
+                SEXPTYPE::STRSXP => SET_STRING_ELT(sexp, i as R_xlen_t, value.to_sexp()),
+                SEXPTYPE::RAWSXP => *RAW(sexp).add(i) = value.to_raw(),
+                _ => unreachable!(),
+            }
+        });
+        self.len += 1;
+    }
+
+    /// Return the vector, truncated to the pushed elements.
+    pub fn finish(self) -> Robj {
+        if self.len == self.capacity() {
+            self.robj
+        } else {
+            self.robj.xlengthgets(self.len).unwrap()
+        }
+    }
+}
+
+impl<T: ToVectorValue> Extend<T> for RVectorBuilder<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: ToVectorValue> From<RVectorBuilder<T>> for Robj {
+    fn from(builder: RVectorBuilder<T>) -> Self {
+        builder.finish()
+    }
+}
+
+/// Build an R object and its attributes before it is handed to R.
+///
+/// Attributes are collected by the chained methods and set on the
+/// object in [`RobjBuilder::build`], so no half-built object is seen
+/// by R and construction code reads top to bottom.
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let mut builder = RobjBuilder::new_real(3);
+///     builder.data_mut().as_real_slice_mut().unwrap().copy_from_slice(&[1., 2., 3.]);
+///     let robj = builder.names(["a", "b", "c"]).class(["my_class"]).build()?;
+///     assert_eq!(robj, R!("structure(c(a = 1, b = 2, c = 3), class = 'my_class')")?);
+///
+///     let matrix = RobjBuilder::new([1, 2, 3, 4, 5, 6])
+///         .dim([2, 3])
+///         .attr("units", "cm")
+///         .build()?;
+///     assert_eq!(matrix, R!("structure(matrix(1:6, 2, 3), units = 'cm')")?);
+///
+///     // Names must match the length of the object.
+///     assert!(RobjBuilder::new_integer(2).names(["a"]).build().is_err());
+/// }
+/// ```
+pub struct RobjBuilder {
+    robj: Robj,
+    attributes: Vec<(Symbol, Robj)>,
+}
+
+impl RobjBuilder {
+    /// Start from an existing value.
+    pub fn new<T: Into<Robj>>(value: T) -> Self {
+        Self {
+            robj: value.into(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// A double vector of zeros.
+    pub fn new_real(len: usize) -> Self {
+        let mut robj = Robj::alloc_vector(SEXPTYPE::REALSXP, len);
+        robj.as_real_slice_mut().unwrap().fill(0.);
+        Self::new(robj)
+    }
+
+    /// An integer vector of zeros.
+    pub fn new_integer(len: usize) -> Self {
+        let mut robj = Robj::alloc_vector(SEXPTYPE::INTSXP, len);
+        robj.as_integer_slice_mut().unwrap().fill(0);
+        Self::new(robj)
+    }
+
+    /// A logical vector of `FALSE`.
+    pub fn new_logical(len: usize) -> Self {
+        let mut robj = Robj::alloc_vector(SEXPTYPE::LGLSXP, len);
+        let slice: &mut [Rbool] = robj.as_typed_slice_mut().unwrap();
+        slice.fill(Rbool::from(false));
+        Self::new(robj)
+    }
+
+    /// A character vector of empty strings.
+    pub fn new_string(len: usize) -> Self {
+        Self::new(Robj::alloc_vector(SEXPTYPE::STRSXP, len))
+    }
+
+    /// A list of `NULL`.
+    pub fn new_list(len: usize) -> Self {
+        Self::new(Robj::alloc_vector(SEXPTYPE::VECSXP, len))
+    }
+
+    /// The object being built, for filling in its elements.
+    pub fn data_mut(&mut self) -> &mut Robj {
+        &mut self.robj
+    }
+
+    /// Set an attribute. Later values of the same attribute replace earlier ones.
+    pub fn attr<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: AsRef<str>,
+        V: Into<Robj>,
+    {
+        self.attributes
+            .push((Symbol::from_string(name.as_ref()), value.into()));
+        self
+    }
+
+    /// Set the `names` attribute.
+    pub fn names<T>(self, names: T) -> Self
+    where
+        T: IntoIterator,
+        T::IntoIter: ExactSizeIterator,
+        T::Item: ToVectorValue + AsRef<str>,
+    {
+        self.attr("names", names.into_iter().collect_robj())
+    }
+
+    /// Set the `class` attribute.
+    pub fn class<T>(self, class: T) -> Self
+    where
+        T: IntoIterator,
+        T::IntoIter: ExactSizeIterator,
+        T::Item: ToVectorValue + AsRef<str>,
+    {
+        self.attr("class", class.into_iter().collect_robj())
+    }
+
+    /// Set the `dim` attribute.
+    pub fn dim<T>(self, dim: T) -> Self
+    where
+        T: IntoIterator<Item = i32>,
+        T::IntoIter: ExactSizeIterator,
+    {
+        self.attr("dim", dim.into_iter().collect_robj())
+    }
+
+    /// Set the `dimnames` attribute.
+    pub fn dimnames<T: Into<Robj>>(self, dimnames: T) -> Self {
+        self.attr("dimnames", dimnames)
+    }
+
+    /// Set the `levels` attribute.
+    pub fn levels<T>(self, levels: T) -> Self
+    where
+        T: IntoIterator,
+        T::IntoIter: ExactSizeIterator,
+        T::Item: ToVectorValue + AsRef<str>,
+    {
+        self.attr("levels", levels.into_iter().collect_robj())
+    }
+
+    /// Set the attributes and return the object.
+    ///
+    /// Fails if `names` does not match the length of the object, or if R
+    /// rejects an attribute, such as a `dim` that does not match the length.
+    pub fn build(self) -> Result<Robj> {
+        let mut robj = self.robj;
+        for (name, value) in self.attributes {
+            if name == wrapper::symbol::names_symbol() && value.len() != robj.len() {
+                return Err(Error::NamesLengthMismatch(value));
+            }
+            robj.set_attrib(name, value)?;
+        }
+        Ok(robj)
+    }
+}
@@ -1,10 +1,15 @@
+use crate::wrapper::with_attributes::attribute_pairs;
 use crate::wrapper::*;
 // use crate::robj::GetSexp;
+use crate::robj::AsTypedSlice;
 use crate::robj::Attributes;
+use crate::robj::Length;
 use crate::robj::Rany;
 use crate::robj::Rinternals;
 use crate::robj::Robj;
 use crate::robj::Types;
+use crate::scalar::{Rbool, Rcplx, Rfloat, Rint};
+use libR_sys::SEXPTYPE;
 
 impl std::fmt::Debug for Symbol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -18,10 +23,293 @@ impl std::fmt::Debug for Symbol {
     }
 }
 
-/// Implement {:?} formatting.
+/// Maximum number of lines shown by `{:#?}` and `{}` formatting of an [`Robj`].
+pub const DISPLAY_MAX_LINES: usize = 20;
+
+// Write the output of R's `print()`, truncated after `DISPLAY_MAX_LINES` lines.
+fn fmt_printed(robj: &Robj, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // The object is bound to a variable, so that symbols and calls are not evaluated.
+    let output = crate::eval_string_with_params("capture.output(print(param.0))", &[robj])
+        .ok()
+        .and_then(|lines| Strings::try_from(lines).ok());
+    let output = match output {
+        Some(output) => output,
+        // If printing fails, fall back to {:?}.
+        None => return write!(f, "{:?}", robj),
+    };
+    let lines = output
+        .iter()
+        .take(DISPLAY_MAX_LINES)
+        .map(|line| line.as_str())
+        .collect::<Vec<_>>();
+    write!(f, "{}", lines.join("\n"))?;
+    if output.len() > DISPLAY_MAX_LINES {
+        write!(f, "\n... ({} more lines)", output.len() - DISPLAY_MAX_LINES)?;
+    }
+    Ok(())
+}
+
+/// Implement {} formatting, which shows the output of R's `print()`
+/// like `{:#?}`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_eq!(format!("{}", r!([1, 2, 3])), "[1] 1 2 3");
+///     assert_eq!(format!("{}", r!(list!(a = 1))), "$a\n[1] 1\n");
+///     assert!(format!("{}", r!(Integers::from_values(0..1000))).ends_with("more lines)"));
+/// }
+/// ```
+impl std::fmt::Display for Robj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_printed(self, f)
+    }
+}
+
+/// Maximum number of elements of a vector shown by `{:?}` formatting of an [`Robj`].
+pub const DEBUG_MAX_ELEMENTS: usize = 100;
+
+impl Robj {
+    /// The first `n` elements of a vector, keeping their names.
+    /// Other attributes are dropped. Other objects, and vectors with
+    /// at most `n` elements, are returned unchanged.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([1, 2, 3]).head(2), r!([1, 2]));
+    ///     assert_eq!(R!("c(a = 1, b = 2)")?.head(1), R!("c(a = 1)")?);
+    ///     assert_eq!(r!([1, 2]).head(5), r!([1, 2]));
+    /// }
+    /// ```
+    pub fn head(&self, n: usize) -> Robj {
+        if self.is_vector() && self.len() > n {
+            self.xlengthgets(n).unwrap()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Format like `{:?}`, but showing at most `max_elems` elements of a
+    /// vector and at most `max_width` characters, followed by `...` if
+    /// the output is cut.
+    ///
+    /// `{:?}` uses this with [`DEBUG_MAX_ELEMENTS`] for long vectors.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let x = r!(Integers::from_values(0..1000000));
+    ///     assert_eq!(x.format_limited(3, 80), "[0, 1, 2] ... (999997 more elements)");
+    ///     assert_eq!(x.format_limited(3, 5), "[0, 1...");
+    ///     assert_eq!(format!("{:?}", x).len() < 1000, true);
+    /// }
+    /// ```
+    pub fn format_limited(&self, max_elems: usize, max_width: usize) -> String {
+        let len = self.len();
+        let mut text = if self.is_vector() && len > max_elems {
+            let mut head = self.head(max_elems);
+            if let Some(class) = self.class() {
+                head.set_class(class).unwrap();
+            }
+            format!(
+                "{:?} ... ({} more elements)",
+                Untruncated(&head),
+                len - max_elems
+            )
+        } else {
+            format!("{:?}", Untruncated(self))
+        };
+        if let Some((end, _)) = text.char_indices().nth(max_width) {
+            text.truncate(end);
+            text.push_str("...");
+        }
+        text
+    }
+}
+
+/// Maximum number of differing elements listed by [`Robj::diff`] for each vector.
+pub const DIFF_MAX_ELEMENTS: usize = 5;
+
+impl Robj {
+    /// Describe how this object differs from `other`, or `None` if they are
+    /// identical. This is the message of [`assert_robj_eq!`](crate::assert_robj_eq).
+    ///
+    /// Each line shows a difference in type, length, an attribute or an element,
+    /// left value first. Elements of lists are compared recursively, and at most
+    /// [`DIFF_MAX_ELEMENTS`] differing elements are listed for each vector.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([1, 2]).diff(&r!([1, 2])), None);
+    ///     assert_eq!(r!([1, 2, 3]).diff(&r!([1, 5, 3])).unwrap(), "[2]: 2 vs 5");
+    ///     assert_eq!(r!(1).diff(&r!(1.0)).unwrap(), "type: Integers vs Doubles");
+    ///
+    ///     let left = list!(a = 1, b = list!(c = "x"));
+    ///     let right = R!("structure(list(a = 1L, b = list(c = 'y')), class = 'thing')")?;
+    ///     assert_eq!(
+    ///         left.diff(&right).unwrap(),
+    ///         "attribute `class`: absent vs [\"thing\"]\n[[2]][[1]][1]: [\"x\"] vs [\"y\"]"
+    ///     );
+    /// }
+    /// ```
+    pub fn diff(&self, other: &Robj) -> Option<String> {
+        let mut lines = Vec::new();
+        diff_into(self, other, "", &mut lines);
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+}
+
+fn diff_into(left: &Robj, right: &Robj, path: &str, lines: &mut Vec<String>) {
+    if left == right {
+        return;
+    }
+    let prefix = if path.is_empty() {
+        String::new()
+    } else {
+        format!("{}: ", path)
+    };
+    let show = |robj: &Robj| robj.format_limited(DIFF_MAX_ELEMENTS, 80);
+    if left.rtype() != right.rtype() {
+        lines.push(format!(
+            "{}type: {:?} vs {:?}",
+            prefix,
+            left.rtype(),
+            right.rtype()
+        ));
+        return;
+    }
+    let reported = lines.len();
+    if left.len() != right.len() {
+        lines.push(format!(
+            "{}length: {} vs {}",
+            prefix,
+            left.len(),
+            right.len()
+        ));
+    }
+
+    let left_attributes = attribute_pairs(left);
+    let right_attributes = attribute_pairs(right);
+    let find = |attributes: &[(String, Robj)], name: &str| {
+        attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.clone())
+    };
+    let mut names: Vec<&String> = left_attributes.iter().map(|(name, _)| name).collect();
+    names.extend(
+        right_attributes
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| find(&left_attributes, name).is_none()),
+    );
+    for name in names {
+        let left_value = find(&left_attributes, name);
+        let right_value = find(&right_attributes, name);
+        if left_value != right_value {
+            let show_attribute = |value: &Option<Robj>| match value {
+                Some(value) => show(value),
+                None => "absent".to_string(),
+            };
+            lines.push(format!(
+                "{}attribute `{}`: {} vs {}",
+                prefix,
+                name,
+                show_attribute(&left_value),
+                show_attribute(&right_value)
+            ));
+        }
+    }
+
+    if left.is_vector() {
+        let mut differing = 0;
+        for i in 0..left.len().min(right.len()) {
+            let (left_elt, right_elt) = (element(left, i), element(right, i));
+            if left_elt == right_elt {
+                continue;
+            }
+            if differing == DIFF_MAX_ELEMENTS {
+                lines.push(format!("{}...", path));
+                break;
+            }
+            differing += 1;
+            if left.is_list() {
+                let path = format!("{}[[{}]]", path, i + 1);
+                diff_into(&left_elt, &right_elt, &path, lines);
+            } else {
+                lines.push(format!(
+                    "{}[{}]: {} vs {}",
+                    path,
+                    i + 1,
+                    show(&left_elt),
+                    show(&right_elt)
+                ));
+            }
+        }
+    }
+
+    // Objects such as functions and environments that differ in other ways.
+    if lines.len() == reported {
+        lines.push(format!("{}{} vs {}", prefix, show(left), show(right)));
+    }
+}
+
+// Element `i` of a vector, without attributes.
+fn element(robj: &Robj, i: usize) -> Robj {
+    fn elt<'a, T>(robj: &'a Robj, i: usize) -> Robj
+    where
+        Robj: AsTypedSlice<'a, T>,
+        T: Clone + Into<Robj> + 'a,
+    {
+        robj.as_typed_slice().unwrap()[i].clone().into()
+    }
+    match robj.sexptype() {
+        SEXPTYPE::LGLSXP => elt::<Rbool>(robj, i),
+        SEXPTYPE::INTSXP => elt::<Rint>(robj, i),
+        SEXPTYPE::REALSXP => elt::<Rfloat>(robj, i),
+        SEXPTYPE::CPLXSXP => elt::<Rcplx>(robj, i),
+        SEXPTYPE::STRSXP => elt::<Rstr>(robj, i),
+        SEXPTYPE::RAWSXP => elt::<u8>(robj, i),
+        _ => List::try_from(robj)
+            .and_then(|list| list.elt(i))
+            .unwrap_or_default(),
+    }
+}
+
+/// Implement {:?} formatting, showing at most [`DEBUG_MAX_ELEMENTS`] elements of vectors.
+/// Implement {:?} formatting, which shows an object as the extendr
+/// macros that make it, with at most [`DEBUG_MAX_ELEMENTS`] elements.
+///
+/// The alternate form `{:#?}`, used by `dbg!()`, shows the output of R's
+/// `print()` instead, truncated after [`DISPLAY_MAX_LINES`] lines.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let robj = R!("c(a = 1.5, b = 2)")?;
+///     assert_eq!(format!("{:#?}", robj), "  a   b \n1.5 2.0 ");
+///     assert_eq!(format!("{:#?}", r!(Integers::from_values(0..1000))).lines().count(), 21);
+/// }
+/// ```
 impl std::fmt::Debug for Robj {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.as_any() {
+        if f.alternate() {
+            fmt_printed(self, f)
+        } else if self.is_vector() && self.len() > DEBUG_MAX_ELEMENTS {
+            write!(f, "{}", self.format_limited(DEBUG_MAX_ELEMENTS, usize::MAX))
+        } else {
+            Untruncated(self).fmt(f)
+        }
+    }
+}
+
+// Formats all the elements of an object.
+struct Untruncated<'a>(&'a Robj);
+
+impl std::fmt::Debug for Untruncated<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.as_any() {
             Rany::Null(_) => write!(f, "()"),
             Rany::Symbol(value) => value.fmt(f),
             Rany::Pairlist(value) => value.fmt(f),
@@ -178,7 +466,7 @@ impl std::fmt::Debug for Robj {
                    _ => write!(f, "??"),
                }?;
         */
-        if let Some(c) = self.class() {
+        if let Some(c) = self.0.class() {
             write!(f, ".set_class({:?}", c)?;
         }
         Ok(())
@@ -48,7 +48,7 @@ impl From<()> for Robj {
 /// * `result_list`: `Ok(T)` is encoded as `list(ok = x_ok, err = NULL)` and `Err` as `list(ok = NULL, err = e_err)`.
 /// * `result_condition'`: `Ok(T)` is encoded as `x_ok` and `Err(E)` as `condition(msg="extendr_error", value = x_err, class=c("extendr_error", "error", "condition"))`
 /// * More than one enabled feature: Only one feature gate will take effect, the current order of precedence is [`result_list`, `result_condition`, ... ].
-/// * Neither of the above (default): `Ok(T)` is encoded as `x_ok`and `Err(E)` will trigger `throw_r_error()`, which is discouraged.
+/// * Neither of the above (default): `Ok(T)` is encoded as `x_ok`and `Err(E)` panics, which raises an R error of class `extendr_error` with the type of `E` in its `rust_type` field.
 /// ```
 /// use extendr_api::prelude::*;
 /// fn my_func() -> Result<f64> {
@@ -68,7 +68,14 @@ where
     E: std::fmt::Debug,
 {
     fn from(res: std::result::Result<T, E>) -> Self {
-        res.unwrap().into()
+        match res {
+            Ok(x) => x.into(),
+            Err(e) => {
+                // Lets the wrapper report the error type in the R condition.
+                crate::error::set_panic_error_type(std::any::type_name::<E>());
+                panic!("called `Result::unwrap()` on an `Err` value: {:?}", e)
+            }
+        }
     }
 }
 
@@ -407,6 +414,48 @@ macro_rules! impl_str_tvv {
 impl_str_tvv! {&str}
 impl_str_tvv! {String}
 
+// Paths and OS strings are stored as UTF-8, replacing invalid characters.
+macro_rules! impl_os_str_tvv {
+    ($t: ty) => {
+        impl ToVectorValue for $t {
+            fn sexptype() -> SEXPTYPE {
+                SEXPTYPE::STRSXP
+            }
+
+            fn to_sexp(&self) -> SEXP
+            where
+                Self: Sized,
+            {
+                str_to_character(&self.to_string_lossy())
+            }
+        }
+
+        impl ToVectorValue for Option<$t> {
+            fn sexptype() -> SEXPTYPE {
+                SEXPTYPE::STRSXP
+            }
+
+            fn to_sexp(&self) -> SEXP
+            where
+                Self: Sized,
+            {
+                if let Some(s) = self {
+                    str_to_character(&s.to_string_lossy())
+                } else {
+                    unsafe { R_NaString }
+                }
+            }
+        }
+    };
+}
+
+impl_os_str_tvv! {std::path::PathBuf}
+impl_os_str_tvv! {&std::path::PathBuf}
+impl_os_str_tvv! {&std::path::Path}
+impl_os_str_tvv! {std::ffi::OsString}
+impl_os_str_tvv! {&std::ffi::OsString}
+impl_os_str_tvv! {&std::ffi::OsStr}
+
 impl ToVectorValue for bool {
     fn sexptype() -> SEXPTYPE {
         SEXPTYPE::LGLSXP
@@ -474,7 +523,7 @@ impl ToVectorValue for Option<bool> {
 }
 
 // Not thread safe.
-fn fixed_size_collect<I>(iter: I, len: usize) -> Robj
+pub(crate) fn fixed_size_collect<I>(iter: I, len: usize) -> Robj
 where
     I: Iterator,
     I: Sized,
@@ -616,35 +665,46 @@ where
     T: ToVectorValue,
 {
     fn from(scalar: T) -> Self {
-        Some(scalar).into_iter().collect_robj()
+        scalar_to_robj(scalar)
     }
 }
 
-macro_rules! impl_from_as_iterator {
-    ($t: ty) => {
-        impl<T> From<$t> for Robj
-        where
-            $t: RobjItertools,
-            <$t as Iterator>::Item: ToVectorValue,
-            T: ToVectorValue,
-        {
-            fn from(val: $t) -> Self {
-                val.collect_robj()
+/// Make a length-1 vector with R's `Rf_Scalar*` constructors,
+/// skipping the iterator machinery of [`RobjItertools::collect_robj`].
+fn scalar_to_robj<T: ToVectorValue>(scalar: T) -> Robj {
+    single_threaded(|| unsafe {
+        let sexp = match T::sexptype() {
+            SEXPTYPE::REALSXP => Rf_ScalarReal(scalar.to_real()),
+            SEXPTYPE::INTSXP => Rf_ScalarInteger(scalar.to_integer()),
+            SEXPTYPE::CPLXSXP => Rf_ScalarComplex(scalar.to_complex()),
+            SEXPTYPE::RAWSXP => Rf_ScalarRaw(scalar.to_raw()),
+            SEXPTYPE::STRSXP => {
+                let charsxp = Rf_protect(scalar.to_sexp());
+                let sexp = Rf_ScalarString(charsxp);
+                Rf_unprotect(1);
+                sexp
             }
-        }
-    };
+            // `Rf_ScalarLogical` returns shared objects which must not be
+            // modified in place, so allocate a fresh one here.
+            SEXPTYPE::LGLSXP => {
+                let sexp = Rf_allocVector(SEXPTYPE::LGLSXP, 1);
+                *LOGICAL(sexp) = scalar.to_logical();
+                sexp
+            }
+            _ => return Robj::from(()),
+        };
+        Robj::from_sexp(sexp)
+    })
 }
 
-// impl<T> From<Range<T>> for Robj
-// where
-//     Range<T> : RobjItertools,
-//     <Range<T> as Iterator>::Item: ToVectorValue,
-//     T : ToVectorValue
-// {
-//     fn from(val: Range<T>) -> Self {
-//         val.collect_robj()
-//     }
-// } //
+/// Used by `#[extendr]` wrappers of functions returning `bool` or `Rbool`.
+///
+/// R keeps single `TRUE`, `FALSE` and `NA` logical objects, so no allocation
+/// takes place. The result is shared and must be handed straight back to R.
+#[doc(hidden)]
+pub fn shared_logical_scalar<T: ToVectorValue>(value: T) -> Robj {
+    single_threaded(|| unsafe { Robj::from_sexp(Rf_ScalarLogical(value.to_logical())) })
+}
 
 impl<'a, T, const N: usize> From<[T; N]> for Robj
 where
@@ -690,6 +750,30 @@ impl<T: ToVectorValue> From<Vec<T>> for Robj {
     }
 }
 
+impl<T: ToVectorValue> From<std::collections::HashSet<T>> for Robj {
+    /// Convert a set into a vector of its elements, in no particular order.
+    fn from(value: std::collections::HashSet<T>) -> Self {
+        let len = value.len();
+        fixed_size_collect(value.into_iter(), len)
+    }
+}
+
+impl<T: ToVectorValue> From<std::collections::BTreeSet<T>> for Robj {
+    /// Convert a set into a vector of its elements, in ascending order.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::collections::BTreeSet;
+    /// test! {
+    ///     let ids = BTreeSet::from([3, 1, 2]);
+    ///     assert_eq!(Robj::from(ids), r!([1, 2, 3]));
+    /// }
+    /// ```
+    fn from(value: std::collections::BTreeSet<T>) -> Self {
+        let len = value.len();
+        fixed_size_collect(value.into_iter(), len)
+    }
+}
+
 impl<'a, T> From<&'a [T]> for Robj
 where
     Self: 'a,
@@ -701,8 +785,81 @@ where
     }
 }
 
-impl_from_as_iterator! {Range<T>}
-impl_from_as_iterator! {RangeInclusive<T>}
+// Shorter integer ranges are collected, as that is cheaper than calling R.
+const COMPACT_RANGE_MIN_LEN: i64 = 1024;
+
+// The integers `from..=to` as a compact sequence, which R's `from:to` makes,
+// or `None` if the range is short or a bound is `NA`, i.e. `i32::MIN`.
+// The elements of a compact sequence are computed when they are used
+// rather than stored, but making one evaluates a call in R.
+fn compact_int_range(from: i32, to: i32) -> Option<Robj> {
+    use crate as extendr_api;
+    if from.is_na() || to.is_na() || (to as i64 - from as i64) < COMPACT_RANGE_MIN_LEN {
+        return None;
+    }
+    let range = lang!(":", from, to)
+        .eval_with_env(&base_env())
+        .expect("Internal error: failed to make an integer sequence");
+    Some(range)
+}
+
+impl<T> From<Range<T>> for Robj
+where
+    Range<T>: RobjItertools,
+    <Range<T> as Iterator>::Item: ToVectorValue,
+    T: ToVectorValue,
+{
+    /// Convert a range to a vector. Long ranges of integers become compact
+    /// sequences, like those of R's `:`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(Robj::from(1..4), R!("1:3")?);
+    ///     assert_eq!(Robj::from(1..100_001), R!("1:100000")?);
+    ///     assert_eq!(Robj::from(0..0), r!(Integers::new(0)));
+    ///     assert_eq!(Robj::from(0_u8..3), r!([0_u8, 1, 2]));
+    ///     assert_eq!(Robj::from(i32::MIN..i32::MIN + 2000).len(), 2000);
+    /// }
+    /// ```
+    fn from(val: Range<T>) -> Self {
+        if T::sexptype() == SEXPTYPE::INTSXP {
+            let (from, end) = (val.start.to_integer(), val.end.to_integer());
+            if from < end {
+                if let Some(range) = compact_int_range(from, end - 1) {
+                    return range;
+                }
+            }
+        }
+        val.collect_robj()
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for Robj
+where
+    RangeInclusive<T>: RobjItertools,
+    <RangeInclusive<T> as Iterator>::Item: ToVectorValue,
+    T: ToVectorValue + PartialOrd,
+{
+    /// Convert a range to a vector. Long ranges of integers become compact
+    /// sequences, like those of R's `:`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(Robj::from(1..=100), R!("1:100")?);
+    ///     assert_eq!(Robj::from(-2..=-2), r!(-2));
+    ///     assert_eq!(Robj::from(i32::MIN..=i32::MIN + 1).len(), 2);
+    /// }
+    /// ```
+    fn from(val: RangeInclusive<T>) -> Self {
+        if T::sexptype() == SEXPTYPE::INTSXP && !val.is_empty() {
+            let (from, to) = (val.start().to_integer(), val.end().to_integer());
+            if let Some(range) = compact_int_range(from, to) {
+                return range;
+            }
+        }
+        val.collect_robj()
+    }
+}
 
 impl From<Vec<Robj>> for Robj {
     /// Convert a vector of Robj into a list.
@@ -711,6 +868,23 @@ impl From<Vec<Robj>> for Robj {
     }
 }
 
+impl<T: ToVectorValue> From<Vec<Vec<T>>> for Robj {
+    /// Convert a vector of vectors into a list of vectors, such as ragged data.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let groups = vec![vec![1.5, 2.0], vec![], vec![3.0]];
+    ///     assert_eq!(Robj::from(groups), R!("list(c(1.5, 2), double(), 3)")?);
+    ///
+    ///     let tokens = vec![vec!["a", "b"], vec!["c"]];
+    ///     assert_eq!(Robj::from(tokens), R!("list(c('a', 'b'), 'c')")?);
+    /// }
+    /// ```
+    fn from(val: Vec<Vec<T>>) -> Self {
+        List::from_values(val.into_iter().map(Robj::from)).into()
+    }
+}
+
 impl From<Vec<Rstr>> for Robj {
     /// Convert a vector of Rstr into strings.
     fn from(val: Vec<Rstr>) -> Self {
@@ -718,6 +892,46 @@ impl From<Vec<Rstr>> for Robj {
     }
 }
 
+impl From<std::time::Duration> for Robj {
+    /// Convert a duration to a `difftime` in seconds.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let elapsed = Robj::from(std::time::Duration::from_millis(1500));
+    ///     assert_eq!(elapsed, R!("as.difftime(1.5, units = 'secs')")?);
+    /// }
+    /// ```
+    fn from(value: std::time::Duration) -> Self {
+        let mut robj = Robj::from(value.as_secs_f64());
+        robj.set_attrib("units", "secs")
+            .and_then(|robj| robj.set_class(["difftime"]))
+            .expect("Internal error: failed to set difftime attributes");
+        robj
+    }
+}
+
+impl From<std::time::SystemTime> for Robj {
+    /// Convert a point in time to a `POSIXct`, like `Sys.time()`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// test! {
+    ///     let time = Robj::from(UNIX_EPOCH + Duration::from_secs(86400));
+    ///     assert_eq!(time, R!("structure(86400, class = c('POSIXct', 'POSIXt'))")?);
+    /// }
+    /// ```
+    fn from(value: std::time::SystemTime) -> Self {
+        let seconds = match value.duration_since(std::time::UNIX_EPOCH) {
+            Ok(after) => after.as_secs_f64(),
+            Err(before) => -before.duration().as_secs_f64(),
+        };
+        let mut robj = Robj::from(seconds);
+        robj.set_class(["POSIXct", "POSIXt"])
+            .expect("Internal error: failed to set POSIXct class");
+        robj
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -738,6 +952,24 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_scalar_to_robj() {
+        test! {
+            assert_eq!(Robj::from(1.5), r!([1.5]));
+            assert_eq!(Robj::from(42), r!([42]));
+            assert_eq!(Robj::from(2_u8), r!([2_u8]));
+            assert_eq!(Robj::from(true), r!([true]));
+            assert_eq!(Robj::from(NA_LOGICAL), R!("NA").unwrap());
+            assert_eq!(Robj::from("abc"), r!(["abc"]));
+            assert_eq!(Robj::from(NA_STRING), R!("NA_character_").unwrap());
+            assert_eq!(Robj::from(Rcplx::from((1., 2.))), R!("1+2i").unwrap());
+
+            // Shared logicals compare equal to freshly allocated ones.
+            assert_eq!(shared_logical_scalar(false), Robj::from(false));
+            assert_eq!(shared_logical_scalar(NA_LOGICAL), Robj::from(NA_LOGICAL));
+        }
+    }
+
     #[test]
     fn test_collect_rarray_matrix() {
         test! {
@@ -0,0 +1,212 @@
+//! Numeric kernels for [`Reductions`](super::Reductions) and the arithmetic
+//! operators of `Robj`.
+//!
+//! These process the data in chunks of `LANES` values with one accumulator
+//! per lane and no branches in the inner loops, so that the compiler
+//! vectorizes them on stable Rust. Note that this sums doubles in a different
+//! order than R, so sums can differ from R's in the last bits.
+//!
+//! Run `cargo bench --bench kernels` to compare them to the R functions.
+
+const LANES: usize = 8;
+
+// Integer sums are moved from the `i64` lanes to the total after this many
+// values, before the lanes can overflow.
+const INT_BLOCK: usize = 1 << 20;
+
+/// The sum of the non-`NA` integers, and how many there are.
+pub(crate) fn sum_i32(values: &[i32]) -> (i128, usize) {
+    let mut total = 0_i128;
+    let mut count = 0_usize;
+    for block in values.chunks(INT_BLOCK) {
+        let mut sums = [0_i64; LANES];
+        let mut counts = [0_usize; LANES];
+        let chunks = block.chunks_exact(LANES);
+        let rest = chunks.remainder();
+        for chunk in chunks {
+            for ((sum, count), &value) in sums.iter_mut().zip(counts.iter_mut()).zip(chunk) {
+                let keep = value != i32::MIN;
+                *sum += if keep { value as i64 } else { 0 };
+                *count += keep as usize;
+            }
+        }
+        for &value in rest {
+            let keep = value != i32::MIN;
+            sums[0] += if keep { value as i64 } else { 0 };
+            counts[0] += keep as usize;
+        }
+        total += sums.iter().sum::<i64>() as i128;
+        count += counts.iter().sum::<usize>();
+    }
+    (total, count)
+}
+
+/// The sum of `value - offset` over the values, skipping `NaN` (including
+/// `NA`) if `skip_nan`, and how many values were summed.
+pub(crate) fn sum_f64(values: &[f64], skip_nan: bool, offset: f64) -> (f64, usize) {
+    let mut sums = [0_f64; LANES];
+    let mut counts = [0_usize; LANES];
+    let chunks = values.chunks_exact(LANES);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for ((sum, count), &value) in sums.iter_mut().zip(counts.iter_mut()).zip(chunk) {
+            let keep = !(skip_nan && value.is_nan());
+            *sum += if keep { value - offset } else { 0. };
+            *count += keep as usize;
+        }
+    }
+    for &value in rest {
+        let keep = !(skip_nan && value.is_nan());
+        sums[0] += if keep { value - offset } else { 0. };
+        counts[0] += keep as usize;
+    }
+    (sums.iter().sum(), counts.iter().sum())
+}
+
+/// The minimum or maximum of the non-`NA` integers, if there are any,
+/// and whether there is an `NA`.
+pub(crate) fn extreme_i32(values: &[i32], is_max: bool) -> (Option<i32>, bool) {
+    // `NA` is `i32::MIN`, which never wins the maximum, and which is
+    // replaced by `i32::MAX` for the minimum.
+    let (start, na_value) = if is_max {
+        (i32::MIN, i32::MIN)
+    } else {
+        (i32::MAX, i32::MAX)
+    };
+    let pick = |best: i32, value: i32| {
+        let value = if value == i32::MIN { na_value } else { value };
+        if (value > best) == is_max {
+            value
+        } else {
+            best
+        }
+    };
+    let mut bests = [start; LANES];
+    let mut nas = [0_usize; LANES];
+    let chunks = values.chunks_exact(LANES);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for ((best, na), &value) in bests.iter_mut().zip(nas.iter_mut()).zip(chunk) {
+            *best = pick(*best, value);
+            *na += (value == i32::MIN) as usize;
+        }
+    }
+    for &value in rest {
+        bests[0] = pick(bests[0], value);
+        nas[0] += (value == i32::MIN) as usize;
+    }
+    let na_count = nas.iter().sum::<usize>();
+    let best = bests.iter().fold(start, |best, &value| pick(best, value));
+    let best = if na_count < values.len() {
+        Some(best)
+    } else {
+        None
+    };
+    (best, na_count > 0)
+}
+
+/// The minimum or maximum of the values that are not `NaN`, which is
+/// `Inf` or `-Inf` if there are none, and whether there is a `NaN`.
+pub(crate) fn extreme_f64(values: &[f64], is_max: bool) -> (f64, bool) {
+    let start = if is_max {
+        f64::NEG_INFINITY
+    } else {
+        f64::INFINITY
+    };
+    // Comparisons with `NaN` are false, so `NaN` never wins.
+    let pick = |best: f64, value: f64| {
+        if (is_max && value > best) || (!is_max && value < best) {
+            value
+        } else {
+            best
+        }
+    };
+    let mut bests = [start; LANES];
+    let mut nans = [false; LANES];
+    let chunks = values.chunks_exact(LANES);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for ((best, nan), &value) in bests.iter_mut().zip(nans.iter_mut()).zip(chunk) {
+            *best = pick(*best, value);
+            *nan |= value.is_nan();
+        }
+    }
+    for &value in rest {
+        bests[0] = pick(bests[0], value);
+        nans[0] |= value.is_nan();
+    }
+    let best = bests.iter().fold(start, |best, &value| pick(best, value));
+    (best, nans.iter().any(|&nan| nan))
+}
+
+/// Apply `op` element-wise, recycling `lhs` or `rhs` if it has one value.
+/// `out` has the length of the longer operand.
+pub(crate) fn binary_f64<F>(lhs: &[f64], rhs: &[f64], out: &mut [f64], op: F)
+where
+    F: Fn(f64, f64) -> f64,
+{
+    match (lhs.len(), rhs.len()) {
+        (1, _) => {
+            let lhs = lhs[0];
+            for (out, &rhs) in out.iter_mut().zip(rhs) {
+                *out = op(lhs, rhs);
+            }
+        }
+        (_, 1) => {
+            let rhs = rhs[0];
+            for (out, &lhs) in out.iter_mut().zip(lhs) {
+                *out = op(lhs, rhs);
+            }
+        }
+        _ => {
+            for ((out, &lhs), &rhs) in out.iter_mut().zip(lhs).zip(rhs) {
+                *out = op(lhs, rhs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernels() {
+        let ints: Vec<i32> = (0..100)
+            .map(|i| if i % 7 == 0 { i32::MIN } else { i - 50 })
+            .collect();
+        let expected: Vec<i32> = ints.iter().copied().filter(|&i| i != i32::MIN).collect();
+        let (total, count) = sum_i32(&ints);
+        assert_eq!(total, expected.iter().map(|&i| i as i128).sum::<i128>());
+        assert_eq!(count, expected.len());
+        assert_eq!(
+            extreme_i32(&ints, false),
+            (expected.iter().min().copied(), true)
+        );
+        assert_eq!(
+            extreme_i32(&ints, true),
+            (expected.iter().max().copied(), true)
+        );
+        assert_eq!(extreme_i32(&[i32::MIN; 3], true), (None, true));
+        assert_eq!(extreme_i32(&[], false), (None, false));
+
+        let doubles: Vec<f64> = (0..21).map(|i| i as f64 * 0.5).collect();
+        assert_eq!(sum_f64(&doubles, false, 0.), (105., 21));
+        assert_eq!(sum_f64(&doubles, false, 5.), (0., 21));
+        let mut with_nan = doubles.clone();
+        with_nan[3] = f64::NAN;
+        assert!(sum_f64(&with_nan, false, 0.).0.is_nan());
+        assert_eq!(sum_f64(&with_nan, true, 0.), (103.5, 20));
+        assert_eq!(extreme_f64(&with_nan, true), (10., true));
+        assert_eq!(extreme_f64(&with_nan, false), (0., true));
+        assert_eq!(extreme_f64(&[], false), (f64::INFINITY, false));
+
+        let mut out = vec![0.; 3];
+        binary_f64(&[1., 2., 3.], &[10.], &mut out, |a, b| a * b);
+        assert_eq!(out, vec![10., 20., 30.]);
+        binary_f64(&[1.], &[1., 2., 4.], &mut out, |a, b| a / b);
+        assert_eq!(out, vec![1., 0.5, 0.25]);
+        binary_f64(&[1., 2., 3.], &[3., 2., 1.], &mut out, |a, b| a - b);
+        assert_eq!(out, vec![-2., 0., 2.]);
+    }
+}
@@ -17,18 +17,25 @@ use std::os::raw;
 use libR_sys::*;
 use SEXPTYPE::*;
 
+pub use builder::{RVectorBuilder, RobjBuilder};
+pub use debug::{DEBUG_MAX_ELEMENTS, DIFF_MAX_ELEMENTS, DISPLAY_MAX_LINES};
 pub use into_robj::*;
 pub use iter::*;
 pub use operators::Operators;
 use prelude::{c64, Rcplx};
+pub use reductions::Reductions;
 pub use rinternals::Rinternals;
+pub use try_from_robj::Distinct;
 
-use crate::scalar::{Rbool, Rfloat, Rint};
+use crate::scalar::{Rbool, Rfloat, Rint, Scalar};
 use crate::*;
 
+mod builder;
 mod debug;
 mod into_robj;
+mod kernels;
 mod operators;
+mod reductions;
 mod rinternals;
 mod try_from_robj;
 
@@ -383,6 +390,39 @@ impl Robj {
         self.as_typed_slice()
     }
 
+    /// Get an iterator over the elements of an integer vector,
+    /// with `None` for `NA`. Returns `None` if not an integer vector.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let robj = r!([Some(1), None, Some(3)]);
+    ///     let values = robj.as_integer_iter().unwrap().collect::<Vec<_>>();
+    ///     assert_eq!(values, [Some(1), None, Some(3)]);
+    ///     assert!(r!([1., 2.]).as_integer_iter().is_none());
+    /// }
+    /// ```
+    pub fn as_integer_iter(&self) -> Option<impl Iterator<Item = Option<i32>> + '_> {
+        self.as_integer_slice()
+            .map(|slice| slice.iter().map(|&value| (!value.is_na()).then_some(value)))
+    }
+
+    /// Get an iterator over the elements of a character vector or the labels
+    /// of a factor, with `None` for `NA`. Returns `None` for other objects.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let robj = r!([Some("a"), None, Some("NA")]);
+    ///     let values = robj.as_string_iter().unwrap().collect::<Vec<_>>();
+    ///     assert_eq!(values, [Some("a"), None, Some("NA")]);
+    ///     let factor = R!("factor(c('x', NA))")?;
+    ///     assert_eq!(factor.as_string_iter().unwrap().collect::<Vec<_>>(), [Some("x"), None]);
+    /// }
+    /// ```
+    pub fn as_string_iter(&self) -> Option<impl Iterator<Item = Option<&str>> + '_> {
+        self.as_str_iter()
+            .map(|iter| iter.map(|value| (!value.is_na()).then_some(value)))
+    }
+
     /// Convert an [`Robj`] into [`Integers`].
     pub fn as_integers(&self) -> Option<Integers> {
         self.clone().try_into().ok()
@@ -390,16 +430,27 @@ impl Robj {
 
     /// Get a `Vec<i32>` copied from the object.
     ///
+    /// Other atomic vectors are coerced as by `as.integer()` in R:
+    /// doubles are truncated towards zero, and values that can not be
+    /// represented become `NA` with an R warning.
+    /// Returns `None` if the object is not an atomic vector.
+    ///
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
-    ///
-    /// let robj = r!([1, 2, 3]);
-    /// assert_eq!(robj.as_integer_slice().unwrap(), vec![1, 2, 3]);
+    ///     let robj = r!([1, 2, 3]);
+    ///     assert_eq!(robj.as_integer_vector().unwrap(), vec![1, 2, 3]);
+    ///     let robj = r!([Some(1.9), None, Some(-2.5)]);
+    ///     assert_eq!(robj.as_integer_vector().unwrap(), vec![1, i32::na(), -2]);
+    ///     let robj = r!([TRUE, FALSE, NA_LOGICAL]);
+    ///     assert_eq!(robj.as_integer_vector().unwrap(), vec![1, 0, i32::na()]);
+    ///     assert_eq!(r!(list!(1)).as_integer_vector(), None);
     /// }
     /// ```
     pub fn as_integer_vector(&self) -> Option<Vec<i32>> {
-        self.as_integer_slice().map(|value| value.to_vec())
+        self.coerce_atomic(SEXPTYPE::INTSXP)?
+            .as_integer_slice()
+            .map(|value| value.to_vec())
     }
 
     /// Get a read-only reference to the content of a logical vector
@@ -417,35 +468,47 @@ impl Robj {
 
     /// Get a `Vec<Rbool>` copied from the object
     /// using the tri-state [`Rbool`].
-    /// Returns `None` if not a logical vector.
+    ///
+    /// Other atomic vectors are coerced as by `as.logical()` in R:
+    /// non-zero numbers are `TRUE`, and `NA`, `NaN` and unrecognised
+    /// strings become `NA`.
+    /// Returns `None` if the object is not an atomic vector.
     ///
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
     ///     let robj = r!([TRUE, FALSE]);
     ///     assert_eq!(robj.as_logical_vector().unwrap(), vec![TRUE, FALSE]);
+    ///     let values = r!([Some(0), Some(2), None]).as_logical_vector().unwrap();
+    ///     assert_eq!(&values[..2], &[FALSE, TRUE]);
+    ///     assert!(values[2].is_na());
+    ///     let values = r!(["T", "false", "maybe"]).as_logical_vector().unwrap();
+    ///     assert_eq!(&values[..2], &[TRUE, FALSE]);
+    ///     assert!(values[2].is_na());
     /// }
     /// ```
     pub fn as_logical_vector(&self) -> Option<Vec<Rbool>> {
-        self.as_logical_slice().map(|value| value.to_vec())
+        self.coerce_atomic(SEXPTYPE::LGLSXP)?
+            .as_logical_slice()
+            .map(|value| value.to_vec())
     }
 
-    /// Get an iterator over logical elements of this slice.
+    /// Get an iterator over the elements of a logical vector,
+    /// with `None` for `NA`. Returns `None` if not a logical vector.
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
     ///     let robj = r!([TRUE, FALSE, NA_LOGICAL]);
-    ///     let mut num_na = 0;
-    ///     for val in robj.as_logical_iter().unwrap() {
-    ///       if val.is_na() {
-    ///           num_na += 1;
-    ///       }
-    ///     }
-    ///     assert_eq!(num_na, 1);
+    ///     let values = robj.as_logical_iter().unwrap().collect::<Vec<_>>();
+    ///     assert_eq!(values, [Some(true), Some(false), None]);
     /// }
     /// ```
-    pub fn as_logical_iter(&self) -> Option<impl Iterator<Item = &Rbool>> {
-        self.as_logical_slice().map(|slice| slice.iter())
+    pub fn as_logical_iter(&self) -> Option<impl Iterator<Item = Option<bool>> + '_> {
+        self.as_logical_slice().map(|slice| {
+            slice
+                .iter()
+                .map(|value| (!value.is_na()).then(|| value.is_true()))
+        })
     }
 
     /// Get a read-only reference to the content of a double vector.
@@ -468,36 +531,63 @@ impl Robj {
         self.as_typed_slice()
     }
 
-    /// Get an iterator over real elements of this slice.
-    ///
+    /// Get an iterator over the elements of a double vector,
+    /// with `None` for `NA`. `NaN` values that are not `NA` are kept.
+    /// Returns `None` if not a double vector.
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
-    ///     let robj = r!([1., 2., 3.]);
-    ///     let mut tot = 0.;
-    ///     for val in robj.as_real_iter().unwrap() {
-    ///       if !val.is_na() {
-    ///         tot += val;
-    ///       }
-    ///     }
-    ///     assert_eq!(tot, 6.);
+    ///     let robj = r!([Some(1.), None, Some(3.)]);
+    ///     let tot: f64 = robj.as_real_iter().unwrap().flatten().sum();
+    ///     assert_eq!(tot, 4.);
+    ///     assert!(R!("NaN")?.as_real_iter().unwrap().next().unwrap().is_some());
     /// }
     /// ```
-    pub fn as_real_iter(&self) -> Option<impl Iterator<Item = &f64>> {
-        self.as_real_slice().map(|slice| slice.iter())
+    pub fn as_real_iter(&self) -> Option<impl Iterator<Item = Option<f64>> + '_> {
+        self.as_real_slice()
+            .map(|slice| slice.iter().map(|&value| (!value.is_na()).then_some(value)))
     }
 
     /// Get a `Vec<f64>` copied from the object.
     ///
+    /// Other atomic vectors are coerced as by `as.double()` in R:
+    /// `NA` stays `NA`, and strings that are not numbers become `NA`
+    /// with an R warning.
+    /// Returns `None` if the object is not an atomic vector.
+    ///
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
     ///     let robj = r!([1., 2., 3.]);
     ///     assert_eq!(robj.as_real_vector().unwrap(), vec![1., 2., 3.]);
+    ///     let robj = r!([Some(1), None]);
+    ///     let values = robj.as_real_vector().unwrap();
+    ///     assert_eq!(values[0], 1.);
+    ///     assert!(values[1].is_na());
+    ///     let robj = r!(["1.5", "1e3"]);
+    ///     assert_eq!(robj.as_real_vector().unwrap(), vec![1.5, 1000.]);
     /// }
     /// ```
     pub fn as_real_vector(&self) -> Option<Vec<f64>> {
-        self.as_real_slice().map(|value| value.to_vec())
+        self.coerce_atomic(SEXPTYPE::REALSXP)?
+            .as_real_slice()
+            .map(|value| value.to_vec())
+    }
+
+    // Coerce an atomic vector to `sexptype` using R's rules,
+    // returning `None` for other objects or if R raises an error.
+    fn coerce_atomic(&self, sexptype: SEXPTYPE) -> Option<Robj> {
+        let from = self.sexptype();
+        if from == sexptype {
+            return Some(self.clone());
+        }
+        if !matches!(from, LGLSXP | INTSXP | REALSXP | CPLXSXP | STRSXP | RAWSXP) {
+            return None;
+        }
+        let sexp = unsafe { self.get() };
+        catch_r_error(move || unsafe { Rf_coerceVector(sexp, sexptype) })
+            .ok()
+            .map(Robj::from_sexp)
     }
 
     /// Get a read-only reference to the content of an integer or logical vector.
@@ -512,6 +602,39 @@ impl Robj {
         self.as_typed_slice()
     }
 
+    /// Iterate over an atomic vector in slices of `chunk_len` elements,
+    /// the last of which may be shorter. Returns `None` if the vector does
+    /// not have elements of type `T`.
+    ///
+    /// Long computations can check for interrupts between chunks, without
+    /// the cost of checking on every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_len` is zero.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let robj = R!("as.double(1:10)")?;
+    ///     let mut tot = 0.;
+    ///     for chunk in robj.chunks::<f64>(4).unwrap() {
+    ///         check_user_interrupt()?;
+    ///         tot += chunk.iter().sum::<f64>();
+    ///     }
+    ///     assert_eq!(tot, 55.);
+    ///     let lengths = robj.chunks::<f64>(4).unwrap().map(|chunk| chunk.len());
+    ///     assert_eq!(lengths.collect::<Vec<_>>(), vec![4, 4, 2]);
+    ///     assert!(robj.chunks::<i32>(4).is_none());
+    /// }
+    /// ```
+    pub fn chunks<'a, T>(&self, chunk_len: usize) -> Option<std::slice::Chunks<'a, T>>
+    where
+        Self: AsTypedSlice<'a, T>,
+    {
+        assert!(chunk_len != 0, "chunk_len must not be zero");
+        self.as_typed_slice().map(|slice| slice.chunks(chunk_len))
+    }
+
     /// Get a read-write reference to the content of an integer or logical vector.
     /// Note that rust slices are 0-based so `slice[1]` is the middle value.
     /// ```
@@ -690,6 +813,130 @@ impl Robj {
             _ => None,
         }
     }
+
+    /// Get a scalar integer, accepting doubles that are whole numbers.
+    ///
+    /// Returns an error if the length is not one or the value is `NA`.
+    /// Use [`Robj::as_scalar_integer_opt`] to accept `NA` and `NULL`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!(1).as_scalar_integer(), Ok(1));
+    ///     assert_eq!(r!(2.).as_scalar_integer(), Ok(2));
+    ///     assert_eq!(r!([1, 2]).as_scalar_integer(), Err(Error::ExpectedScalar(r!([1, 2]))));
+    ///     assert_eq!(r!(NA_INTEGER).as_scalar_integer(), Err(Error::MustNotBeNA(r!(NA_INTEGER))));
+    ///     assert!(r!(1.5).as_scalar_integer().is_err());
+    /// }
+    /// ```
+    pub fn as_scalar_integer(&self) -> Result<i32> {
+        i32::try_from(self)
+    }
+
+    /// Get a scalar real, accepting integers.
+    ///
+    /// Returns an error if the length is not one or the value is `NA`.
+    /// Use [`Robj::as_scalar_real_opt`] to accept `NA` and `NULL`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!(1.5).as_scalar_real(), Ok(1.5));
+    ///     assert_eq!(r!(2).as_scalar_real(), Ok(2.));
+    ///     assert_eq!(r!(NA_REAL).as_scalar_real(), Err(Error::MustNotBeNA(r!(NA_REAL))));
+    ///     assert!(r!("1").as_scalar_real().is_err());
+    /// }
+    /// ```
+    pub fn as_scalar_real(&self) -> Result<f64> {
+        f64::try_from(self)
+    }
+
+    /// Get a scalar string.
+    ///
+    /// Returns an error if the length is not one or the value is `NA`.
+    /// Use [`Robj::as_scalar_string_opt`] to accept `NA` and `NULL`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!("a").as_scalar_string(), Ok("a".to_string()));
+    ///     assert_eq!(r!(["a", "b"]).as_scalar_string(), Err(Error::ExpectedScalar(r!(["a", "b"]))));
+    ///     assert!(r!(1).as_scalar_string().is_err());
+    /// }
+    /// ```
+    pub fn as_scalar_string(&self) -> Result<String> {
+        String::try_from(self)
+    }
+
+    /// Get a scalar boolean.
+    ///
+    /// Returns an error if the length is not one or the value is `NA`.
+    /// Use [`Robj::as_scalar_bool_opt`] to accept `NA` and `NULL`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!(TRUE).as_scalar_bool(), Ok(true));
+    ///     assert_eq!(r!(NA_LOGICAL).as_scalar_bool(), Err(Error::MustNotBeNA(r!(NA_LOGICAL))));
+    ///     assert!(r!([TRUE, FALSE]).as_scalar_bool().is_err());
+    /// }
+    /// ```
+    pub fn as_scalar_bool(&self) -> Result<bool> {
+        bool::try_from(self)
+    }
+
+    /// Like [`Robj::as_scalar_integer`], but `NA` and `NULL` give `None`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!(1).as_scalar_integer_opt(), Ok(Some(1)));
+    ///     assert_eq!(r!(NA_INTEGER).as_scalar_integer_opt(), Ok(None));
+    ///     assert_eq!(r!(NULL).as_scalar_integer_opt(), Ok(None));
+    ///     assert!(r!([1, 2]).as_scalar_integer_opt().is_err());
+    /// }
+    /// ```
+    pub fn as_scalar_integer_opt(&self) -> Result<Option<i32>> {
+        Option::<i32>::try_from(self)
+    }
+
+    /// Like [`Robj::as_scalar_real`], but `NA` and `NULL` give `None`.
+    pub fn as_scalar_real_opt(&self) -> Result<Option<f64>> {
+        Option::<f64>::try_from(self)
+    }
+
+    /// Like [`Robj::as_scalar_string`], but `NA` and `NULL` give `None`.
+    pub fn as_scalar_string_opt(&self) -> Result<Option<String>> {
+        Option::<String>::try_from(self)
+    }
+
+    /// Like [`Robj::as_scalar_bool`], but `NA` and `NULL` give `None`.
+    pub fn as_scalar_bool_opt(&self) -> Result<Option<bool>> {
+        Option::<bool>::try_from(self)
+    }
+
+    /// Iterate over the names and elements of an atomic vector or list.
+    ///
+    /// Names are `None` if the object has no names, or if the name is
+    /// empty or `NA`. Returns `None` if the object is not a vector.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let list = list!(a = 1, 2, b = "x");
+    ///     let pairs = list.as_robj().pairs().unwrap().collect::<Vec<_>>();
+    ///     assert_eq!(pairs, vec![(Some("a"), r!(1)), (None, r!(2)), (Some("b"), r!("x"))]);
+    ///
+    ///     let vector = R!("c(x = 1L, y = 2L)")?;
+    ///     let pairs = vector.pairs().unwrap().collect::<Vec<_>>();
+    ///     assert_eq!(pairs, vec![(Some("x"), r!(1)), (Some("y"), r!(2))]);
+    ///
+    ///     assert_eq!(r!([1, 2]).pairs().unwrap().next(), Some((None, r!(1))));
+    ///     assert!(r!(Symbol::from_string("x")).pairs().is_none());
+    /// }
+    /// ```
+    pub fn pairs(&self) -> Option<NamedPairIter> {
+        match self.sexptype() {
+            LGLSXP | INTSXP | REALSXP | CPLXSXP | STRSXP | RAWSXP | VECSXP | EXPRSXP => {
+                Some(NamedPairIter::new(self))
+            }
+            _ => None,
+        }
+    }
 }
 
 pub trait Eval: GetSexp {
@@ -1072,7 +1319,101 @@ impl PartialEq<str> for Robj {
     }
 }
 
+impl Robj {
+    /// Compare with R's `identical()` using its default arguments.
+    ///
+    /// Unlike `==`, which ignores the environments of closures,
+    /// closures are only identical if their environments are.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert!(r!([1, 2]).identical(&r!([1, 2])));
+    ///     assert!(!r!([1, 2]).identical(&r!([1.0, 2.0])));
+    ///     let f = R!("function() 1")?;
+    ///     let g = R!("local(function() 1)")?;
+    ///     assert!(f == g);
+    ///     assert!(!f.identical(&g));
+    /// }
+    /// ```
+    pub fn identical(&self, other: &Robj) -> bool {
+        unsafe { R_compute_identical(self.get(), other.get(), 0) != Rboolean::FALSE }
+    }
+
+    /// Compare numeric vectors as R's `all.equal()` does: true if both have the
+    /// same length and `NA`s, and the mean relative difference is at most
+    /// `tolerance` (the mean absolute difference if the values are close to zero).
+    ///
+    /// Integer and double vectors can be compared with each other; attributes are ignored.
+    /// Other objects are compared with `==`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert!(r!([1.0, 2.0]).all_equal(&r!([1.0, 2.0 + 1e-10]), 1.5e-8));
+    ///     assert!(r!([1.0, 2.0]).all_equal(&r!([1, 2]), 1.5e-8));
+    ///     assert!(!r!([1.0, 2.0]).all_equal(&r!([1.0, 2.1]), 1.5e-8));
+    ///     assert!(!r!([1.0, NA_REAL]).all_equal(&r!([1.0, 2.0]), 1.5e-8));
+    /// }
+    /// ```
+    pub fn all_equal(&self, other: &Robj, tolerance: f64) -> bool {
+        let as_numeric = |robj: &Robj| -> Option<Vec<Option<f64>>> {
+            if let Some(slice) = robj.as_real_slice() {
+                Some(
+                    slice
+                        .iter()
+                        .map(|x| if x.is_na() { None } else { Some(*x) })
+                        .collect(),
+                )
+            } else {
+                robj.as_integer_slice().map(|slice| {
+                    slice
+                        .iter()
+                        .map(|x| if x.is_na() { None } else { Some(*x as f64) })
+                        .collect()
+                })
+            }
+        };
+
+        let (target, current) = match (as_numeric(self), as_numeric(other)) {
+            (Some(target), Some(current)) => (target, current),
+            _ => return self == other,
+        };
+        if target.len() != current.len() {
+            return false;
+        }
+
+        let mut abs_diff = 0.0;
+        let mut abs_target = 0.0;
+        let mut count = 0;
+        for (t, c) in target.iter().zip(current.iter()) {
+            match (t, c) {
+                // As in R, equal values (including infinities) are left out.
+                (Some(t), Some(c)) if t == c => {}
+                (Some(t), Some(c)) => {
+                    abs_diff += (t - c).abs();
+                    abs_target += t.abs();
+                    count += 1;
+                }
+                (None, None) => {}
+                _ => return false,
+            }
+        }
+        if count == 0 {
+            return true;
+        }
+
+        let mean_diff = abs_diff / count as f64;
+        let mean_target = abs_target / count as f64;
+        if mean_target.is_finite() && mean_target > tolerance {
+            mean_diff / mean_target <= tolerance
+        } else {
+            mean_diff <= tolerance
+        }
+    }
+}
+
 /// Compare equality with two Robjs.
+///
+/// This is R's `identical()`, except that the environments of closures are ignored.
 impl PartialEq<Robj> for Robj {
     fn eq(&self, rhs: &Robj) -> bool {
         unsafe {
@@ -1086,6 +1427,85 @@ impl PartialEq<Robj> for Robj {
     }
 }
 
+impl Eq for Robj {}
+
+/// Hash the content of an object, consistently with `==`, so that `Robj`
+/// can be used as a key of a `HashMap` or `HashSet`.
+///
+/// Vectors and lists are hashed by type and content, so equal vectors
+/// have equal hashes even if they are different R objects.
+/// As with `==`, `0` and `-0`, and all `NaN`s other than `NA`, hash the same.
+/// Attributes are not hashed, and neither are the contents of other objects,
+/// such as functions and pairlists; these are still correct but may collide.
+/// Environments, symbols and external pointers are hashed by address.
+/// ```
+/// use extendr_api::prelude::*;
+/// use std::collections::HashSet;
+/// test! {
+///     let set: HashSet<Robj> = [r!([1, 2]), r!([1, 2]), r!("a"), r!([1., 2.])]
+///         .into_iter()
+///         .collect();
+///     assert_eq!(set.len(), 3);
+///     assert!(set.contains(&r!("a")));
+/// }
+/// ```
+impl std::hash::Hash for Robj {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        fn hash_f64<H: std::hash::Hasher>(value: f64, state: &mut H) {
+            let bits = if value == 0.0 {
+                0
+            } else if unsafe { R_IsNA(value) } != 0 {
+                1
+            } else if value.is_nan() {
+                2
+            } else {
+                value.to_bits()
+            };
+            state.write_u64(bits);
+        }
+
+        let sexptype = self.sexptype();
+        state.write_u32(sexptype as u32);
+        match sexptype {
+            LGLSXP | INTSXP => {
+                let values = unsafe { self.as_typed_slice_raw::<i32>() };
+                values.hash(state);
+            }
+            REALSXP => {
+                let values = self.as_real_slice().unwrap();
+                state.write_usize(values.len());
+                values.iter().for_each(|&value| hash_f64(value, state));
+            }
+            CPLXSXP => {
+                let values: &[Rcplx] = self.as_typed_slice().unwrap();
+                state.write_usize(values.len());
+                for value in values {
+                    hash_f64(value.re().inner(), state);
+                    hash_f64(value.im().inner(), state);
+                }
+            }
+            STRSXP => {
+                let strings = Strings::try_from(self.clone()).unwrap();
+                state.write_usize(strings.len());
+                for s in strings.iter() {
+                    (!s.is_na()).then(|| s.as_str()).hash(state);
+                }
+            }
+            RAWSXP => self.as_raw_slice().unwrap().hash(state),
+            VECSXP | EXPRSXP => {
+                let len = self.len();
+                state.write_usize(len);
+                for i in 0..len {
+                    let elt = unsafe { Robj::from_sexp(VECTOR_ELT(self.get(), i as R_xlen_t)) };
+                    elt.hash(state);
+                }
+            }
+            SYMSXP | ENVSXP | EXTPTRSXP => unsafe { (self.get() as usize).hash(state) },
+            _ => state.write_usize(self.len()),
+        }
+    }
+}
+
 /// Release any owned objects.
 impl Drop for Robj {
     fn drop(&mut self) {
@@ -1,4 +1,6 @@
+use super::kernels;
 use crate as extendr_api;
+use crate::scalar::{Rfloat, Rint};
 use crate::*;
 use std::ops::{Add, Div, Mul, Sub};
 
@@ -40,6 +42,50 @@ pub trait Operators: Rinternals {
         call!("`[`", self.as_robj(), rhs.into())
     }
 
+    /// Do the equivalent of `x[i]`, with R's subscript rules.
+    ///
+    /// `i` may be positive (1-based) or negative integers, a logical mask,
+    /// which is recycled, or a vector of names. Unlike [`Operators::slice`],
+    /// this takes the index by reference.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let x = R!("c(a = 10L, b = 20L, c = 30L)")?;
+    ///     assert_eq!(x.subset(&r!([1, 3]))?, R!("c(a = 10L, c = 30L)")?);
+    ///     assert_eq!(x.subset(&r!(-2))?, R!("c(a = 10L, c = 30L)")?);
+    ///     assert_eq!(x.subset(&r!([TRUE, FALSE]))?, R!("c(a = 10L, c = 30L)")?);
+    ///     assert_eq!(x.subset(&r!(["b"]))?, R!("c(b = 20L)")?);
+    ///     assert!(x.subset(&r!([-1, 1])).is_err());
+    /// }
+    /// ```
+    fn subset(&self, i: &Robj) -> Result<Robj> {
+        call!("`[`", self.as_robj(), i)
+    }
+
+    /// Do the equivalent of `x[i] <- value`, with the same subscripts as
+    /// [`Operators::subset`].
+    ///
+    /// As in R, the object is copied if it is shared, extended if `i`
+    /// is beyond its end and coerced if `value` has a higher type.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let mut x = r!([1, 2, 3]);
+    ///     x.subset_assign(&r!([TRUE, FALSE, TRUE]), r!(0))?;
+    ///     assert_eq!(x, r!([0, 2, 0]));
+    ///     x.subset_assign(&r!(4), r!(4.5))?;
+    ///     assert_eq!(x, r!([0., 2., 0., 4.5]));
+    /// }
+    /// ```
+    fn subset_assign<V>(&mut self, i: &Robj, value: V) -> Result<()>
+    where
+        V: Into<Robj>,
+    {
+        let result = call!("`[<-`", self.as_robj(), i, value.into())?;
+        *self.as_robj_mut() = result;
+        Ok(())
+    }
+
     /// Do the equivalent of `x[[y]]`
     /// ```
     /// use extendr_api::prelude::*;
@@ -139,7 +185,11 @@ where
     /// }
     /// ```
     fn add(self, rhs: Rhs) -> Self::Output {
-        call!("`+`", self, rhs.into()).expect("Robj add failed")
+        let rhs = rhs.into();
+        if let Some(result) = arith_f64(&self, &rhs, |lhs, rhs| lhs + rhs) {
+            return result;
+        }
+        call!("`+`", self, rhs).expect("Robj add failed")
     }
 }
 
@@ -171,7 +221,11 @@ where
     /// }
     /// ```
     fn sub(self, rhs: Rhs) -> Self::Output {
-        call!("`-`", self, rhs.into()).expect("Robj subtract failed")
+        let rhs = rhs.into();
+        if let Some(result) = arith_f64(&self, &rhs, |lhs, rhs| lhs - rhs) {
+            return result;
+        }
+        call!("`-`", self, rhs).expect("Robj subtract failed")
     }
 }
 
@@ -203,7 +257,11 @@ where
     /// }
     /// ```
     fn mul(self, rhs: Rhs) -> Self::Output {
-        call!("`*`", self, rhs.into()).expect("Robj multiply failed")
+        let rhs = rhs.into();
+        if let Some(result) = arith_f64(&self, &rhs, |lhs, rhs| lhs * rhs) {
+            return result;
+        }
+        call!("`*`", self, rhs).expect("Robj multiply failed")
     }
 }
 
@@ -235,8 +293,101 @@ where
     /// }
     /// ```
     fn div(self, rhs: Rhs) -> Self::Output {
-        call!("`/`", self, rhs.into()).expect("Robj divide failed")
+        let rhs = rhs.into();
+        if let Some(result) = arith_f64(&self, &rhs, |lhs, rhs| lhs / rhs) {
+            return result;
+        }
+        call!("`/`", self, rhs).expect("Robj divide failed")
+    }
+}
+
+// Arithmetic on plain double vectors is done in Rust, without calling R.
+// This is only used where the result is the same as R's: both operands are
+// doubles without names, dimensions or a class, and have the same length,
+// or one of them has a single value.
+fn arith_f64<F>(lhs: &Robj, rhs: &Robj, op: F) -> Option<Robj>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let (Some(x), Some(y)) = (lhs.as_real_slice(), rhs.as_real_slice()) else {
+        return None;
+    };
+    let len = match (x.len(), y.len()) {
+        (n, m) if n == m => n,
+        (1, m) if m > 0 => m,
+        (n, 1) if n > 0 => n,
+        _ => return None,
+    };
+    let plain = |robj: &Robj| {
+        !robj.is_object()
+            && robj.names().is_none()
+            && robj.dim().is_none()
+            && !robj.has_attrib(wrapper::symbol::dimnames_symbol())
+    };
+    if !plain(lhs) || !plain(rhs) {
+        return None;
+    }
+    let mut result = Robj::alloc_vector(SEXPTYPE::REALSXP, len);
+    kernels::binary_f64(x, y, result.as_real_slice_mut().unwrap(), op);
+    // As in R, other attributes are copied from the operands of full length,
+    // with precedence for the left hand side.
+    unsafe {
+        if y.len() == len {
+            Rf_copyMostAttrib(rhs.get(), result.get_mut());
+        }
+        if x.len() == len {
+            Rf_copyMostAttrib(lhs.get(), result.get_mut());
+        }
     }
+    Some(result)
+}
+
+// Operators on a borrowed `Robj`, and with a scalar on the left hand side.
+// These all use the R operators, so recycling, NA propagation, type promotion
+// and S3/S4 methods work as in R.
+macro_rules! impl_robj_ops {
+    ($($trait: ident, $method: ident;)*) => {
+        $(
+            impl<Rhs> $trait<Rhs> for &Robj
+            where
+                Rhs: Into<Robj>,
+            {
+                type Output = Robj;
+
+                fn $method(self, rhs: Rhs) -> Self::Output {
+                    self.clone().$method(rhs)
+                }
+            }
+
+            impl_robj_ops!(@scalar $trait, $method, f64, i32, Rfloat, Rint);
+        )*
+    };
+    (@scalar $trait: ident, $method: ident, $($scalar: ty),*) => {
+        $(
+            impl $trait<Robj> for $scalar {
+                type Output = Robj;
+
+                fn $method(self, rhs: Robj) -> Self::Output {
+                    Robj::from(self).$method(rhs)
+                }
+            }
+
+            impl $trait<&Robj> for $scalar {
+                type Output = Robj;
+
+                fn $method(self, rhs: &Robj) -> Self::Output {
+                    Robj::from(self).$method(rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_robj_ops! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
 }
 
 impl Operators for Robj {}
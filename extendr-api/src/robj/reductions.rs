@@ -0,0 +1,159 @@
+use super::kernels;
+use crate::*;
+use SEXPTYPE::*;
+
+// The data of a numeric vector. Logical vectors are summed as integers, as in R.
+enum Numbers<'a> {
+    Integers(&'a [i32]),
+    Doubles(&'a [f64]),
+}
+
+fn numbers(robj: &Robj) -> Result<Numbers<'_>> {
+    if robj.inherits("factor") {
+        return Err(Error::ExpectedNumeric(robj.clone()));
+    }
+    // Logical, integer and double vectors are stored as `i32` and `f64`.
+    unsafe {
+        match robj.sexptype() {
+            LGLSXP | INTSXP => Ok(Numbers::Integers(robj.as_typed_slice_raw())),
+            REALSXP => Ok(Numbers::Doubles(robj.as_typed_slice_raw())),
+            _ => Err(Error::ExpectedNumeric(robj.clone())),
+        }
+    }
+}
+
+/// Summaries of logical, integer and double vectors, with the results of
+/// the R functions of the same names.
+///
+/// With `na_rm`, `NA` and `NaN` values are skipped. Otherwise, the result is
+/// `NA` if there are any `NA` values. These work on the data of the vector
+/// without copying it. Other types, including factors, are an error.
+pub trait Reductions: GetSexp {
+    /// The sum, like R's `sum(x, na.rm = na_rm)`.
+    ///
+    /// The sum of a logical or integer vector is an integer, unless it does
+    /// not fit in one, in which case it is a double.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([1, 2, 3]).sum(false)?, r!(6));
+    ///     assert_eq!(r!([1.5, 2.5]).sum(false)?, r!(4.));
+    ///     assert_eq!(r!([true, false, true]).sum(false)?, r!(2));
+    ///     assert_eq!(r!([Some(1), None]).sum(false)?, r!(NA_INTEGER));
+    ///     assert_eq!(r!([Some(1), None]).sum(true)?, r!(1));
+    ///     assert_eq!(r!([i32::MAX, 1]).sum(false)?, r!(2147483648.));
+    ///     assert!(r!("a").sum(false).is_err());
+    /// }
+    /// ```
+    fn sum(&self, na_rm: bool) -> Result<Robj> {
+        match numbers(self.as_robj())? {
+            Numbers::Integers(values) => {
+                let (total, count) = kernels::sum_i32(values);
+                if count < values.len() && !na_rm {
+                    return Ok(r!(NA_INTEGER));
+                }
+                Ok(match i32::try_from(total) {
+                    Ok(total) if total != i32::MIN => r!(total),
+                    _ => r!(total as f64),
+                })
+            }
+            Numbers::Doubles(values) => {
+                let (total, _) = kernels::sum_f64(values, na_rm, 0.);
+                Ok(r!(na_or(values, total)))
+            }
+        }
+    }
+
+    /// The arithmetic mean, like R's `mean(x, na.rm = na_rm)`.
+    /// This is always a double, and `NaN` for an empty vector.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([1, 2, 3, 4]).mean(false)?, r!(2.5));
+    ///     assert_eq!(r!([Some(1.), None, Some(3.)]).mean(false)?, r!(NA_REAL));
+    ///     assert_eq!(r!([Some(1.), None, Some(3.)]).mean(true)?, r!(2.));
+    /// }
+    /// ```
+    fn mean(&self, na_rm: bool) -> Result<Robj> {
+        match numbers(self.as_robj())? {
+            Numbers::Integers(values) => {
+                let (total, count) = kernels::sum_i32(values);
+                if count < values.len() && !na_rm {
+                    return Ok(r!(NA_REAL));
+                }
+                Ok(r!(total as f64 / count as f64))
+            }
+            Numbers::Doubles(values) => {
+                let (total, count) = kernels::sum_f64(values, na_rm, 0.);
+                let mean = total / count as f64;
+                if !mean.is_finite() {
+                    return Ok(r!(na_or(values, mean)));
+                }
+                // As in R, a second pass over the residuals makes the mean more accurate.
+                let (residuals, _) = kernels::sum_f64(values, na_rm, mean);
+                Ok(r!(mean + residuals / count as f64))
+            }
+        }
+    }
+
+    /// The minimum, like R's `min(x, na.rm = na_rm)`.
+    ///
+    /// The minimum of a logical or integer vector is an integer.
+    /// As in R, the minimum of an empty vector is `Inf`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([3, 1, 2]).min(false)?, r!(1));
+    ///     assert_eq!(r!([Some(2.), None]).min(false)?, r!(NA_REAL));
+    ///     assert_eq!(r!([Some(2.), None]).min(true)?, r!(2.));
+    ///     assert_eq!(r!(NA_REAL).min(true)?, r!(f64::INFINITY));
+    /// }
+    /// ```
+    fn min(&self, na_rm: bool) -> Result<Robj> {
+        extreme(self.as_robj(), na_rm, false)
+    }
+
+    /// The maximum, like R's `max(x, na.rm = na_rm)`.
+    ///
+    /// The maximum of a logical or integer vector is an integer.
+    /// As in R, the maximum of an empty vector is `-Inf`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([3, 1, 2]).max(false)?, r!(3));
+    ///     assert_eq!(r!([Some(2), None]).max(false)?, r!(NA_INTEGER));
+    ///     assert_eq!(r!([1.5, f64::NAN]).max(true)?, r!(1.5));
+    ///     assert_eq!(r!(NA_INTEGER).max(true)?, r!(f64::NEG_INFINITY));
+    /// }
+    /// ```
+    fn max(&self, na_rm: bool) -> Result<Robj> {
+        extreme(self.as_robj(), na_rm, true)
+    }
+}
+
+fn extreme(robj: &Robj, na_rm: bool, is_max: bool) -> Result<Robj> {
+    match numbers(robj)? {
+        Numbers::Integers(values) => match kernels::extreme_i32(values, is_max) {
+            (_, true) if !na_rm => Ok(r!(NA_INTEGER)),
+            (Some(best), _) => Ok(r!(best)),
+            (None, _) if is_max => Ok(r!(f64::NEG_INFINITY)),
+            (None, _) => Ok(r!(f64::INFINITY)),
+        },
+        Numbers::Doubles(values) => match kernels::extreme_f64(values, is_max) {
+            (_, true) if !na_rm => Ok(r!(na_or(values, f64::NAN))),
+            (best, _) => Ok(r!(best)),
+        },
+    }
+}
+
+// `NA` if `result` is `NaN` and there is an `NA` among the values, otherwise `result`.
+// As in R, `NA` takes precedence over other `NaN`s.
+fn na_or(values: &[f64], result: f64) -> Option<f64> {
+    if result.is_nan() && values.iter().any(|value| value.is_na()) {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+impl Reductions for Robj {}
@@ -1,5 +1,12 @@
 use crate::*;
 
+// From Rinternals.h; the libR-sys bindings have neither `NAMED` nor the
+// `MAYBE_SHARED` and `MAYBE_REFERENCED` macros that are defined with it.
+#[allow(improper_ctypes)]
+extern "C" {
+    fn NAMED(x: SEXP) -> std::os::raw::c_int;
+}
+
 ///////////////////////////////////////////////////////////////
 /// The following impls wrap specific Rinternals.h functions.
 ///
@@ -39,11 +46,16 @@ pub trait Rinternals: Types + Conversions {
         unsafe { Rf_isEnvironment(self.get()).into() }
     }
 
-    /// Return true if this is an environment.
+    /// Return true if this is a promise.
     fn is_promise(&self) -> bool {
         self.sexptype() == SEXPTYPE::PROMSXP
     }
 
+    /// Return true if this is a weak reference.
+    fn is_weak_ref(&self) -> bool {
+        self.sexptype() == SEXPTYPE::WEAKREFSXP
+    }
+
     /// Return true if this is a string.
     fn is_string(&self) -> bool {
         unsafe { Rf_isString(self.get()).into() }
@@ -104,12 +116,54 @@ pub trait Rinternals: Types + Conversions {
         single_threaded(|| unsafe { Robj::from_sexp(Rf_allocMatrix(sexptype, rows, cols)) })
     }
 
-    /// Do a deep copy of this object.
+    /// Do a deep copy of this object, including the elements of lists
+    /// and the attributes.
     /// Note that clone() only adds a reference.
+    ///
+    /// extendr does not copy R objects implicitly: cloning an `Robj` or
+    /// a wrapper such as [`Doubles`] shares the object, and setters such as
+    /// `set_elt()` and `set_attrib()` modify it in place, which is visible
+    /// to every R variable bound to it (see [`is_shared`](Self::is_shared)).
+    /// Arguments of exported functions are bound in the caller, so
+    /// duplicate them before running an in-place algorithm on them.
+    /// Only conversions to Rust types such as `Vec<f64>` or `String` copy.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let x = r!([1., 2.]);
+    ///     let mut y = Doubles::try_from(x.duplicate())?;
+    ///     y.set_elt(0, 10.0.into());
+    ///     assert_eq!(x, r!([1., 2.]));
+    ///
+    ///     let mut z = Doubles::try_from(x.clone())?;
+    ///     z.set_elt(0, 10.0.into());
+    ///     assert_eq!(x, r!([10., 2.]));
+    /// }
+    /// ```
     fn duplicate(&self) -> Robj {
         single_threaded(|| unsafe { Robj::from_sexp(Rf_duplicate(self.get())) })
     }
 
+    /// Do a shallow copy of this object: the top-level vector and its
+    /// attributes are copied, but the elements of lists are shared.
+    ///
+    /// This is cheaper than [`duplicate`](Self::duplicate) when only the
+    /// top level of a list is modified, e.g. to replace elements.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let inner = r!([1, 2]);
+    ///     let x = list!(inner.clone(), "a");
+    ///     let mut y = List::try_from(x.as_robj().shallow_duplicate())?;
+    ///     y.set_elt(1, r!("b"))?;
+    ///     assert_eq!(x.elt(1)?, r!("a"));
+    ///     assert_eq!(unsafe { y.elt(0)?.get() }, unsafe { inner.get() });
+    /// }
+    /// ```
+    fn shallow_duplicate(&self) -> Robj {
+        single_threaded(|| unsafe { Robj::from_sexp(Rf_shallow_duplicate(self.get())) })
+    }
+
     /// Find a function in an environment ignoring other variables.
     ///
     /// This evaulates promises if they are found.
@@ -344,6 +398,47 @@ pub trait Rinternals: Types + Conversions {
         unsafe { Rf_isPrimitive(self.get()).into() }
     }
 
+    /// Return true if this is a function defined in R (CLOSXP), not a primitive.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert!(R!("function(x) x")?.is_closure());
+    ///     assert!(!R!("sum")?.is_closure());
+    /// }
+    /// ```
+    fn is_closure(&self) -> bool {
+        self.sexptype() == SEXPTYPE::CLOSXP
+    }
+
+    /// Return true if this is a primitive that evaluates its arguments (BUILTINSXP), eg. `sum`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert!(R!("sum")?.is_builtin());
+    ///     assert!(!R!("`if`")?.is_builtin());
+    /// }
+    /// ```
+    fn is_builtin(&self) -> bool {
+        self.sexptype() == SEXPTYPE::BUILTINSXP
+    }
+
+    /// Return true if this is a primitive that does not evaluate its arguments (SPECIALSXP), eg. `if`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert!(R!("`if`")?.is_special());
+    ///     assert!(!R!("sum")?.is_special());
+    /// }
+    /// ```
+    fn is_special(&self) -> bool {
+        self.sexptype() == SEXPTYPE::SPECIALSXP
+    }
+
+    /// Return true if this is byte code (BCODESXP).
+    fn is_bytecode(&self) -> bool {
+        self.sexptype() == SEXPTYPE::BCODESXP
+    }
+
     /// Return true if this is a time series vector (see tsp).
     fn is_ts(&self) -> bool {
         unsafe { Rf_isTs(self.get()).into() }
@@ -469,7 +564,29 @@ pub trait Rinternals: Types + Conversions {
         unsafe { ALTREP(self.get()) != 0 && TYPEOF(self.get()) == SEXPTYPE::VECSXP }
     }
 
+    /// Generate a text representation of this object, one string per line,
+    /// as returned by R's `deparse()`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let f = R!("function(x) {\n  x + 1\n}")?;
+    ///     assert_eq!(f.deparse_lines()?, vec!["function (x) ", "{", "    x + 1", "}"]);
+    /// }
+    /// ```
+    fn deparse_lines(&self) -> Result<Vec<String>> {
+        use crate as extendr_api;
+        let strings: Strings = call!("deparse", self.as_robj())?.try_into()?;
+        Ok(strings.iter().map(|s| s.as_str().to_string()).collect())
+    }
+
     /// Generate a text representation of this object.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     assert_eq!(r!([1, 2, 3]).deparse()?, "1:3");
+    ///     assert_eq!(parse("x + 1")?.deparse()?, "expression(x + 1)");
+    /// }
+    /// ```
     fn deparse(&self) -> Result<String> {
         use crate as extendr_api;
         let strings: Strings = call!("deparse", self.as_robj())?.try_into()?;
@@ -483,6 +600,63 @@ pub trait Rinternals: Types + Conversions {
                 .join(""))
         }
     }
+
+    /// The memory used by the object in bytes, as estimated by
+    /// `utils::object.size()`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let small = r!([1., 2.]).object_size()?;
+    ///     let large = Robj::from(vec![0.; 1000]).object_size()?;
+    ///     assert!(large >= 8000);
+    ///     assert!(small < large);
+    /// }
+    /// ```
+    fn object_size(&self) -> Result<usize> {
+        let size = eval_string_with_params("utils::object.size(param.0)", &[self.as_robj()])?;
+        match size.as_real() {
+            Some(size) => Ok(size as usize),
+            None => Err(Error::ExpectedReal(size)),
+        }
+    }
+
+    /// Return true if the object may be bound to more than one variable or
+    /// element, so that modifying it in place would be visible elsewhere
+    /// (`MAYBE_SHARED`, i.e. `NAMED(x) > 1`, in C). R copies such objects before modifying them.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let x = r!([1, 2, 3]);
+    ///     assert!(!x.is_shared());
+    ///     global_env().set_local(sym!(x), &x);
+    ///     assert!(x.is_shared());
+    /// }
+    /// ```
+    fn is_shared(&self) -> bool {
+        unsafe { NAMED(self.get()) > 1 }
+    }
+
+    /// Return true if the object may be bound to a variable or element
+    /// (`MAYBE_REFERENCED`, i.e. `NAMED(x) > 0`, in C).
+    ///
+    /// Note that an `Robj` holds a reference of its own.
+    fn is_referenced(&self) -> bool {
+        unsafe { NAMED(self.get()) > 0 }
+    }
+
+    #[cfg(feature = "non-api")]
+    /// The reference count of the object (`REFCNT` in C).
+    ///
+    /// Note that an `Robj` holds a reference of its own.
+    fn refcount(&self) -> usize {
+        unsafe { REFCNT(self.get()) as usize }
+    }
+
+    #[cfg(feature = "non-api")]
+    /// The `NAMED` status of the object, which is derived from the reference count.
+    fn named(&self) -> usize {
+        unsafe { NAMED(self.get()) as usize }
+    }
 }
 
 impl Rinternals for Robj {}
@@ -290,3 +290,26 @@ fn input_iterator_test() {
         */
     }
 }
+
+#[test]
+fn test_arithmetic_operators() {
+    test! {
+        let x = r!([1, 2, 3, 4]);
+        let y = r!([10, 20]);
+
+        // Borrowed operands are not consumed, and the shorter vector is recycled.
+        assert_eq!(&x + &y, r!([11, 22, 13, 24]));
+        assert_eq!(&x - 1, r!([0, 1, 2, 3]));
+        assert_eq!(&x * 2.0, r!([2., 4., 6., 8.]));
+        assert_eq!(&x / &y, r!([0.1, 0.1, 0.3, 0.2]));
+
+        // Scalars on the left hand side.
+        assert_eq!(10 - &x, r!([9, 8, 7, 6]));
+        assert_eq!(1.0 / r!([2., 4.]), r!([0.5, 0.25]));
+        assert_eq!(Rint::from(2) * &y, r!([20, 40]));
+
+        // NA propagates.
+        assert_eq!(r!([Some(1), None]) + 1, r!([Some(2), None]));
+        assert_eq!(r!(NA_REAL) + &x, r!([NA_REAL; 4]));
+    }
+}
@@ -277,6 +277,117 @@ impl TryFrom<&Robj> for Vec<String> {
     }
 }
 
+impl<T> TryFrom<&Robj> for Vec<Vec<T>>
+where
+    Vec<T>: TryFrom<Robj, Error = Error>,
+{
+    type Error = Error;
+
+    /// Convert a list of vectors, such as ragged data, into a vector of vectors.
+    /// Each element is converted like a single vector.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let groups = R!("list(c(1.5, 2), double(), 3)")?;
+    ///     assert_eq!(<Vec<Vec<f64>>>::try_from(&groups)?, vec![vec![1.5, 2.0], vec![], vec![3.0]]);
+    ///
+    ///     let tokens = R!("list(c('a', 'b'), 'c')")?;
+    ///     assert_eq!(<Vec<Vec<String>>>::try_from(&tokens)?, vec![vec!["a", "b"], vec!["c"]]);
+    ///
+    ///     assert!(<Vec<Vec<f64>>>::try_from(&R!("list(1, 'a')")?).is_err());
+    ///     assert!(<Vec<Vec<f64>>>::try_from(&r!([1.0, 2.0])).is_err());
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        let list = List::try_from(robj)?;
+        list.values().map(<Vec<T>>::try_from).collect()
+    }
+}
+
+impl<T> TryFrom<Robj> for Vec<Vec<T>>
+where
+    Vec<T>: TryFrom<Robj, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl TryFrom<&Robj> for Vec<Option<i32>> {
+    type Error = Error;
+
+    /// Convert an INTSXP object into a vector of optional i32 (integer),
+    /// with `None` for NA.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let column = <Vec<Option<i32>>>::try_from(&r!([Some(1), None]))?;
+    ///     assert_eq!(column, vec![Some(1), None]);
+    ///     assert_eq!(r!(column), r!([Some(1), None]));
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if let Some(v) = robj.as_integer_slice() {
+            Ok(v.iter().map(|x| (!x.is_na()).then_some(*x)).collect())
+        } else {
+            Err(Error::ExpectedInteger(robj.clone()))
+        }
+    }
+}
+
+impl TryFrom<&Robj> for Vec<Option<f64>> {
+    type Error = Error;
+
+    /// Convert a REALSXP object into a vector of optional f64 (double precision
+    /// floating point), with `None` for NA. `NaN` values other than NA are kept.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if let Some(v) = robj.as_real_slice() {
+            Ok(v.iter().map(|x| (!x.is_na()).then_some(*x)).collect())
+        } else {
+            Err(Error::ExpectedReal(robj.clone()))
+        }
+    }
+}
+
+impl TryFrom<&Robj> for Vec<Option<bool>> {
+    type Error = Error;
+
+    /// Convert a LGLSXP object into a vector of optional bool, with `None` for NA.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if let Some(v) = robj.as_logical_slice() {
+            Ok(v.iter()
+                .map(|x| (!x.is_na()).then(|| x.is_true()))
+                .collect())
+        } else {
+            Err(Error::ExpectedLogical(robj.clone()))
+        }
+    }
+}
+
+impl TryFrom<&Robj> for Vec<Option<String>> {
+    type Error = Error;
+
+    /// Convert a STRSXP object into a vector of optional `String`s,
+    /// with `None` for NA.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let column = <Vec<Option<String>>>::try_from(&r!([Some("a"), None]))?;
+    ///     assert_eq!(column, vec![Some("a".to_string()), None]);
+    ///     assert_eq!(r!(column), r!([Some("a"), None]));
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if let Some(iter) = robj.as_str_iter() {
+            Ok(iter.map(|s| (!s.is_na()).then(|| s.to_string())).collect())
+        } else {
+            Err(Error::ExpectedString(robj.clone()))
+        }
+    }
+}
+
 impl TryFrom<&Robj> for &[i32] {
     type Error = Error;
 
@@ -465,6 +576,242 @@ impl TryFrom<&Robj> for Rcplx {
     }
 }
 
+// A non-`NA` scalar double or integer, e.g. of a `difftime` or `POSIXct`.
+fn time_scalar(robj: &Robj) -> Result<f64> {
+    match robj.len() {
+        0 => return Err(Error::ExpectedNonZeroLength(robj.clone())),
+        1 => {}
+        _ => return Err(Error::ExpectedScalar(robj.clone())),
+    };
+    if robj.is_na() {
+        return Err(Error::MustNotBeNA(robj.clone()));
+    }
+    robj.as_real()
+        .or_else(|| robj.as_integer().map(f64::from))
+        .ok_or_else(|| Error::ExpectedNumeric(robj.clone()))
+}
+
+// `Duration::from_secs_f64` panics for negative and too large values.
+fn duration_from_secs(seconds: f64, robj: &Robj) -> Result<std::time::Duration> {
+    if (0. ..u64::MAX as f64).contains(&seconds) {
+        Ok(std::time::Duration::from_secs_f64(seconds))
+    } else {
+        Err(Error::OutOfRange(robj.clone()))
+    }
+}
+
+impl TryFrom<&Robj> for std::time::Duration {
+    type Error = Error;
+
+    /// Convert a `difftime` in any units, or a number of seconds, to a duration.
+    /// Negative durations are out of range.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::time::Duration;
+    /// test! {
+    ///     let minutes = R!("as.difftime(2, units = 'mins')")?;
+    ///     assert_eq!(Duration::try_from(&minutes)?, Duration::from_secs(120));
+    ///     assert_eq!(Duration::try_from(&r!(0.5))?, Duration::from_millis(500));
+    ///     assert!(Duration::try_from(&r!(-1)).is_err());
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        let value = time_scalar(robj)?;
+        let scale = if robj.inherits("difftime") {
+            robj.get_attrib("units")
+                .and_then(|units| units.as_str())
+                .unwrap_or_default()
+                .parse::<DifftimeUnits>()?
+                .seconds()
+        } else {
+            1.
+        };
+        duration_from_secs(value * scale, robj)
+    }
+}
+
+impl TryFrom<&Robj> for std::time::SystemTime {
+    type Error = Error;
+
+    /// Convert a `POSIXct` to a point in time.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    /// test! {
+    ///     let time = R!("as.POSIXct('1969-12-31 23:59:59', tz = 'UTC')")?;
+    ///     assert_eq!(SystemTime::try_from(&time)?, UNIX_EPOCH - Duration::from_secs(1));
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if !robj.inherits("POSIXct") {
+            return Err(Error::Other(format!("expected a POSIXct, got {:?}", robj)));
+        }
+        let seconds = time_scalar(robj)?;
+        let since_epoch = duration_from_secs(seconds.abs(), robj)?;
+        let time = if seconds >= 0. {
+            std::time::UNIX_EPOCH.checked_add(since_epoch)
+        } else {
+            std::time::UNIX_EPOCH.checked_sub(since_epoch)
+        };
+        time.ok_or_else(|| Error::OutOfRange(robj.clone()))
+    }
+}
+
+// The strings as UTF-8 with `~` expanded to the home directory, like `path.expand()`.
+fn expand_paths(robj: &Robj) -> Result<Robj> {
+    if !robj.is_string() {
+        return Err(Error::ExpectedString(robj.clone()));
+    }
+    eval_string_with_params("enc2utf8(path.expand(param.0))", &[robj])
+}
+
+impl TryFrom<&Robj> for std::path::PathBuf {
+    type Error = Error;
+
+    /// Convert a scalar string to a path, expanding `~` as R does.
+    /// NAs are not allowed.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::path::PathBuf;
+    /// test! {
+    ///     assert_eq!(PathBuf::try_from(&r!("data/x.csv"))?, PathBuf::from("data/x.csv"));
+    ///     let home = R!("path.expand('~')")?;
+    ///     assert_eq!(PathBuf::try_from(&r!("~/x.csv"))?, PathBuf::try_from(&home)?.join("x.csv"));
+    ///     assert_eq!(Robj::from(PathBuf::from("data/x.csv")), r!("data/x.csv"));
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        <&str>::try_from(robj)?;
+        let expanded = expand_paths(robj)?;
+        Ok(<&str>::try_from(&expanded)?.into())
+    }
+}
+
+impl TryFrom<&Robj> for Vec<std::path::PathBuf> {
+    type Error = Error;
+
+    /// Convert a character vector to paths, expanding `~` as R does.
+    /// NAs are not allowed.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        let expanded = expand_paths(robj)?;
+        let paths = expanded.as_str_iter().unwrap();
+        if paths.clone().any(|path| path.is_na()) {
+            return Err(Error::MustNotBeNA(robj.clone()));
+        }
+        Ok(paths.map(std::path::PathBuf::from).collect())
+    }
+}
+
+impl TryFrom<&Robj> for std::ffi::OsString {
+    type Error = Error;
+
+    /// Convert a scalar string to an OS string, translating it to UTF-8 first
+    /// if it is in another encoding, e.g. latin1.
+    /// NAs are not allowed.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::ffi::OsString;
+    /// test! {
+    ///     let latin1 = R!(r#"iconv("caf\u00e9", "UTF-8", "latin1")"#)?;
+    ///     assert_eq!(OsString::try_from(&latin1)?, OsString::from("caf\u{e9}"));
+    ///     assert_eq!(Robj::from(OsString::from("caf\u{e9}")), r!("caf\u{e9}"));
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if !robj.is_string() {
+            return Err(Error::ExpectedString(robj.clone()));
+        }
+        match robj.len() {
+            0 => return Err(Error::ExpectedNonZeroLength(robj.clone())),
+            1 => {}
+            _ => return Err(Error::ExpectedScalar(robj.clone())),
+        };
+        let charsxp = unsafe { STRING_ELT(robj.get(), 0) };
+        let utf8 = unsafe { crate::wrapper::rstr::charsxp_to_utf8(charsxp) };
+        utf8.map(Into::into)
+            .ok_or_else(|| Error::MustNotBeNA(robj.clone()))
+    }
+}
+
+// The first and last element of an integer vector whose elements increase by one.
+// The elements are read in chunks, so that compact sequences are not expanded.
+fn contiguous_integers(robj: &Robj) -> Result<Option<(i32, i32)>> {
+    let integers = Integers::try_from(robj)?;
+    let mut chunk = [Rint::default(); 1024];
+    let mut bounds: Option<(i32, i32)> = None;
+    let mut index = 0;
+    while index < integers.len() {
+        let len = integers.get_region(index, &mut chunk);
+        for value in &chunk[..len] {
+            let value =
+                Option::<i32>::from(*value).ok_or_else(|| Error::MustNotBeNA(robj.clone()))?;
+            bounds = match bounds {
+                None => Some((value, value)),
+                Some((first, last)) if last.checked_add(1) == Some(value) => Some((first, value)),
+                Some(_) => {
+                    return Err(Error::Other(format!(
+                        "expected contiguous integers, but element {} is {}",
+                        index + 1,
+                        value
+                    )))
+                }
+            };
+            index += 1;
+        }
+        if len == 0 {
+            break;
+        }
+    }
+    Ok(bounds)
+}
+
+impl TryFrom<&Robj> for Range<i32> {
+    type Error = Error;
+
+    /// Convert an integer vector whose elements increase by one, such as `1:10`, to a range.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::ops::Range;
+    /// test! {
+    ///     assert_eq!(Range::<i32>::try_from(R!("1:10")?)?, 1..11);
+    ///     assert_eq!(Range::<i32>::try_from(r!(Integers::new(0)))?, 0..0);
+    ///     assert!(Range::<i32>::try_from(r!([1, 3])).is_err());
+    ///     assert!(Range::<i32>::try_from(r!([1., 2.])).is_err());
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        match contiguous_integers(robj)? {
+            Some((first, last)) => match last.checked_add(1) {
+                Some(end) => Ok(first..end),
+                None => Err(Error::OutOfRange(robj.clone())),
+            },
+            None => Ok(0..0),
+        }
+    }
+}
+
+impl TryFrom<&Robj> for RangeInclusive<i32> {
+    type Error = Error;
+
+    /// Convert an integer vector whose elements increase by one, such as `1:10`, to a range.
+    /// The vector must not be empty.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::ops::RangeInclusive;
+    /// test! {
+    ///     assert_eq!(RangeInclusive::<i32>::try_from(R!("-1:1")?)?, -1..=1);
+    ///     assert_eq!(RangeInclusive::<i32>::try_from(r!(5))?, 5..=5);
+    ///     assert!(RangeInclusive::<i32>::try_from(r!(Integers::new(0))).is_err());
+    /// }
+    /// ```
+    fn try_from(robj: &Robj) -> Result<Self> {
+        match contiguous_integers(robj)? {
+            Some((first, last)) => Ok(first..=last),
+            None => Err(Error::ExpectedNonZeroLength(robj.clone())),
+        }
+    }
+}
+
 // Convert TryFrom<&Robj> into TryFrom<Robj>. Sadly, we are unable to make a blanket
 // conversion using GetSexp with the current version of Rust.
 macro_rules! impl_try_from_robj {
@@ -548,11 +895,118 @@ impl_try_from_robj!(
     Vec::<String>,
     HashMap::<String, Robj>, HashMap::<&str, Robj>,
     Vec::<Rint>, Vec::<Rfloat>, Vec::<Rbool>, Vec::<Rcplx>, Vec::<u8>, Vec::<i32>, Vec::<f64>,
+    Vec::<Option<i32>>, Vec::<Option<f64>>, Vec::<Option<bool>>, Vec::<Option<String>>,
     &[Rint], &[Rfloat], &[Rbool], &[Rcplx], &[u8], &[i32], &[f64],
     &mut [Rint], &mut [Rfloat], &mut [Rbool], &mut [Rcplx], &mut [u8], &mut [i32], &mut [f64],
     &str, String,
+    std::time::Duration, std::time::SystemTime,
+    std::path::PathBuf, Vec::<std::path::PathBuf>,
+    std::ffi::OsString,
+    Range::<i32>, RangeInclusive::<i32>,
 );
 
+/// A set converted from an R vector that must not have duplicate elements.
+///
+/// Converting a vector to a `HashSet` or `BTreeSet` drops duplicates, while
+/// converting it to a `Distinct` set is an error if there are any, e.g. for
+/// arguments that are sets of IDs.
+/// ```
+/// use extendr_api::prelude::*;
+/// use std::collections::HashSet;
+/// test! {
+///     let ids = r!(["a", "b", "a"]);
+///     assert_eq!(HashSet::<String>::try_from(&ids)?.len(), 2);
+///     assert!(Distinct::<HashSet<String>>::try_from(&ids).is_err());
+///
+///     let ids = Distinct::<HashSet<String>>::try_from(r!(["a", "b"]))?;
+///     assert!(ids.contains("b"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distinct<S>(pub S);
+
+impl<S> Distinct<S> {
+    /// Return the set.
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S> std::ops::Deref for Distinct<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.0
+    }
+}
+
+macro_rules! impl_try_from_robj_for_set {
+    ($set:ident, $($bound:tt)+) => {
+        impl<T> TryFrom<&Robj> for std::collections::$set<T>
+        where
+            T: $($bound)+,
+            for<'a> Vec<T>: TryFrom<&'a Robj, Error = Error>,
+        {
+            type Error = Error;
+
+            /// Convert a vector into a set of its unique elements.
+            fn try_from(robj: &Robj) -> Result<Self> {
+                Ok(Vec::<T>::try_from(robj)?.into_iter().collect())
+            }
+        }
+
+        impl<T> TryFrom<Robj> for std::collections::$set<T>
+        where
+            T: $($bound)+,
+            for<'a> Vec<T>: TryFrom<&'a Robj, Error = Error>,
+        {
+            type Error = Error;
+
+            fn try_from(robj: Robj) -> Result<Self> {
+                Self::try_from(&robj)
+            }
+        }
+
+        impl<T> TryFrom<&Robj> for Distinct<std::collections::$set<T>>
+        where
+            T: $($bound)+,
+            for<'a> Vec<T>: TryFrom<&'a Robj, Error = Error>,
+        {
+            type Error = Error;
+
+            /// Convert a vector into a set, failing if it has duplicate elements.
+            fn try_from(robj: &Robj) -> Result<Self> {
+                let values = Vec::<T>::try_from(robj)?;
+                let len = values.len();
+                let set: std::collections::$set<T> = values.into_iter().collect();
+                if set.len() < len {
+                    return Err(Error::Other(format!(
+                        "expected distinct values, but {} of {} are duplicates",
+                        len - set.len(),
+                        len
+                    )));
+                }
+                Ok(Distinct(set))
+            }
+        }
+
+        impl<T> TryFrom<Robj> for Distinct<std::collections::$set<T>>
+        where
+            T: $($bound)+,
+            for<'a> Vec<T>: TryFrom<&'a Robj, Error = Error>,
+        {
+            type Error = Error;
+
+            fn try_from(robj: Robj) -> Result<Self> {
+                Self::try_from(&robj)
+            }
+        }
+    };
+}
+
+impl_try_from_robj_for_set!(HashSet, Eq + std::hash::Hash);
+impl_try_from_robj_for_set!(BTreeSet, Ord);
+
 // NOTE: this is included for compatibility with previously defined `FromRobj`
 // One should prefer `List::from_hashmap` instead,
 // and this `impl` should be deprecated next.
@@ -0,0 +1,103 @@
+//! Manage R's check of the C stack.
+//!
+//! R checks on entry to many functions that the C stack has not grown past
+//! `R_CStackLimit`, measured from `R_CStackStart`, the stack of the thread
+//! that started R. Calls into R from deep Rust recursion may trip this check,
+//! and calls from another thread always measure the wrong stack, giving
+//! errors such as `C stack usage is too close to the limit`.
+//!
+//! [`without_stack_check`] turns the check off while a closure runs.
+//! These items use non-API parts of R and need the `non-api` feature.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use extendr_api::stack::{c_stack_usage, without_stack_check};
+//! test! {
+//!     assert!(c_stack_usage().is_some());
+//!     let x = without_stack_check(|| R!("1 + 1"))?;
+//!     assert_eq!(x, r!(2.0));
+//! }
+//! ```
+use crate::*;
+use std::os::raw::c_int;
+
+// From Rinterface.h; the libR-sys bindings only have `R_CStackLimit`.
+extern "C" {
+    static R_CStackStart: usize;
+    static R_CStackDir: c_int;
+}
+
+/// The value of `R_CStackLimit` that disables the check.
+const NO_STACK_LIMIT: usize = usize::MAX;
+
+/// The number of bytes of C stack R allows, or `None` if the check is disabled.
+pub fn c_stack_limit() -> Option<usize> {
+    let limit = single_threaded(|| unsafe { R_CStackLimit });
+    (limit != NO_STACK_LIMIT).then_some(limit)
+}
+
+/// Set the number of bytes of C stack R allows, or disable the check with `None`.
+///
+/// # Safety
+///
+/// A limit that is larger than the stack R runs on lets deep recursion in R
+/// overflow the stack instead of raising an R error. This must be called on
+/// the thread that runs R.
+pub unsafe fn set_c_stack_limit(limit: Option<usize>) {
+    single_threaded(|| R_CStackLimit = limit.unwrap_or(NO_STACK_LIMIT));
+}
+
+/// The number of bytes of C stack in use between R's start and this call,
+/// as R measures it, or `None` if that is not known.
+///
+/// The value is only meaningful on the thread that started R; on other
+/// threads the stack may appear to have grown the wrong way, giving `None`.
+pub fn c_stack_usage() -> Option<usize> {
+    let marker = 0_u8;
+    let here = &marker as *const u8 as usize;
+    single_threaded(|| unsafe {
+        if R_CStackStart == usize::MAX {
+            return None;
+        }
+        // R_CStackDir is 1 if the stack grows down and -1 if it grows up.
+        if R_CStackDir > 0 {
+            R_CStackStart.checked_sub(here)
+        } else {
+            here.checked_sub(R_CStackStart)
+        }
+    })
+}
+
+/// Restores `R_CStackLimit` when dropped, even if the closure panics.
+struct StackLimitGuard {
+    limit: usize,
+}
+
+impl Drop for StackLimitGuard {
+    fn drop(&mut self) {
+        single_threaded(|| unsafe { R_CStackLimit = self.limit });
+    }
+}
+
+/// Run `f` with R's check of the C stack turned off, then restore the limit.
+///
+/// This allows calling R from deep Rust recursion or from a thread other than
+/// the one that started R, for example inside [`single_threaded`].
+/// Calls may be nested.
+///
+/// Without the check, R code in `f` that recurses without end overflows the
+/// stack and crashes R instead of raising an error, so `f` should only call
+/// R code that is known to terminate.
+pub fn without_stack_check<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = single_threaded(|| unsafe {
+        let guard = StackLimitGuard {
+            limit: R_CStackLimit,
+        };
+        R_CStackLimit = NO_STACK_LIMIT;
+        guard
+    });
+    f()
+}
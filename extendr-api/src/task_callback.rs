@@ -0,0 +1,133 @@
+//! Rust closures that run after each top-level R expression,
+//! like `addTaskCallback()` in R.
+//!
+//! Task callbacks are useful to flush buffers, invalidate caches or
+//! propagate changes once the user's command has completed.
+use crate::*;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int, c_void};
+
+type ToplevelCallback =
+    unsafe extern "C" fn(SEXP, SEXP, Rboolean, Rboolean, *mut c_void) -> Rboolean;
+
+// From R_ext/Callbacks.h, which is not part of the libR-sys bindings.
+extern "C" {
+    fn Rf_addTaskCallback(
+        cb: Option<ToplevelCallback>,
+        data: *mut c_void,
+        finalizer: Option<unsafe extern "C" fn(*mut c_void)>,
+        name: *const c_char,
+        pos: *mut c_int,
+    ) -> *mut c_void;
+    fn Rf_removeTaskCallbackByName(name: *const c_char) -> Rboolean;
+}
+
+/// The top-level expression that has just completed.
+#[derive(Debug)]
+pub struct TaskInfo {
+    /// The expression.
+    pub expr: Robj,
+    /// Its value.
+    pub value: Robj,
+    /// `false` if the evaluation failed.
+    pub succeeded: bool,
+    /// `true` if the value was printed.
+    pub visible: bool,
+}
+
+type Callback = Box<dyn FnMut(&TaskInfo) -> bool>;
+
+/// A registered task callback, see [`add_task_callback`].
+///
+/// The callback stays registered when this is dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskCallback {
+    name: String,
+}
+
+impl TaskCallback {
+    /// The name the callback was registered with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Unregister the callback. Returns `false` if it was not registered,
+    /// for example because it had already removed itself.
+    pub fn remove(self) -> bool {
+        remove_task_callback(&self.name)
+    }
+}
+
+/// Register `f` to be called after each top-level R expression completes.
+///
+/// The callback is kept while `f` returns `true` and removed when it
+/// returns `false`, as in R. A callback that panics is removed.
+///
+/// `name` identifies the callback in `getTaskCallbackNames()` and in
+/// [`remove_task_callback`].
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let callback = add_task_callback("extendr_counter", |task| task.succeeded)?;
+///     assert_eq!(callback.name(), "extendr_counter");
+///     let names = R!("getTaskCallbackNames()")?;
+///     assert!(names.as_str_iter().unwrap().any(|n| n == "extendr_counter"));
+///     assert!(callback.remove());
+///     assert!(!remove_task_callback("extendr_counter"));
+/// }
+/// ```
+pub fn add_task_callback<F>(name: &str, f: F) -> Result<TaskCallback>
+where
+    F: FnMut(&TaskInfo) -> bool + 'static,
+{
+    unsafe extern "C" fn call(
+        expr: SEXP,
+        value: SEXP,
+        succeeded: Rboolean,
+        visible: Rboolean,
+        data: *mut c_void,
+    ) -> Rboolean {
+        let f = &mut *(data as *mut Callback);
+        let info = TaskInfo {
+            expr: Robj::from_sexp(expr),
+            value: Robj::from_sexp(value),
+            succeeded: succeeded != Rboolean::FALSE,
+            visible: visible != Rboolean::FALSE,
+        };
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&info))) {
+            Ok(true) => Rboolean::TRUE,
+            _ => Rboolean::FALSE,
+        }
+    }
+
+    unsafe extern "C" fn finalize(data: *mut c_void) {
+        drop(Box::from_raw(data as *mut Callback));
+    }
+
+    let c_name = CString::new(name).map_err(|err| Error::Other(err.to_string()))?;
+    let data = Box::into_raw(Box::new(Box::new(f) as Callback));
+    single_threaded(|| unsafe {
+        let mut pos: c_int = 0;
+        Rf_addTaskCallback(
+            Some(call),
+            data as *mut c_void,
+            Some(finalize),
+            c_name.as_ptr(),
+            &mut pos,
+        );
+    });
+    Ok(TaskCallback {
+        name: name.to_string(),
+    })
+}
+
+/// Unregister the task callback called `name`, which may have been added
+/// from Rust or from R. Returns `false` if there is no such callback.
+pub fn remove_task_callback(name: &str) -> bool {
+    match CString::new(name) {
+        Ok(c_name) => single_threaded(|| unsafe {
+            Rf_removeTaskCallbackByName(c_name.as_ptr()) != Rboolean::FALSE
+        }),
+        Err(_) => false,
+    }
+}
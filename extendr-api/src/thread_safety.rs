@@ -1,5 +1,6 @@
 //! Provide limited protection for multithreaded access to the R API.
 
+use crate as extendr_api;
 use crate::*;
 use std::cell::Cell;
 use std::sync::Mutex;
@@ -72,6 +73,109 @@ pub fn throw_r_error<S: AsRef<str>>(s: S) -> ! {
     };
 }
 
+/// Make an R error condition of class `c("extendr_error", "error", "condition")`.
+///
+/// Besides `message` and `call`, the condition has a `rust_type` field with
+/// the type name of the Rust error, and a `backtrace` field which is `NULL`
+/// unless a backtrace is given.
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let cnd = error_condition("oops", "std::io::Error", None);
+///     assert!(cnd.inherits("extendr_error"));
+///     assert!(cnd.inherits("error"));
+///     assert_eq!(cnd.dollar("message")?, r!("oops"));
+///     assert_eq!(cnd.dollar("rust_type")?, r!("std::io::Error"));
+///     assert_eq!(cnd.dollar("backtrace")?, r!(NULL));
+/// }
+/// ```
+pub fn error_condition(message: &str, rust_type: &str, backtrace: Option<&str>) -> Robj {
+    let mut condition = list!(
+        message = message,
+        call = NULL,
+        rust_type = rust_type,
+        backtrace = backtrace.map(Robj::from).unwrap_or_default()
+    );
+    condition
+        .set_class(["extendr_error", "error", "condition"])
+        .expect("internal error: failed to set class");
+    condition.into()
+}
+
+/// Raise an [`error_condition`] in R, so that R code can catch it with
+/// `tryCatch(..., extendr_error = function(e) ...)`.
+///
+/// Like [`throw_r_error`], this does not return, and Rust values that are
+/// still alive in the calling frames are not dropped.
+/// The arguments are taken by value so that they can be freed first.
+pub fn throw_r_condition(message: String, rust_type: &str, backtrace: Option<String>) -> ! {
+    let condition = error_condition(&message, rust_type, backtrace.as_deref());
+    drop(message);
+    drop(backtrace);
+    let call = lang!("stop", condition);
+    unsafe {
+        // Hand the call over to R's protection stack, which is reset by the long jump.
+        let sexp = Rf_protect(call.get());
+        drop(call);
+        Rf_eval(sexp, R_BaseEnv);
+    }
+    unreachable!("stop() returned")
+}
+
+/// Run `f` with `R_ToplevelExec`, so that an R error or interrupt in `f`
+/// returns an `Err` instead of jumping over the Rust code that called it.
+///
+/// This is the building block for calling parts of the R API that may raise
+/// errors, such as `R_CheckUserInterrupt()`. R cannot tell this function why
+/// it jumped, so the error does not say whether it was an error or an interrupt.
+/// The message of an R error is still printed by R.
+///
+/// If R jumps out of `f`, values owned by `f` are not dropped, so `f` should
+/// only hold plain data or protected `SEXP`s. A panic in `f` is resumed
+/// once `R_ToplevelExec` has returned.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     assert_eq!(toplevel_exec(|| 1 + 1)?, 2);
+///     assert!(toplevel_exec(|| throw_r_error("bad things!")).is_err());
+/// }
+/// ```
+pub fn toplevel_exec<F, R>(f: F) -> Result<R>
+where
+    F: FnOnce() -> R,
+{
+    struct Data<F, R> {
+        f: Option<F>,
+        result: Option<std::thread::Result<R>>,
+    }
+
+    unsafe extern "C" fn do_call<F, R>(data: *mut std::os::raw::c_void)
+    where
+        F: FnOnce() -> R,
+    {
+        let data = &mut *(data as *mut Data<F, R>);
+        let f = data.f.take().unwrap();
+        // Panics must not unwind through R's C frames.
+        data.result = Some(std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)));
+    }
+
+    let mut data = Data {
+        f: Some(f),
+        result: None,
+    };
+    let completed = single_threaded(|| unsafe {
+        R_ToplevelExec(Some(do_call::<F, R>), &mut data as *mut Data<F, R> as _)
+    });
+    match data.result {
+        Some(Ok(result)) if completed != Rboolean::FALSE => Ok(result),
+        Some(Err(payload)) => std::panic::resume_unwind(payload),
+        _ => Err(Error::Other(
+            "R code raised an error or was interrupted".into(),
+        )),
+    }
+}
+
 /// Wrap an R function such as `Rf_findFunction` and convert errors and panics into results.
 /// ```ignore
 /// use extendr_api::prelude::*;
@@ -0,0 +1,122 @@
+//! The version of the running R session and checks of what it supports.
+//!
+//! Packages can use these to manage compatibility with older versions of R
+//! in code. Exported functions can also require a minimum version with
+//! `#[extendr(min_r = "4.2")]`, which raises an R error on older versions
+//! before the arguments are converted.
+//!
+//! ```
+//! use extendr_api::prelude::*;
+//! use extendr_api::version::{r_version, RVersion};
+//! test! {
+//!     assert!(r_version() >= RVersion::new(3, 5, 0));
+//!     assert!(r_version().to_string().starts_with(&r_version().major.to_string()));
+//! }
+//! ```
+use crate::*;
+use once_cell::sync::OnceCell;
+
+/// A version of R, such as `4.2.1`.
+///
+/// Versions are ordered, so they can be compared with `<`, `>=` and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl RVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl std::fmt::Display for RVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl std::str::FromStr for RVersion {
+    type Err = Error;
+
+    /// Parse a version with two or three parts, such as `"4.2"` or `"4.2.1"`.
+    /// ```
+    /// use extendr_api::version::RVersion;
+    /// assert_eq!("4.2".parse::<RVersion>().unwrap(), RVersion::new(4, 2, 0));
+    /// assert_eq!("4.2.1".parse::<RVersion>().unwrap(), RVersion::new(4, 2, 1));
+    /// assert!("4".parse::<RVersion>().is_err());
+    /// ```
+    fn from_str(version: &str) -> Result<Self> {
+        let parts = version
+            .split('.')
+            .map(|part| part.parse::<u32>())
+            .collect::<std::result::Result<Vec<_>, _>>();
+        match parts.as_deref() {
+            Ok([major, minor]) => Ok(Self::new(*major, *minor, 0)),
+            Ok([major, minor, patch]) => Ok(Self::new(*major, *minor, *patch)),
+            _ => Err(Error::Other(format!("invalid R version {:?}", version))),
+        }
+    }
+}
+
+static R_VERSION: OnceCell<RVersion> = OnceCell::new();
+
+/// The version of the running R, from `R.version`.
+pub fn r_version() -> RVersion {
+    *R_VERSION.get_or_init(|| {
+        // `R.version$minor` has the minor version and the patch, e.g. "2.1".
+        eval_string("paste(R.version$major, R.version$minor, sep = '.')")
+            .ok()
+            .and_then(|version| version.as_str()?.parse().ok())
+            .expect("R.version should have a valid version")
+    })
+}
+
+/// The platform R was built for, such as `"x86_64-pc-linux-gnu"`.
+pub fn r_platform() -> Result<String> {
+    String::try_from(eval_string("R.version$platform")?)
+}
+
+/// Return true if this R supports ALTREP vectors, which were added in R 3.5.0.
+/// Constructors such as `Integers::from_values_altrep` need them.
+pub fn has_altrep() -> bool {
+    r_version() >= RVersion::new(3, 5, 0)
+}
+
+/// Return true if the session uses a UTF-8 locale, so that strings passed
+/// to and from R need no translation. This is the `UTF-8` entry of
+/// `l10n_info()`, which is true on Windows since R 4.2.
+pub fn utf8_locale() -> Result<bool> {
+    bool::try_from(eval_string("l10n_info()[['UTF-8']]")?)
+}
+
+/// Return an error if R is older than `min_version`, such as `"4.2"`.
+/// `name` is the name of the function that needs this version.
+///
+/// This is called by the wrappers of functions with `#[extendr(min_r = "...")]`.
+/// ```
+/// use extendr_api::prelude::*;
+/// use extendr_api::version::check_min_r_version;
+/// test! {
+///     check_min_r_version("f", "3.5")?;
+///     let error = check_min_r_version("f", "999.0").unwrap_err();
+///     assert!(error.to_string().starts_with("f requires R >= 999.0.0, but this is R "));
+/// }
+/// ```
+pub fn check_min_r_version(name: &str, min_version: &str) -> Result<()> {
+    let min_version = min_version.parse::<RVersion>()?;
+    let version = r_version();
+    if version < min_version {
+        return Err(Error::Other(format!(
+            "{} requires R >= {}, but this is R {}",
+            name, min_version, version
+        )));
+    }
+    Ok(())
+}
@@ -7,6 +7,7 @@
 //! [`IntoDataFrameRow`]: ::extendr_macros::IntoDataFrameRow
 //!
 use super::*;
+use crate::wrapper::symbol::{names_symbol, row_names_symbol};
 
 /// A trait to convert a collection of `IntoDataFrameRow` into
 /// [`Dataframe`]. Typical usage involves using the derive-macro [`IntoDataFrameRow`]
@@ -67,6 +68,114 @@ impl<T> Dataframe<T> {
     pub fn try_from_values<I: IntoDataFrameRow<T>>(iter: I) -> Result<Self> {
         iter.into_dataframe()
     }
+
+    /// Get the row names, or `None` if the data frame has automatic
+    /// row names (`1:n`).
+    ///
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let robj = R!("data.frame(x = 1:2, y = c('a', 'b'))")?;
+    ///     let mut df = Dataframe::<()>::try_from(robj)?;
+    ///     assert_eq!(df.rownames(), None);
+    ///     df.set_rownames(["r1", "r2"])?;
+    ///     df.set_colnames(["a", "b"])?;
+    ///     assert_eq!(df.rownames(), Some(Strings::from_values(["r1", "r2"])));
+    ///     assert_eq!(df.colnames(), Some(Strings::from_values(["a", "b"])));
+    ///     assert!(df.set_colnames(["a"]).is_err());
+    /// }
+    /// ```
+    pub fn rownames(&self) -> Option<Strings> {
+        self.robj.get_attrib(row_names_symbol())?.try_into().ok()
+    }
+
+    /// Get the column names.
+    pub fn colnames(&self) -> Option<Strings> {
+        self.robj.get_attrib(names_symbol())?.try_into().ok()
+    }
+
+    /// Set the row names.
+    ///
+    /// Returns `Error::NamesLengthMismatch` if the number of names
+    /// is not the number of rows.
+    ///
+    /// Equivalent to `rownames<-` in R.
+    pub fn set_rownames<N>(&mut self, names: N) -> Result<&mut Self>
+    where
+        N: IntoIterator,
+        N::IntoIter: ExactSizeIterator,
+        N::Item: ToVectorValue + AsRef<str>,
+    {
+        self.set_names_attrib(row_names_symbol(), names.into_iter().collect_robj())
+    }
+
+    /// Set the column names.
+    ///
+    /// Returns `Error::NamesLengthMismatch` if the number of names
+    /// is not the number of columns.
+    ///
+    /// Equivalent to `colnames<-` in R.
+    pub fn set_colnames<N>(&mut self, names: N) -> Result<&mut Self>
+    where
+        N: IntoIterator,
+        N::IntoIter: ExactSizeIterator,
+        N::Item: ToVectorValue + AsRef<str>,
+    {
+        self.set_names_attrib(names_symbol(), names.into_iter().collect_robj())
+    }
+
+    /// Set the row and column names at once.
+    ///
+    /// `dimnames` must be a list of the row names and the column names.
+    ///
+    /// Equivalent to `dimnames<-` in R.
+    pub fn set_dimnames(&mut self, dimnames: List) -> Result<&mut Self> {
+        if dimnames.len() != 2 {
+            return Err(Error::ExpectedLength(2));
+        }
+        let rownames: Strings = dimnames.elt(0)?.try_into()?;
+        let colnames: Strings = dimnames.elt(1)?.try_into()?;
+        self.set_names_attrib(row_names_symbol(), rownames.into())?;
+        self.set_names_attrib(names_symbol(), colnames.into())
+    }
+
+    fn set_names_attrib(&mut self, symbol: Symbol, names: Robj) -> Result<&mut Self> {
+        let expected = if symbol == row_names_symbol() {
+            self.nrows()
+        } else {
+            self.robj.len()
+        };
+        if names.len() != expected {
+            return Err(Error::NamesLengthMismatch(names));
+        }
+        self.robj.set_attrib(symbol, names)?;
+        Ok(self)
+    }
+
+    // The number of rows, from the compact or expanded row names,
+    // or else from the length of the first column.
+    fn nrows(&self) -> usize {
+        match self.robj.get_attrib(row_names_symbol()) {
+            Some(row_names) => match row_names.as_integer_slice() {
+                Some(&[a, b]) if a.is_na() => b.unsigned_abs() as usize,
+                _ => row_names.len(),
+            },
+            None => self
+                .robj
+                .as_list()
+                .and_then(|columns| columns.iter().next())
+                .map_or(0, |(_, column)| column.len()),
+        }
+    }
+}
+
+/// The compact form of automatic row names `1:rows`, as R stores them.
+pub(crate) fn compact_row_names(rows: usize) -> Robj {
+    if rows == 0 {
+        Robj::from(Vec::<i32>::new())
+    } else {
+        Robj::from([NA_INTEGER, Some(-(rows as i32))])
+    }
 }
 
 impl<T> Attributes for Dataframe<T> {}
@@ -0,0 +1,150 @@
+use super::*;
+use std::time::Duration;
+
+/// The `units` of a `difftime`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifftimeUnits {
+    Secs,
+    Mins,
+    Hours,
+    Days,
+    Weeks,
+}
+
+impl DifftimeUnits {
+    /// The name R uses, such as `"mins"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DifftimeUnits::Secs => "secs",
+            DifftimeUnits::Mins => "mins",
+            DifftimeUnits::Hours => "hours",
+            DifftimeUnits::Days => "days",
+            DifftimeUnits::Weeks => "weeks",
+        }
+    }
+
+    /// The number of seconds in one unit.
+    pub fn seconds(&self) -> f64 {
+        match self {
+            DifftimeUnits::Secs => 1.,
+            DifftimeUnits::Mins => 60.,
+            DifftimeUnits::Hours => 3600.,
+            DifftimeUnits::Days => 86400.,
+            DifftimeUnits::Weeks => 604800.,
+        }
+    }
+}
+
+impl std::str::FromStr for DifftimeUnits {
+    type Err = Error;
+
+    fn from_str(units: &str) -> Result<Self> {
+        match units {
+            "secs" => Ok(DifftimeUnits::Secs),
+            "mins" => Ok(DifftimeUnits::Mins),
+            "hours" => Ok(DifftimeUnits::Hours),
+            "days" => Ok(DifftimeUnits::Days),
+            "weeks" => Ok(DifftimeUnits::Weeks),
+            _ => Err(Error::Other(format!("unknown difftime units {:?}", units))),
+        }
+    }
+}
+
+/// A time difference in given units, an R `difftime` of length one.
+///
+/// Unlike [`Duration`], a `Difftime` can be negative, and it keeps the
+/// units it was read with.
+/// ```
+/// use extendr_api::prelude::*;
+/// use std::time::Duration;
+/// test! {
+///     let days = Difftime::try_from(&R!("as.difftime(-1.5, units = 'days')")?)?;
+///     assert_eq!(days, Difftime::new(-1.5, DifftimeUnits::Days));
+///     assert_eq!(days.in_units(DifftimeUnits::Hours).value, -36.);
+///
+///     let hours = Difftime::from_duration(Duration::from_secs(5400), DifftimeUnits::Hours);
+///     assert_eq!(Robj::from(hours), R!("as.difftime(1.5, units = 'hours')")?);
+///     assert_eq!(hours.to_duration()?, Duration::from_secs(5400));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difftime {
+    /// The time difference, in `units`.
+    pub value: f64,
+    pub units: DifftimeUnits,
+}
+
+impl Difftime {
+    pub fn new(value: f64, units: DifftimeUnits) -> Self {
+        Self { value, units }
+    }
+
+    /// A duration expressed in `units`.
+    pub fn from_duration(duration: Duration, units: DifftimeUnits) -> Self {
+        Self::new(duration.as_secs_f64() / units.seconds(), units)
+    }
+
+    /// The time difference in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.value * self.units.seconds()
+    }
+
+    /// The same time difference in other units.
+    pub fn in_units(&self, units: DifftimeUnits) -> Self {
+        Self::new(self.seconds() / units.seconds(), units)
+    }
+
+    /// Convert to a duration. Negative time differences are out of range.
+    pub fn to_duration(&self) -> Result<Duration> {
+        let seconds = self.seconds();
+        if (0. ..u64::MAX as f64).contains(&seconds) {
+            Ok(Duration::from_secs_f64(seconds))
+        } else {
+            Err(Error::Other(format!(
+                "time difference of {} seconds is out of range for a Duration",
+                seconds
+            )))
+        }
+    }
+}
+
+impl TryFrom<&Robj> for Difftime {
+    type Error = Error;
+
+    /// Convert a `difftime`, or a number of seconds, of length one.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        let units = if robj.inherits("difftime") {
+            robj.get_attrib("units")
+                .and_then(|units| units.as_str())
+                .unwrap_or_default()
+                .parse()?
+        } else {
+            DifftimeUnits::Secs
+        };
+        let value = robj.as_scalar_real().or_else(|_| {
+            robj.as_scalar_integer()
+                .map(f64::from)
+                .map_err(|_| Error::ExpectedNumeric(robj.clone()))
+        })?;
+        Ok(Self::new(value, units))
+    }
+}
+
+impl TryFrom<Robj> for Difftime {
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl From<Difftime> for Robj {
+    /// Make a `difftime` with the units of the time difference.
+    fn from(value: Difftime) -> Self {
+        RobjBuilder::new(value.value)
+            .attr("units", value.units.as_str())
+            .class(["difftime"])
+            .build()
+            .expect("Internal error: failed to set difftime attributes")
+    }
+}
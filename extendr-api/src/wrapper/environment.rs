@@ -143,6 +143,45 @@ impl Environment {
         self.iter().map(|(k, _)| k)
     }
 
+    /// Iterate over the bindings of an environment as `(name, value)` pairs,
+    /// like `ls()` followed by `get()`.
+    ///
+    /// `all_names` includes names starting with a `.` and `sorted` sorts by name,
+    /// as the arguments of `ls()`. Values of delayed bindings are unevaluated promises.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let env = Environment::new_with_parent(global_env());
+    ///     env.set_local(sym!(b), 2);
+    ///     env.set_local(sym!(a), 1);
+    ///     env.set_local(sym!(.hidden), 3);
+    ///     let bindings = env.bindings(false, true).collect::<Vec<_>>();
+    ///     assert_eq!(bindings, vec![("a".to_string(), r!(1)), ("b".to_string(), r!(2))]);
+    ///     assert_eq!(env.bindings(true, false).len(), 3);
+    /// }
+    /// ```
+    pub fn bindings(&self, all_names: bool, sorted: bool) -> EnvBindings {
+        let to_rboolean = |value: bool| {
+            if value {
+                Rboolean::TRUE
+            } else {
+                Rboolean::FALSE
+            }
+        };
+        let names = single_threaded(|| unsafe {
+            Robj::from_sexp(R_lsInternal3(
+                self.get(),
+                to_rboolean(all_names),
+                to_rboolean(sorted),
+            ))
+        });
+        EnvBindings {
+            env: self.clone(),
+            names: names.try_into().unwrap(),
+            index: 0,
+        }
+    }
+
     /// Set or define a variable in an environment.
     /// ```
     /// use extendr_api::prelude::*;
@@ -221,6 +260,38 @@ impl Iterator for EnvIter {
     }
 }
 
+/// Iterator over the bindings of an environment, see [`Environment::bindings`].
+#[derive(Clone)]
+pub struct EnvBindings {
+    env: Environment,
+    names: Strings,
+    index: usize,
+}
+
+impl Iterator for EnvBindings {
+    type Item = (String, Robj);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.names.len() {
+            return None;
+        }
+        let name = self.names.elt(self.index);
+        self.index += 1;
+        let value = single_threaded(|| unsafe {
+            let sym = Rf_installChar(name.get());
+            Robj::from_sexp(Rf_findVarInFrame(self.env.get(), sym))
+        });
+        Some((name.as_str().to_string(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.names.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for EnvBindings {}
+
 impl std::fmt::Debug for Environment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         unsafe {
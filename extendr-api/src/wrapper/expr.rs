@@ -1,5 +1,18 @@
 use super::*;
 
+/// Wrapper for expression vectors (EXPRSXP), as returned by `parse()`
+/// and `expression()`.
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let exprs = parse("x <- 1; x + 1")?;
+///     assert_eq!(exprs.len(), 2);
+///     assert_eq!(exprs.elt(1)?, lang!("+", sym!(x), 1.));
+///     let env = Environment::new_with_parent(global_env());
+///     assert_eq!(exprs.eval_all_with_env(&env)?, r!(2.));
+/// }
+/// ```
 #[derive(PartialEq, Clone)]
 pub struct Expressions {
     pub(crate) robj: Robj,
@@ -32,9 +45,92 @@ impl Expressions {
     }
 
     /// Return an iterator over the values of this expression list.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let exprs = parse("a; b + 1")?;
+    ///     let calls = exprs.values().filter(|expr| expr.is_language()).count();
+    ///     assert_eq!(calls, 1);
+    /// }
+    /// ```
     pub fn values(&self) -> ListIter {
         ListIter::from_parts(self.robj.clone(), 0, self.robj.len())
     }
+
+    /// Get an expression by position.
+    pub fn elt(&self, i: usize) -> Result<Robj> {
+        if i >= self.robj.len() {
+            Err(Error::OutOfRange(self.robj.clone()))
+        } else {
+            unsafe {
+                let sexp = VECTOR_ELT(self.robj.get(), i as R_xlen_t);
+                Ok(Robj::from_sexp(sexp))
+            }
+        }
+    }
+
+    /// Replace an expression by position.
+    ///
+    /// If the expression vector may be shared with R, it is copied first,
+    /// as R would do, so the change is not visible elsewhere.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let mut exprs = parse("1 + 1")?;
+    ///     exprs.set_elt(0, lang!("*", 2, 3))?;
+    ///     assert_eq!(exprs.eval_all_with_env(&global_env())?, r!(6));
+    ///     assert!(exprs.set_elt(1, r!(1)).is_err());
+    ///
+    ///     R!("shared_exprs <- expression(1 + 1)")?;
+    ///     let mut exprs = Expressions::try_from(R!("shared_exprs")?)?;
+    ///     exprs.set_elt(0, r!(3))?;
+    ///     assert_eq!(R!("eval(shared_exprs[[1]])")?, r!(2.));
+    /// }
+    /// ```
+    pub fn set_elt(&mut self, i: usize, value: Robj) -> Result<()> {
+        single_threaded(|| unsafe {
+            if i >= self.robj.len() {
+                Err(Error::OutOfRange(self.robj.clone()))
+            } else {
+                if self.robj.is_shared() {
+                    self.robj = self.robj.duplicate();
+                }
+                SET_VECTOR_ELT(self.robj.get_mut(), i as R_xlen_t, value.get());
+                Ok(())
+            }
+        })
+    }
+
+    /// Evaluate each expression in turn in `env` and return the value of
+    /// the last one, or `NULL` if there are none.
+    ///
+    /// This is what `eval()` does with an expression vector in R. Note
+    /// that [`Eval::eval`] returns an expression vector unchanged.
+    pub fn eval_all_with_env(&self, env: &Environment) -> Result<Robj> {
+        let mut result = Robj::from(());
+        for expr in self.values() {
+            result = expr.eval_with_env(env)?;
+        }
+        Ok(result)
+    }
+}
+
+impl IntoIterator for Expressions {
+    type IntoIter = ListIter;
+    type Item = Robj;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values()
+    }
+}
+
+impl IntoIterator for &Expressions {
+    type IntoIter = ListIter;
+    type Item = Robj;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values()
+    }
 }
 
 impl std::default::Default for Expressions {
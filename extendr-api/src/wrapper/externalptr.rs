@@ -124,6 +124,34 @@ impl<T: 'static> ExternalPtr<T> {
     /// An ExternalPtr behaves like a Box except that the information is
     /// tracked by a R object.
     pub fn new(val: T) -> Self {
+        Self::new_with_finalizer(val, Some(finalizer))
+    }
+
+    /// Construct an external pointer object whose finalizer calls
+    /// [`OnRRelease::on_r_release`] before the Rust object is dropped.
+    ///
+    /// This is used by `#[extendr(on_r_release = "method")]` impl blocks.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     struct Handle(i32);
+    ///     impl OnRRelease for Handle {
+    ///         fn on_r_release(&mut self) {
+    ///             self.0 = 0;
+    ///         }
+    ///     }
+    ///     let extptr = ExternalPtr::new_with_release(Handle(1));
+    ///     assert_eq!(extptr.0, 1);
+    /// }
+    /// ```
+    pub fn new_with_release(val: T) -> Self
+    where
+        T: OnRRelease,
+    {
+        Self::new_with_finalizer(val, Some(release_finalizer::<T>))
+    }
+
+    fn new_with_finalizer(val: T, func: R_CFinalizer_t) -> Self {
         single_threaded(|| unsafe {
             // This allocates some memory for our object and moves the object into it.
             let boxed: Box<dyn Any> = Box::new(val);
@@ -141,24 +169,8 @@ impl<T: 'static> ExternalPtr<T> {
                 }))
             };
 
-            extern "C" fn finalizer(x: SEXP) {
-                unsafe {
-                    let ptr = R_ExternalPtrAddr(x).cast::<Box<dyn Any>>();
-
-                    // Free the `tag`, which is the type-name
-                    R_SetExternalPtrTag(x, R_NilValue);
-
-                    // Convert the pointer to a box and drop it implictly.
-                    // This frees up the memory we have used and calls the "T::drop" method if there is one.
-                    drop(Box::from_raw(ptr));
-
-                    // Now set the pointer in ExternalPTR to C `NULL`
-                    R_ClearExternalPtr(x);
-                }
-            }
-
             // Tell R about our finalizer
-            robj.register_c_finalizer(Some(finalizer));
+            robj.register_c_finalizer(func);
 
             // Return an object in a wrapper.
             Self {
@@ -233,6 +245,47 @@ impl<T: 'static> ExternalPtr<T> {
     }
 }
 
+/// A hook run by the finalizer of an [`ExternalPtr`] when R garbage-collects it,
+/// just before the wrapped value is dropped.
+///
+/// Use this to flush files or close connections deterministically.
+/// Panics inside the hook are caught, as the finalizer cannot unwind into R.
+pub trait OnRRelease {
+    fn on_r_release(&mut self);
+}
+
+/// Drop the boxed Rust object of an external pointer and clear the pointer.
+unsafe fn free_external_ptr(x: SEXP) {
+    let ptr = R_ExternalPtrAddr(x).cast::<Box<dyn Any>>();
+    if ptr.is_null() {
+        return;
+    }
+
+    // Free the `tag`, which is the type-name
+    R_SetExternalPtrTag(x, R_NilValue);
+
+    // Convert the pointer to a box and drop it implictly.
+    // This frees up the memory we have used and calls the "T::drop" method if there is one.
+    drop(Box::from_raw(ptr));
+
+    // Now set the pointer in ExternalPTR to C `NULL`
+    R_ClearExternalPtr(x);
+}
+
+extern "C" fn finalizer(x: SEXP) {
+    unsafe { free_external_ptr(x) }
+}
+
+extern "C" fn release_finalizer<T: OnRRelease + 'static>(x: SEXP) {
+    unsafe {
+        let ptr = R_ExternalPtrAddr(x).cast::<Box<dyn Any>>();
+        if let Some(val) = ptr.as_mut().and_then(|b| b.downcast_mut::<T>()) {
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| val.on_r_release()));
+        }
+        free_external_ptr(x);
+    }
+}
+
 impl<T: 'static> TryFrom<&Robj> for &ExternalPtr<T> {
     type Error = Error;
 
@@ -29,27 +29,51 @@ pub struct Function {
 }
 
 impl Function {
-    #[cfg(feature = "non-api")]
-    /// Make a function from parts.
+    /// Make a closure from its formal arguments, body and enclosing environment,
+    /// like `` `function`(formals, body) `` evaluated in `env`.
+    /// The body is usually a language object, but may be any value.
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
     ///     let formals = pairlist!(a=NULL);
-    ///     let body = lang!("+", sym!(a), r!(1)).try_into()?;
+    ///     let body = lang!("+", sym!(a), r!(1));
     ///     let env = global_env();
     ///     let f = r!(Function::from_parts(formals, body, env )?);
     ///     assert_eq!(f.call(pairlist!(a=1))?, r!(2));
+    ///
+    ///     // Missing arguments have no default.
+    ///     let formals = Pairlist::from_pairs(vec![("x", missing_arg().into()), ("n", r!(2))]);
+    ///     let f = Function::from_parts(formals, lang!("^", sym!(x), sym!(n)), base_env())?;
+    ///     assert_eq!(f.call(pairlist!(3))?, r!(9.0));
     /// }
     /// ```
-    pub fn from_parts(formals: Pairlist, body: Language, env: Environment) -> Result<Self> {
-        single_threaded(|| unsafe {
-            let sexp = Rf_allocSExp(SEXPTYPE::CLOSXP);
-            let robj = Robj::from_sexp(sexp);
-            SET_FORMALS(sexp, formals.get());
-            SET_BODY(sexp, body.get());
-            SET_CLOENV(sexp, env.get());
-            Ok(Function { robj })
-        })
+    pub fn from_parts<B: Into<Robj>>(formals: Pairlist, body: B, env: Environment) -> Result<Self> {
+        use crate as extendr_api;
+        // `function` does not evaluate its arguments.
+        let robj = lang!("function", formals, body.into()).eval_with_env(&env)?;
+        robj.as_function()
+            .ok_or_else(|| Error::ExpectedFunction(robj.clone()))
+    }
+
+    /// Byte-compile the function with `compiler::cmpfun()`, which may make
+    /// loops and arithmetic in the body faster.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let f = Function::from_parts(pairlist!(a=NULL), lang!("*", sym!(a), 2), global_env())?;
+    ///     let compiled = f.compile()?;
+    ///     assert!(compiled.body().unwrap().is_bytecode());
+    ///     assert_eq!(compiled.call(pairlist!(a=21))?, r!(42));
+    /// }
+    /// ```
+    pub fn compile(&self) -> Result<Function> {
+        let cmpfun = eval_string("compiler::cmpfun")?;
+        let cmpfun = cmpfun
+            .as_function()
+            .ok_or_else(|| Error::ExpectedFunction(cmpfun.clone()))?;
+        let robj = cmpfun.call(Pairlist::from_pairs([("", self.robj.clone())]))?;
+        robj.as_function()
+            .ok_or_else(|| Error::ExpectedFunction(robj.clone()))
     }
 
     /// Do the equivalent of x(a, b, c)
@@ -67,6 +91,27 @@ impl Function {
         })
     }
 
+    /// Do the equivalent of x(a, b, c) with arguments that may be optional,
+    /// spliced from a list or forwarded from `...`. See [CallArg].
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let round = R!("round")?.as_function().unwrap();
+    ///     let digits: Option<i32> = None;
+    ///     let args = vec![
+    ///         CallArg::Value("", r!(1.25)),
+    ///         CallArg::Optional("digits", digits.map(Robj::from)),
+    ///     ];
+    ///     assert_eq!(round.call_with(args)?, r!(1.0));
+    ///
+    ///     let args = vec![CallArg::Splice(list!(1.25, digits = 1).into())];
+    ///     assert_eq!(round.call_with(args)?, r!(1.2));
+    /// }
+    /// ```
+    pub fn call_with(&self, args: Vec<CallArg>) -> Result<Robj> {
+        self.call(call_args(args)?)
+    }
+
     /// Get the formal arguments of the function or None if it is a primitive.
     pub fn formals(&self) -> Option<Pairlist> {
         unsafe {
@@ -108,6 +153,77 @@ impl Function {
     }
 }
 
+/// An argument of a call to an R function, as used by [Function::call_with] and `call!`.
+#[derive(Debug, Clone)]
+pub enum CallArg {
+    /// A value with a name, which is empty if there is none.
+    Value(&'static str, Robj),
+    /// A named value which is left out of the call if it is `None`,
+    /// so that the function uses its default. `name? = value` in `call!`.
+    Optional(&'static str, Option<Robj>),
+    /// The elements of a list, pairlist or vector, with their names. `!!value` in `call!`.
+    /// `NULL` adds no arguments and other objects add a single argument.
+    Splice(Robj),
+    /// The arguments matched to `...` in an environment, which are forwarded
+    /// without evaluating them. `...` in `call!` forwards those of
+    /// [current_env](crate::functions::current_env).
+    Dots(Environment),
+}
+
+/// Make the argument pairlist of a call.
+/// ```
+/// use extendr_api::{prelude::*, wrapper::function::call_args};
+/// test! {
+///     let args = call_args(vec![
+///         CallArg::Value("", r!(1)),
+///         CallArg::Optional("b", None),
+///         CallArg::Optional("c", Some(r!(3))),
+///         CallArg::Splice(list!(d = 4, 5).into()),
+///         CallArg::Splice(r!(NULL)),
+///     ])?;
+///     assert_eq!(r!(args), pairlist!(1, c = 3, d = 4, 5));
+///
+///     // An environment in which `...` is bound.
+///     let env = R!("(function(...) environment())(x = 1, 2)")?;
+///     let args = call_args(vec![CallArg::Dots(env.try_into()?)])?;
+///     assert_eq!(call!("list", !!args)?, list!(x = 1, 2));
+/// }
+/// ```
+#[doc(hidden)]
+pub fn call_args(args: Vec<CallArg>) -> Result<Pairlist> {
+    let mut pairs: Vec<(&'static str, Robj)> = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            CallArg::Value(name, value) | CallArg::Optional(name, Some(value)) => {
+                pairs.push((name, value))
+            }
+            CallArg::Optional(_, None) => {}
+            CallArg::Splice(robj) if robj.is_null() => {}
+            CallArg::Splice(robj) if robj.is_pairlist() => {
+                pairs.extend(robj.as_pairlist().unwrap().iter())
+            }
+            CallArg::Splice(robj) => match robj.pairs() {
+                Some(iter) => pairs.extend(iter.map(|(name, value)| (name.unwrap_or(""), value))),
+                None => pairs.push(("", robj)),
+            },
+            CallArg::Dots(env) => {
+                let dots = env
+                    .find_var(Robj::from(super::symbol::dots_symbol()))
+                    .map_err(|_| Error::Other("`...` is not defined in this environment".into()))?;
+                // `...` is the missing argument if nothing was matched to it.
+                if dots.rtype() == Rtype::Dot {
+                    let iter = PairlistIter {
+                        list_elem: unsafe { dots.get() },
+                        robj: dots,
+                    };
+                    pairs.extend(iter);
+                }
+            }
+        }
+    }
+    Ok(Pairlist::from_pairs(pairs))
+}
+
 impl std::fmt::Debug for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.deparse().unwrap())
@@ -122,6 +122,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn into_iter() {
+        test! {
+            let mut vec = Integers::from_values([Some(1), None, Some(3)]);
+            for value in &mut vec {
+                if !value.is_na() {
+                    *value = *value * 2;
+                }
+            }
+            let mut values = Vec::new();
+            for value in &vec {
+                values.push(value);
+            }
+            assert_eq!(values, [Rint::from(2), Rint::na(), Rint::from(6)]);
+        }
+    }
+
     #[test]
     fn iter() {
         test! {
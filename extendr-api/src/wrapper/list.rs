@@ -193,6 +193,95 @@ impl List {
     }
 }
 
+/// A hashed index from element names to positions, built once
+/// so that repeated lookups by name do not scan the `names` attribute.
+///
+/// Like R's `[[`, only the first element with a given name is found.
+/// Empty and `NA` names are not indexed.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let list = list!(a = 1, b = "x", a = 3);
+///     let index = NameIndex::new(&list).unwrap();
+///     assert_eq!(index.position("b"), Some(1));
+///     assert_eq!(index.get("a"), Some(r!(1)));
+///     assert_eq!(index.get("z"), None);
+///
+///     let vector = r!([1, 2, 3]).set_names(&["x", "y", "z"])?.clone();
+///     let index = NameIndex::new(&vector).unwrap();
+///     assert_eq!(index.get("y"), Some(r!(2)));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct NameIndex {
+    robj: Robj,
+    positions: HashMap<&'static str, usize>,
+}
+
+impl NameIndex {
+    /// Index the names of a vector or list.
+    /// Returns `None` if the object has no `names` attribute.
+    pub fn new<T: Attributes>(obj: &T) -> Option<Self> {
+        let names = obj.names()?;
+        let mut positions = HashMap::with_capacity(names.len());
+        for (i, name) in names.enumerate() {
+            if !name.is_empty() && !name.is_na() {
+                positions.entry(name).or_insert(i);
+            }
+        }
+        Some(Self {
+            robj: obj.as_robj().clone(),
+            positions,
+        })
+    }
+
+    /// Zero-based position of the first element called `name`.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.positions.get(name).copied()
+    }
+
+    /// Return true if some element is called `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.positions.contains_key(name)
+    }
+
+    /// Get the first element called `name`, equivalent to `x[["name", exact = TRUE]]`.
+    pub fn get(&self, name: &str) -> Option<Robj> {
+        let i = self.position(name)?;
+        if self.robj.is_list() {
+            unsafe { Some(Robj::from_sexp(VECTOR_ELT(self.robj.get(), i as R_xlen_t))) }
+        } else {
+            self.robj.index((i + 1) as i32).ok()
+        }
+    }
+
+    /// Number of distinct names in the index.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Return true if no names were indexed.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+impl List {
+    /// Build a [`NameIndex`] for repeated lookups by name.
+    /// Returns `None` if the list has no names.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let list = list!(a = 1, b = 2).as_list().unwrap();
+    ///     let index = list.name_index().unwrap();
+    ///     assert_eq!(index.get("b"), Some(r!(2)));
+    /// }
+    /// ```
+    pub fn name_index(&self) -> Option<NameIndex> {
+        NameIndex::new(self)
+    }
+}
+
 impl IntoIterator for List {
     type IntoIter = NamedListIter;
     type Item = (&'static str, Robj);
@@ -258,32 +347,55 @@ impl Iterator for ListIter {
     type Item = Robj;
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len, Some(self.len))
+        let len = self.len();
+        (len, Some(len))
     }
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.len {
+            return None;
+        }
         let i = self.i;
         self.i += 1;
-        if i >= self.len {
-            None
-        } else {
-            Some(unsafe { Robj::from_sexp(VECTOR_ELT(self.robj.get(), i as isize)) })
-        }
+        Some(unsafe { Robj::from_sexp(VECTOR_ELT(self.robj.get(), i as isize)) })
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.i += n;
+        self.i = self.i.saturating_add(n).min(self.len);
         self.next()
     }
 }
 
+/// Iterate from the end, for example with `rev()`.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let list = list!(1, 2, 3);
+///     let values = list.values().rev().collect::<Vec<_>>();
+///     assert_eq!(values, vec![r!(3), r!(2), r!(1)]);
+///     let pairs = list!(a = 1, b = 2).iter().rev().collect::<Vec<_>>();
+///     assert_eq!(pairs, vec![("b", r!(2)), ("a", r!(1))]);
+/// }
+/// ```
+impl DoubleEndedIterator for ListIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.i >= self.len {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { Robj::from_sexp(VECTOR_ELT(self.robj.get(), self.len as isize)) })
+    }
+}
+
 impl ExactSizeIterator for ListIter {
     /// Length of a list iterator.
     fn len(&self) -> usize {
-        self.len - self.i
+        self.len.saturating_sub(self.i)
     }
 }
 
+impl std::iter::FusedIterator for ListIter {}
+
 /// You can use the FromList wrapper to coerce a Robj into a list.
 /// ```
 /// use extendr_api::prelude::*;
@@ -394,6 +506,57 @@ impl<T: Into<Robj>> FromIterator<T> for List {
     }
 }
 
+/// An entry of `list!`: a value with a name, which is empty if there is none,
+/// or a vector or list whose elements are spliced in with `!!`.
+#[doc(hidden)]
+pub enum ListEntry {
+    Value(&'static str, Robj),
+    Splice(Robj),
+}
+
+/// Used by `list!` when it has spliced entries.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let other = list!(c = 3, 4);
+///     assert_eq!(list!(1, b = 2, !!other), list!(1, b = 2, c = 3, 4));
+///     assert_eq!(list!(!!r!([1, 2]), !!()), list!(1, 2));
+///     assert_eq!(list!(a = 1, !!list!()), list!(a = 1));
+/// }
+/// ```
+#[doc(hidden)]
+pub fn list_from_entries(entries: Vec<ListEntry>) -> List {
+    let mut names = Vec::with_capacity(entries.len());
+    let mut values = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match entry {
+            ListEntry::Value(name, value) => {
+                names.push(name);
+                values.push(value);
+            }
+            ListEntry::Splice(robj) if robj.is_null() => {}
+            ListEntry::Splice(robj) => match robj.pairs() {
+                Some(pairs) => {
+                    for (name, value) in pairs {
+                        names.push(name.unwrap_or(""));
+                        values.push(value);
+                    }
+                }
+                None => {
+                    names.push("");
+                    values.push(robj);
+                }
+            },
+        }
+    }
+    if names.iter().all(|name| name.is_empty()) {
+        List::from_values(values)
+    } else {
+        // Note that this unwrap should not fail, as there is a name for each value.
+        List::from_names_and_values(names, values).unwrap()
+    }
+}
+
 impl Attributes for List {}
 
 impl Deref for List {
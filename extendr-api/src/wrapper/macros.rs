@@ -130,6 +130,26 @@ macro_rules! gen_vector_wrapper_impl {
             }
         }
 
+        impl<'a> IntoIterator for &'a $type {
+            type Item = $scalar_type;
+            type IntoIter = std::iter::Cloned<std::slice::Iter<'a, $scalar_type>>;
+
+            /// Iterate over the elements, like `iter()`.
+            fn into_iter(self) -> Self::IntoIter {
+                self.as_robj().as_typed_slice().unwrap().iter().cloned()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a mut $type {
+            type Item = &'a mut $scalar_type;
+            type IntoIter = std::slice::IterMut<'a, $scalar_type>;
+
+            /// Iterate over mutable references to the elements, like `iter_mut()`.
+            fn into_iter(self) -> Self::IntoIter {
+                self.as_robj_mut().as_typed_slice_mut().unwrap().iter_mut()
+            }
+        }
+
         impl FromIterator<$scalar_type> for $type {
             /// A more generalised iterator collector for small vectors.
             /// Generates a non-ALTREP vector.
@@ -72,6 +72,8 @@ impl<T, D> RArray<T, D> {
 pub type RColumn<T> = RArray<T, [usize; 1]>;
 pub type RMatrix<T> = RArray<T, [usize; 2]>;
 pub type RMatrix3D<T> = RArray<T, [usize; 3]>;
+/// An array with any number of dimensions, as given by its `dim` attribute.
+pub type RArrayN<T> = RArray<T, Vec<usize>>;
 
 impl<T> RMatrix<T>
 where
@@ -140,6 +142,61 @@ impl<T> RMatrix<T> {
             }
         }
     }
+
+    /// Set the row names, keeping any column names.
+    ///
+    /// Returns `Error::NamesLengthMismatch` if the number of names
+    /// is not the number of rows.
+    ///
+    /// Equivalent to `rownames<-` in R.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let mut matrix = RMatrix::<f64>::new_matrix(2, 2, |r, c| (r + c) as f64);
+    ///     assert_eq!(matrix.get_rownames(), None);
+    ///     matrix.set_rownames(["x", "y"])?;
+    ///     matrix.set_colnames(["a", "b"])?;
+    ///     assert_eq!(matrix.get_rownames(), Some(Strings::from_values(["x", "y"])));
+    ///     assert_eq!(matrix.get_colnames(), Some(Strings::from_values(["a", "b"])));
+    ///     assert!(matrix.set_rownames(["x"]).is_err());
+    /// }
+    /// ```
+    pub fn set_rownames<N>(&mut self, names: N) -> Result<&mut Self>
+    where
+        N: IntoIterator,
+        N::IntoIter: ExactSizeIterator,
+        N::Item: ToVectorValue + AsRef<str>,
+    {
+        self.set_dimnames_component(0, names.into_iter().collect_robj())
+    }
+
+    /// Set the column names, keeping any row names.
+    ///
+    /// Returns `Error::NamesLengthMismatch` if the number of names
+    /// is not the number of columns.
+    ///
+    /// Equivalent to `colnames<-` in R.
+    pub fn set_colnames<N>(&mut self, names: N) -> Result<&mut Self>
+    where
+        N: IntoIterator,
+        N::IntoIter: ExactSizeIterator,
+        N::Item: ToVectorValue + AsRef<str>,
+    {
+        self.set_dimnames_component(1, names.into_iter().collect_robj())
+    }
+
+    fn set_dimnames_component(&mut self, i: usize, names: Robj) -> Result<&mut Self> {
+        if names.len() != self.dim[i] {
+            return Err(Error::NamesLengthMismatch(names));
+        }
+        let mut dimnames: [Robj; 2] = [self.get_rownames().into(), self.get_colnames().into()];
+        dimnames[i] = names;
+        self.set_attrib(
+            wrapper::symbol::dimnames_symbol(),
+            List::from_values(dimnames),
+        )?;
+        Ok(self)
+    }
 }
 
 const BASE: usize = 0;
@@ -188,6 +245,25 @@ impl<T> Offset<[usize; 3]> for RArray<T, [usize; 3]> {
     }
 }
 
+impl<T> Offset<&[usize]> for RArray<T, Vec<usize>> {
+    /// Get the offset into the array for a given index, in column-major order.
+    fn offset(&self, index: &[usize]) -> usize {
+        if index.len() != self.dim.len() {
+            panic!("array index: expected {} dimensions", self.dim.len());
+        }
+        let mut offset = 0;
+        let mut stride = 1;
+        for (i, (&idx, &extent)) in index.iter().zip(self.dim.iter()).enumerate() {
+            if idx - BASE >= extent {
+                panic!("array index: dimension {} overflow", i + 1);
+            }
+            offset += (idx - BASE) * stride;
+            stride *= extent;
+        }
+        offset
+    }
+}
+
 impl<T, D> RArray<T, D>
 where
     Robj: for<'a> AsTypedSlice<'a, T>,
@@ -278,6 +354,88 @@ where
     }
 }
 
+/// Elements of a matrix row, see [`RMatrix::rows`].
+pub type RowIter<'a, T> = std::iter::StepBy<std::slice::Iter<'a, T>>;
+
+impl<T> RMatrix<T>
+where
+    Robj: for<'a> AsTypedSlice<'a, T>,
+{
+    /// Iterate over the columns, which are contiguous in R.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let matrix = RMatrix::new_matrix(2, 3, |r, c| (r + 10 * c) as f64);
+    ///     let cols = matrix.cols().collect::<Vec<_>>();
+    ///     assert_eq!(cols, vec![&[0., 1.][..], &[10., 11.], &[20., 21.]]);
+    /// }
+    /// ```
+    pub fn cols(&self) -> impl Iterator<Item = &[T]> + '_ {
+        let nrows = self.dim[0];
+        (0..self.dim[1]).map(move |c| &self.data()[c * nrows..(c + 1) * nrows])
+    }
+
+    /// Iterate over the rows. Each row is an iterator over its elements.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let matrix = RMatrix::new_matrix(2, 3, |r, c| (r + 10 * c) as f64);
+    ///     let rows = matrix.rows().map(|row| row.copied().collect::<Vec<_>>()).collect::<Vec<_>>();
+    ///     assert_eq!(rows, vec![vec![0., 10., 20.], vec![1., 11., 21.]]);
+    /// }
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = RowIter<'_, T>> + '_ {
+        let nrows = self.dim[0];
+        (0..nrows).map(move |r| self.data()[r..].iter().step_by(nrows))
+    }
+
+    /// Apply `f` to each column, giving a vector with one element per column,
+    /// named by the column names, like `apply(x, 2, f)`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let matrix = RMatrix::new_matrix(2, 3, |r, c| (r + 10 * c) as f64);
+    ///     let sums = matrix.map_cols(|col| col.iter().sum::<f64>());
+    ///     assert_eq!(sums, r!([1., 21., 41.]));
+    /// }
+    /// ```
+    pub fn map_cols<U, F>(&self, f: F) -> Robj
+    where
+        U: ToVectorValue,
+        F: FnMut(&[T]) -> U,
+    {
+        let mut res = self.cols().map(f).collect_robj();
+        if let Some(names) = self.get_colnames() {
+            res.set_attrib(wrapper::symbol::names_symbol(), names)
+                .unwrap();
+        }
+        res
+    }
+
+    /// Apply `f` to each row, giving a vector with one element per row,
+    /// named by the row names, like `apply(x, 1, f)`.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let matrix = RMatrix::new_matrix(2, 3, |r, c| (r + 10 * c) as f64);
+    ///     let maxima = matrix.map_rows(|row| row.copied().fold(f64::MIN, f64::max));
+    ///     assert_eq!(maxima, r!([20., 21.]));
+    /// }
+    /// ```
+    pub fn map_rows<U, F>(&self, f: F) -> Robj
+    where
+        U: ToVectorValue,
+        F: FnMut(RowIter<'_, T>) -> U,
+    {
+        let mut res = self.rows().map(f).collect_robj();
+        if let Some(names) = self.get_rownames() {
+            res.set_attrib(wrapper::symbol::names_symbol(), names)
+                .unwrap();
+        }
+        res
+    }
+}
+
 impl<T> RMatrix3D<T>
 where
     T: ToVectorValue,
@@ -319,6 +477,73 @@ where
     }
 }
 
+impl<T> RArrayN<T>
+where
+    T: ToVectorValue,
+    Robj: for<'a> AsTypedSlice<'a, T>,
+{
+    /// Create an array with dimensions `dim`, calling `f` with the zero-based
+    /// index of each element.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let array = RArrayN::new_array(vec![2, 3, 4], |i| (i[0] + 10 * i[1] + 100 * i[2]) as f64);
+    ///     assert_eq!(array.ndim(), 3);
+    ///     assert_eq!(array[&[1, 2, 3][..]], 123.);
+    ///     assert_eq!(r!(array), R!("array(outer(outer(0:1, 10 * 0:2, `+`), 100 * 0:3, `+`), c(2, 3, 4))")?);
+    /// }
+    /// ```
+    pub fn new_array<F: FnMut(&[usize]) -> T>(dim: Vec<usize>, mut f: F) -> Self {
+        let len = dim.iter().product::<usize>();
+        let mut index = vec![0; dim.len()];
+        let mut robj = (0..len)
+            .map(|_| {
+                let value = f(&index);
+                // Advance the index in column-major order.
+                for (idx, &extent) in index.iter_mut().zip(dim.iter()) {
+                    *idx += 1;
+                    if *idx < extent {
+                        break;
+                    }
+                    *idx = 0;
+                }
+                value
+            })
+            .collect_robj();
+        let r_dim = dim.iter().map(|&d| d as i32).collect::<Vec<_>>();
+        robj.set_attrib(wrapper::symbol::dim_symbol(), r_dim)
+            .unwrap();
+        RArray::from_parts(robj, dim)
+    }
+}
+
+impl<T> RArrayN<T> {
+    /// Get the number of dimensions.
+    pub fn ndim(&self) -> usize {
+        self.dim.len()
+    }
+}
+
+impl<T> TryFrom<&Robj> for RArrayN<T>
+where
+    Robj: for<'a> AsTypedSlice<'a, T>,
+{
+    type Error = Error;
+
+    /// Vectors without a `dim` attribute are one-dimensional arrays.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if let Some(_slice) = robj.as_typed_slice() {
+            let dim = match robj.dim() {
+                Some(dim) => dim.iter().map(|d| d.inner() as usize).collect(),
+                None => vec![robj.len()],
+            };
+            Ok(RArray::from_parts(robj.clone(), dim))
+        } else {
+            Err(Error::TypeMismatch(robj.clone()))
+        }
+    }
+}
+
 impl<T> TryFrom<&Robj> for RColumn<T>
 where
     Robj: for<'a> AsTypedSlice<'a, T>,
@@ -432,6 +657,7 @@ impl_try_from_robj_ref!(
     RMatrix
     RColumn
     RMatrix3D
+    RArrayN
 );
 
 impl<T, D> From<RArray<T, D>> for Robj {
@@ -462,6 +688,13 @@ pub trait MatrixConversions: GetSexp {
     {
         <RMatrix3D<E>>::try_from(self.as_robj()).ok()
     }
+
+    fn as_array_n<E>(&self) -> Option<RArrayN<E>>
+    where
+        Robj: for<'a> AsTypedSlice<'a, E>,
+    {
+        <RArrayN<E>>::try_from(self.as_robj()).ok()
+    }
 }
 
 impl MatrixConversions for Robj {}
@@ -526,6 +759,33 @@ where
     }
 }
 
+impl<T> Index<&[usize]> for RArrayN<T>
+where
+    Robj: for<'a> AsTypedSlice<'a, T>,
+{
+    type Output = T;
+
+    /// Zero-based indexing with one index per dimension.
+    ///
+    /// Panics if out of bounds.
+    fn index(&self, index: &[usize]) -> &Self::Output {
+        &self.data()[self.offset(index)]
+    }
+}
+
+impl<T> IndexMut<&[usize]> for RArrayN<T>
+where
+    Robj: for<'a> AsTypedSlice<'a, T>,
+{
+    /// Zero-based mutable indexing with one index per dimension.
+    ///
+    /// Panics if out of bounds.
+    fn index_mut(&mut self, index: &[usize]) -> &mut Self::Output {
+        let offset = self.offset(index);
+        &mut self.data_mut()[offset]
+    }
+}
+
 impl<T, D> Deref for RArray<T, D> {
     type Target = Robj;
 
@@ -8,6 +8,7 @@ use libR_sys::*;
 pub mod altrep;
 pub mod complexes;
 pub mod dataframe;
+pub mod difftime;
 pub mod doubles;
 pub mod environment;
 pub mod expr;
@@ -21,13 +22,17 @@ mod macros;
 pub mod matrix;
 pub mod nullable;
 pub mod pairlist;
+pub mod posixlt;
 pub mod primitive;
 pub mod promise;
 pub mod raw;
 pub mod rstr;
 pub mod s4;
+pub mod sparse;
 pub mod strings;
 pub mod symbol;
+pub mod weakref;
+pub mod with_attributes;
 pub mod wrapper_macros;
 
 pub use self::rstr::Rstr;
@@ -39,22 +44,27 @@ pub use altrep::{
 };
 pub use complexes::Complexes;
 pub use dataframe::{Dataframe, IntoDataFrameRow};
+pub use difftime::{Difftime, DifftimeUnits};
 pub use doubles::Doubles;
-pub use environment::{EnvIter, Environment};
+pub use environment::{EnvBindings, EnvIter, Environment};
 pub use expr::Expressions;
-pub use externalptr::ExternalPtr;
-pub use function::Function;
+pub use externalptr::{ExternalPtr, OnRRelease};
+pub use function::{CallArg, Function};
 pub use integers::Integers;
 pub use lang::Language;
-pub use list::{FromList, List, ListIter};
+pub use list::{FromList, List, ListIter, NameIndex};
 pub use logicals::Logicals;
-pub use matrix::{MatrixConversions, RArray, RColumn, RMatrix, RMatrix3D};
+pub use matrix::{MatrixConversions, RArray, RArrayN, RColumn, RMatrix, RMatrix3D, RowIter};
 pub use nullable::Nullable;
 pub use pairlist::{Pairlist, PairlistIter};
+pub use posixlt::PosixLt;
 pub use primitive::Primitive;
 pub use promise::Promise;
 pub use raw::Raw;
 pub use s4::S4;
+pub use sparse::{SparseLayout, SparseMatrix};
 pub use strings::Strings;
 pub use symbol::Symbol;
+pub use weakref::WeakRef;
+pub use with_attributes::{WithAttributes, STRUCTURAL_ATTRIBUTES};
 pub use wrapper_macros::*;
@@ -1,5 +1,4 @@
 use super::*;
-use crate as extendr_api;
 
 /// Wrapper for handling potentially NULL values.
 /// ```
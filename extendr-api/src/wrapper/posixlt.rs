@@ -0,0 +1,301 @@
+use super::*;
+
+// The components of a `POSIXlt`, in the order R creates them.
+const COMPONENTS: [&str; 9] = [
+    "sec", "min", "hour", "mday", "mon", "year", "wday", "yday", "isdst",
+];
+
+/// A broken-down time, one element of an R `POSIXlt`.
+///
+/// R stores a `POSIXlt` as a list of component vectors (`sec`, `min`,
+/// `hour`, `mday`, `mon`, `year`, `wday`, `yday` and `isdst`) with a
+/// `tzone` attribute. The fields here are numbered like the calendar,
+/// not like R: `month` and `day` start at 1 and `year` is the full year.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let lt = R!("as.POSIXlt('2024-03-01 12:30:15', tz = 'UTC')")?;
+///     let time = PosixLt::try_from(&lt)?;
+///     assert_eq!((time.year, time.month, time.day), (2024, 3, 1));
+///     assert_eq!((time.hour, time.minute, time.second), (12, 30, 15.0));
+///     assert_eq!((time.weekday, time.ordinal), (5, 61));
+///     assert_eq!(time.tzone, "UTC");
+///
+///     let time = PosixLt::new(2024, 3, 1, 12, 30, 15.0)?.with_tzone("UTC");
+///     assert_eq!(time.weekday, 5);
+///     let robj = Robj::from(time);
+///     assert!(robj.inherits("POSIXlt"));
+///     assert_eq!(call!("format", robj)?, r!("2024-03-01 12:30:15"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PosixLt {
+    /// The year, such as 2024.
+    pub year: i32,
+    /// The month, from 1 to 12.
+    pub month: u32,
+    /// The day of the month, from 1 to 31.
+    pub day: u32,
+    /// The hour, from 0 to 23.
+    pub hour: u32,
+    /// The minute, from 0 to 59.
+    pub minute: u32,
+    /// The seconds, from 0 to 61 to allow for leap seconds.
+    pub second: f64,
+    /// The day of the week, from 0 for Sunday to 6.
+    pub weekday: u32,
+    /// The day of the year, from 1 to 366.
+    pub ordinal: u32,
+    /// Whether daylight saving time is in effect, or `None` if unknown.
+    pub is_dst: Option<bool>,
+    /// The time zone, or `""` for the current time zone.
+    pub tzone: String,
+}
+
+impl PosixLt {
+    /// A time in the current time zone, with the day of the week and of
+    /// the year filled in. Fails if the date does not exist.
+    pub fn new(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: f64,
+    ) -> Result<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(Error::Other(format!(
+                "invalid date {}-{:02}-{:02}",
+                year, month, day
+            )));
+        }
+        if hour > 23 || minute > 59 || !(0. ..62.).contains(&second) {
+            return Err(Error::Other(format!(
+                "invalid time {:02}:{:02}:{}",
+                hour, minute, second
+            )));
+        }
+        let ordinal = (1..month).map(|m| days_in_month(year, m)).sum::<u32>() + day;
+        let weekday = (days_from_civil(year, month, day) + 4).rem_euclid(7) as u32;
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            weekday,
+            ordinal,
+            is_dst: None,
+            tzone: String::new(),
+        })
+    }
+
+    /// Set the time zone, such as `"UTC"` or `"Europe/London"`.
+    pub fn with_tzone(mut self, tzone: &str) -> Self {
+        self.tzone = tzone.to_string();
+        self
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    // Months are counted from March, so that the leap day comes last.
+    let month = (i64::from(month) + 9) % 12;
+    let day_of_year = (153 * month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// The values of a component, as doubles with `None` for `NA`.
+fn component(list: &List, name: &str) -> Result<Vec<Option<f64>>> {
+    let robj = list
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| Error::Other(format!("missing POSIXlt component `{}`", name)))?;
+    if let Some(values) = robj.as_real_slice() {
+        Ok(values
+            .iter()
+            .map(|x| if x.is_na() { None } else { Some(*x) })
+            .collect())
+    } else if let Some(values) = robj.as_integer_slice() {
+        Ok(values
+            .iter()
+            .map(|x| if x.is_na() { None } else { Some(f64::from(*x)) })
+            .collect())
+    } else {
+        Err(Error::ExpectedNumeric(robj))
+    }
+}
+
+impl TryFrom<&Robj> for Vec<Option<PosixLt>> {
+    type Error = Error;
+
+    /// Convert a `POSIXlt`, with `None` for `NA` times.
+    /// Shorter components are recycled, as in unbalanced `POSIXlt`s.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        if !robj.inherits("POSIXlt") {
+            return Err(Error::Other(format!("expected a POSIXlt, got {:?}", robj)));
+        }
+        let list = List::try_from(robj)?;
+        let components = COMPONENTS
+            .iter()
+            .map(|name| component(&list, name))
+            .collect::<Result<Vec<_>>>()?;
+        let len = components.iter().map(Vec::len).max().unwrap_or(0);
+        if components.iter().any(Vec::is_empty) {
+            return Ok(vec![None; len]);
+        }
+        let tzone = robj
+            .get_attrib("tzone")
+            .and_then(|tzone| tzone.as_str_iter()?.next().map(str::to_string))
+            .unwrap_or_default();
+
+        Ok((0..len)
+            .map(|i| {
+                let value = |c: usize| components[c][i % components[c].len()];
+                let [sec, min, hour, mday, mon, year, wday, yday] =
+                    [0, 1, 2, 3, 4, 5, 6, 7].map(value);
+                Some(PosixLt {
+                    year: year? as i32 + 1900,
+                    month: mon? as u32 + 1,
+                    day: mday? as u32,
+                    hour: hour? as u32,
+                    minute: min? as u32,
+                    second: sec?,
+                    weekday: wday? as u32,
+                    ordinal: yday? as u32 + 1,
+                    is_dst: match value(8) {
+                        Some(isdst) if isdst >= 0. => Some(isdst > 0.),
+                        _ => None,
+                    },
+                    tzone: tzone.clone(),
+                })
+            })
+            .collect())
+    }
+}
+
+impl TryFrom<&Robj> for Vec<PosixLt> {
+    type Error = Error;
+
+    /// Convert a `POSIXlt` without `NA` times.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        <Vec<Option<PosixLt>>>::try_from(robj)?
+            .into_iter()
+            .map(|time| time.ok_or_else(|| Error::MustNotBeNA(robj.clone())))
+            .collect()
+    }
+}
+
+impl TryFrom<&Robj> for PosixLt {
+    type Error = Error;
+
+    /// Convert a `POSIXlt` of length one.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        let mut times = <Vec<PosixLt>>::try_from(robj)?;
+        match times.len() {
+            0 => Err(Error::ExpectedNonZeroLength(robj.clone())),
+            1 => Ok(times.remove(0)),
+            _ => Err(Error::ExpectedScalar(robj.clone())),
+        }
+    }
+}
+
+impl TryFrom<Robj> for Vec<Option<PosixLt>> {
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl TryFrom<Robj> for Vec<PosixLt> {
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl TryFrom<Robj> for PosixLt {
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl From<Vec<Option<PosixLt>>> for Robj {
+    /// Make a `POSIXlt`, with `NA` for `None`. The time zone is that of
+    /// the first time, as all the elements of a `POSIXlt` share one.
+    fn from(times: Vec<Option<PosixLt>>) -> Self {
+        let tzone = times
+            .iter()
+            .flatten()
+            .next()
+            .map(|time| time.tzone.clone())
+            .unwrap_or_default();
+        let int = |f: fn(&PosixLt) -> i32| -> Robj {
+            times.iter().map(|time| time.as_ref().map(f)).collect_robj()
+        };
+        let values = [
+            times
+                .iter()
+                .map(|time| time.as_ref().map(|time| time.second))
+                .collect_robj(),
+            int(|time| time.minute as i32),
+            int(|time| time.hour as i32),
+            int(|time| time.day as i32),
+            int(|time| time.month as i32 - 1),
+            int(|time| time.year - 1900),
+            int(|time| time.weekday as i32),
+            int(|time| time.ordinal as i32 - 1),
+            times
+                .iter()
+                .map(|time| match time {
+                    Some(time) => Some(time.is_dst.map_or(-1, i32::from)),
+                    None => Some(-1),
+                })
+                .collect_robj(),
+        ];
+        let list = List::from_names_and_values(COMPONENTS, values)
+            .expect("Internal error: failed to make POSIXlt components");
+        RobjBuilder::new(list)
+            .class(["POSIXlt", "POSIXt"])
+            .attr("tzone", tzone)
+            .build()
+            .expect("Internal error: failed to set POSIXlt attributes")
+    }
+}
+
+impl From<Vec<PosixLt>> for Robj {
+    /// Make a `POSIXlt`, with the time zone of the first time.
+    fn from(times: Vec<PosixLt>) -> Self {
+        times.into_iter().map(Some).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<PosixLt> for Robj {
+    /// Make a `POSIXlt` of length one.
+    fn from(time: PosixLt) -> Self {
+        vec![time].into()
+    }
+}
@@ -40,10 +40,13 @@ impl Promise {
 
     #[cfg(feature = "non-api")]
     /// Get the environment for the execution from the promise.
-    pub fn environment(&self) -> Environment {
+    ///
+    /// R drops the environment once the promise has been forced,
+    /// in which case this returns `None`.
+    pub fn environment(&self) -> Option<Environment> {
         unsafe {
             let sexp = self.robj.get();
-            Robj::from_sexp(PRENV(sexp)).try_into().unwrap()
+            Robj::from_sexp(PRENV(sexp)).try_into().ok()
         }
     }
 
@@ -65,8 +68,44 @@ impl Promise {
         }
     }
 
+    #[cfg(feature = "non-api")]
+    /// Return true if the promise has been evaluated.
+    pub fn is_forced(&self) -> bool {
+        !self.value().is_unbound_value()
+    }
+
+    #[cfg(feature = "non-api")]
+    /// Evaluate the promise, if it has not been evaluated yet, and return its value.
+    ///
+    /// As with `force()` in R, the code is only evaluated once and the value
+    /// is stored in the promise.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let env = Environment::new_with_parent(global_env());
+    ///     let promise = Promise::from_parts(lang!("+", 1, 2), env.clone())?;
+    ///     assert!(!promise.is_forced());
+    ///     assert_eq!(promise.code(), lang!("+", 1, 2));
+    ///     assert_eq!(promise.environment(), Some(env));
+    ///
+    ///     assert_eq!(promise.force()?, r!(3));
+    ///     assert!(promise.is_forced());
+    ///     assert_eq!(promise.value(), r!(3));
+    ///     assert_eq!(promise.environment(), None);
+    /// }
+    /// ```
+    pub fn force(&self) -> Result<Robj> {
+        if self.is_forced() {
+            Ok(self.value())
+        } else {
+            self.robj.eval()
+        }
+    }
+
     #[cfg(feature = "non-api")]
     /// If this promise has not been evaluated, evaluate it, otherwise return the value.
+    ///
+    /// Same as [`Promise::force`].
     /// ```
     /// use extendr_api::prelude::*;
     /// test! {
@@ -76,12 +115,7 @@ impl Promise {
     /// }
     /// ```
     pub fn eval(&self) -> Result<Robj> {
-        assert!(self.is_promise());
-        if !self.value().is_unbound_value() {
-            Ok(self.value())
-        } else {
-            self.robj.eval()
-        }
+        self.force()
     }
 }
 
@@ -36,6 +36,17 @@ pub(crate) unsafe fn charsxp_to_str(charsxp: SEXP) -> Option<&'static str> {
     }
 }
 
+/// Returns the string of a `CHARSXP` in UTF-8, translated from its declared
+/// encoding, e.g. latin1 or the native encoding on Windows. `None` for `NA`.
+pub(crate) unsafe fn charsxp_to_utf8(charsxp: SEXP) -> Option<String> {
+    assert_eq!(TYPEOF(charsxp), SEXPTYPE::CHARSXP);
+    if charsxp == R_NaString {
+        return None;
+    }
+    let utf8 = single_threaded(|| std::ffi::CStr::from_ptr(Rf_translateCharUTF8(charsxp)));
+    Some(utf8.to_string_lossy().into_owned())
+}
+
 impl Rstr {
     /// Make a character object from a string.
     pub fn from_string(val: &str) -> Self {
@@ -49,6 +60,41 @@ impl Rstr {
     pub fn as_str(&self) -> &str {
         self.into()
     }
+
+    /// Get the string as a NUL-terminated UTF-16 wide string, as used by
+    /// Windows APIs such as `CreateFileW`. Returns `None` if the string is NA.
+    ///
+    /// Unlike `as_str()`, this translates strings that are not in UTF-8.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let latin1 = R!(r#"iconv("caf\u00e9", "UTF-8", "latin1")"#)?;
+    ///     let rstr = Strings::try_from(latin1)?.elt(0);
+    ///     assert_eq!(rstr.to_wide().unwrap(), [99, 97, 102, 233, 0]);
+    ///     assert_eq!(Rstr::na().to_wide(), None);
+    /// }
+    /// ```
+    pub fn to_wide(&self) -> Option<Vec<u16>> {
+        let utf8 = unsafe { charsxp_to_utf8(self.robj.get())? };
+        Some(utf8.encode_utf16().chain(std::iter::once(0)).collect())
+    }
+
+    /// Make a character object from a UTF-16 wide string, up to the first NUL
+    /// if there is one. Unpaired surrogates are replaced by U+FFFD.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let wide: Vec<u16> = "caf\u{e9}\0ignored".encode_utf16().collect();
+    ///     assert_eq!(Rstr::from_wide(&wide), "caf\u{e9}");
+    /// }
+    /// ```
+    pub fn from_wide(wide: &[u16]) -> Self {
+        let end = wide
+            .iter()
+            .position(|&unit| unit == 0)
+            .unwrap_or(wide.len());
+        Rstr::from_string(&String::from_utf16_lossy(&wide[..end]))
+    }
 }
 
 impl AsRef<str> for Rstr {
@@ -0,0 +1,318 @@
+use super::*;
+
+/// Whether a [`SparseMatrix`] is stored by column or by row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparseLayout {
+    /// Compressed sparse column, as in the Matrix package's `dgCMatrix`.
+    Csc,
+    /// Compressed sparse row, as in the Matrix package's `dgRMatrix`.
+    Csr,
+}
+
+/// A sparse matrix of doubles, converted from and to the `dgCMatrix` and
+/// `dgRMatrix` classes of the Matrix package.
+///
+/// The non-zero entries are grouped by column for [`SparseLayout::Csc`] and
+/// by row for [`SparseLayout::Csr`]. The entries of group `k` are at
+/// `indptr[k]..indptr[k + 1]` in `indices` and `values`, where `indices`
+/// holds their rows (or columns), counted from zero and increasing.
+/// This is the layout used by Matrix, and by crates such as `sprs`.
+///
+/// Dimnames are not kept.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     // 1 0 2
+///     // 0 0 3
+///     let indptr = vec![0, 1, 1, 3];
+///     let m = SparseMatrix::new(2, 3, SparseLayout::Csc, indptr, vec![0, 0, 1], vec![1., 2., 3.])?;
+///     assert_eq!(m.get(1, 2), 3.);
+///     assert_eq!(m.get(1, 0), 0.);
+///     let bad_indptr = vec![0, 5, 2];
+///     assert!(SparseMatrix::new(1, 2, SparseLayout::Csc, bad_indptr, vec![0, 0], vec![1., 1.]).is_err());
+///
+///     let csr = m.to_csr();
+///     assert_eq!(csr.indptr, vec![0, 2, 3]);
+///     assert_eq!(csr.indices, vec![0, 2, 2]);
+///     assert_eq!(csr.to_csc(), m);
+///
+///     if R!("requireNamespace('Matrix', quietly = TRUE)")?.as_bool() == Some(true) {
+///         let robj = Robj::from(m.clone());
+///         assert!(robj.inherits("dgCMatrix"));
+///         assert_eq!(R!("as.matrix({{&robj}})")?, R!("matrix(c(1, 0, 0, 0, 2, 3), 2)")?);
+///         assert_eq!(SparseMatrix::try_from(&robj)?, m);
+///         let rows = R!("as({{robj}}, 'RsparseMatrix')")?;
+///         assert_eq!(SparseMatrix::try_from(rows)?, csr);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    pub nrow: usize,
+    pub ncol: usize,
+    pub layout: SparseLayout,
+    /// Where the entries of each column (or row) start, with the number of
+    /// entries at the end.
+    pub indptr: Vec<usize>,
+    /// The row (or column) of each entry.
+    pub indices: Vec<usize>,
+    /// The value of each entry.
+    pub values: Vec<f64>,
+}
+
+impl SparseMatrix {
+    /// A sparse matrix from its compressed parts, which are checked to be
+    /// consistent with each other and with the dimensions.
+    pub fn new(
+        nrow: usize,
+        ncol: usize,
+        layout: SparseLayout,
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<f64>,
+    ) -> Result<Self> {
+        let matrix = Self {
+            nrow,
+            ncol,
+            layout,
+            indptr,
+            indices,
+            values,
+        };
+        matrix.check()?;
+        Ok(matrix)
+    }
+
+    /// An empty matrix of the given size.
+    pub fn zeros(nrow: usize, ncol: usize, layout: SparseLayout) -> Self {
+        let mut matrix = Self {
+            nrow,
+            ncol,
+            layout,
+            indptr: Vec::new(),
+            indices: Vec::new(),
+            values: Vec::new(),
+        };
+        matrix.indptr = vec![0; matrix.major_len() + 1];
+        matrix
+    }
+
+    // The number of groups and the range of the indices within a group.
+    fn major_len(&self) -> usize {
+        match self.layout {
+            SparseLayout::Csc => self.ncol,
+            SparseLayout::Csr => self.nrow,
+        }
+    }
+
+    fn minor_len(&self) -> usize {
+        match self.layout {
+            SparseLayout::Csc => self.nrow,
+            SparseLayout::Csr => self.ncol,
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(Error::Other(format!("invalid sparse matrix: {}", msg)));
+        if self.indptr.len() != self.major_len() + 1 {
+            return Err(Error::ExpectedLength(self.major_len() + 1));
+        }
+        if self.indices.len() != self.values.len() {
+            return invalid("indices and values differ in length");
+        }
+        if self.indptr[0] != 0 || self.indptr[self.major_len()] != self.indices.len() {
+            return invalid("indptr must go from zero to the number of entries");
+        }
+        // Check all of indptr before slicing with it.
+        if self.indptr.windows(2).any(|group| group[0] > group[1]) {
+            return invalid("indptr must not decrease");
+        }
+        for group in self.indptr.windows(2) {
+            let indices = &self.indices[group[0]..group[1]];
+            if indices.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return invalid("indices must increase within each column or row");
+            }
+            if indices.last().is_some_and(|&last| last >= self.minor_len()) {
+                return invalid("index out of range");
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The value at `row` and `col`, which is zero if it is not stored.
+    ///
+    /// Panics if `row` or `col` is out of range.
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        assert!(row < self.nrow && col < self.ncol, "index out of range");
+        let (major, minor) = match self.layout {
+            SparseLayout::Csc => (col, row),
+            SparseLayout::Csr => (row, col),
+        };
+        let range = self.indptr[major]..self.indptr[major + 1];
+        match self.indices[range.clone()].binary_search(&minor) {
+            Ok(i) => self.values[range.start + i],
+            Err(_) => 0.,
+        }
+    }
+
+    /// The same matrix stored by column.
+    pub fn to_csc(&self) -> Self {
+        match self.layout {
+            SparseLayout::Csc => self.clone(),
+            SparseLayout::Csr => self.relayout(SparseLayout::Csc),
+        }
+    }
+
+    /// The same matrix stored by row.
+    pub fn to_csr(&self) -> Self {
+        match self.layout {
+            SparseLayout::Csr => self.clone(),
+            SparseLayout::Csc => self.relayout(SparseLayout::Csr),
+        }
+    }
+
+    // Regroup the entries by their indices. Visiting the groups in order
+    // keeps the new indices increasing.
+    fn relayout(&self, layout: SparseLayout) -> Self {
+        let mut indptr = vec![0; self.minor_len() + 1];
+        for &minor in &self.indices {
+            indptr[minor + 1] += 1;
+        }
+        for k in 0..self.minor_len() {
+            indptr[k + 1] += indptr[k];
+        }
+        let mut next = indptr.clone();
+        let mut indices = vec![0; self.nnz()];
+        let mut values = vec![0.; self.nnz()];
+        for major in 0..self.major_len() {
+            for entry in self.indptr[major]..self.indptr[major + 1] {
+                let slot = &mut next[self.indices[entry]];
+                indices[*slot] = major;
+                values[*slot] = self.values[entry];
+                *slot += 1;
+            }
+        }
+        Self {
+            nrow: self.nrow,
+            ncol: self.ncol,
+            layout,
+            indptr,
+            indices,
+            values,
+        }
+    }
+
+    /// Make a `dgCMatrix` or `dgRMatrix`. This needs the Matrix package.
+    pub fn to_robj(&self) -> Result<Robj> {
+        let to_int = |values: &[usize]| -> Result<Robj> {
+            values
+                .iter()
+                .map(|&i| i32::try_from(i))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map(Robj::from)
+                .map_err(|_| Error::Other("sparse matrix is too large for R".into()))
+        };
+        let (class, index) = match self.layout {
+            SparseLayout::Csc => ("dgCMatrix", "i"),
+            SparseLayout::Csr => ("dgRMatrix", "j"),
+        };
+        let code = format!(
+            "methods::new(methods::getClass(param.0, where = asNamespace('Matrix')), \
+             p = param.1, {} = param.2, x = param.3, Dim = param.4)",
+            index
+        );
+        eval_string_with_params(
+            &code,
+            &[
+                &r!(class),
+                &to_int(&self.indptr)?,
+                &to_int(&self.indices)?,
+                &Robj::from(&self.values),
+                &to_int(&[self.nrow, self.ncol])?,
+            ],
+        )
+    }
+}
+
+// A slot of a Matrix object as indices or sizes.
+fn usize_slot(s4: &S4, name: &str) -> Result<Vec<usize>> {
+    let slot = s4
+        .get_slot(name)
+        .ok_or_else(|| Error::Other(format!("missing slot `{}`", name)))?;
+    let values = slot
+        .as_integer_slice()
+        .ok_or_else(|| Error::ExpectedInteger(slot.clone()))?;
+    values
+        .iter()
+        .map(|&i| {
+            usize::try_from(i)
+                .map_err(|_| Error::Other(format!("negative value in slot `{}`", name)))
+        })
+        .collect()
+}
+
+impl TryFrom<&Robj> for SparseMatrix {
+    type Error = Error;
+
+    /// Convert a `dgCMatrix` or `dgRMatrix`. Other sparse matrices can be
+    /// converted in R first with `as(x, "generalMatrix")`,
+    /// `as(x, "dMatrix")` and `as(x, "CsparseMatrix")`.
+    fn try_from(robj: &Robj) -> Result<Self> {
+        let (layout, index) = if robj.inherits("dgCMatrix") {
+            (SparseLayout::Csc, "i")
+        } else if robj.inherits("dgRMatrix") {
+            (SparseLayout::Csr, "j")
+        } else {
+            return Err(Error::Other(format!(
+                "expected a dgCMatrix or dgRMatrix, got {:?}",
+                robj.class().map(|class| class.collect::<Vec<_>>())
+            )));
+        };
+        let s4 = S4::try_from(robj)?;
+        let dim = usize_slot(&s4, "Dim")?;
+        if dim.len() != 2 {
+            return Err(Error::ExpectedLength(2));
+        }
+        let x = s4
+            .get_slot("x")
+            .ok_or_else(|| Error::Other("missing slot `x`".into()))?;
+        let values = x
+            .as_real_slice()
+            .ok_or_else(|| Error::ExpectedReal(x.clone()))?
+            .to_vec();
+        Self::new(
+            dim[0],
+            dim[1],
+            layout,
+            usize_slot(&s4, "p")?,
+            usize_slot(&s4, index)?,
+            values,
+        )
+    }
+}
+
+impl TryFrom<Robj> for SparseMatrix {
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        Self::try_from(&robj)
+    }
+}
+
+impl From<SparseMatrix> for Robj {
+    /// Make a `dgCMatrix` or `dgRMatrix`.
+    ///
+    /// Panics if the Matrix package is not installed. Use
+    /// [`SparseMatrix::to_robj`] to handle this as an error.
+    fn from(matrix: SparseMatrix) -> Self {
+        match matrix.to_robj() {
+            Ok(robj) => robj,
+            Err(e) => panic!("failed to make a sparse matrix: {}", e),
+        }
+    }
+}
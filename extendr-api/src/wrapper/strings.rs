@@ -58,6 +58,57 @@ impl Strings {
         })
     }
 
+    /// Make a string vector from values with many repeats, such as the labels
+    /// of categorical data. Up to 4096 distinct strings are cached while the
+    /// vector is made, so that each is converted to an R string only once.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// test! {
+    ///     let labels = (0..1000).map(|i| if i % 3 == 0 { "low" } else { "high" });
+    ///     let strings = Strings::from_repeated_values(labels);
+    ///     assert_eq!(strings.len(), 1000);
+    ///     assert_eq!(strings.elt(0), "low");
+    ///     assert_eq!(strings.elt(1), "high");
+    ///
+    ///     // NA is not confused with the string "NA".
+    ///     let strings = Strings::from_repeated_values([<&str>::na(), "NA", <&str>::na()]);
+    ///     assert_eq!(r!(strings), R!("c(NA, 'NA', NA)")?);
+    /// }
+    /// ```
+    pub fn from_repeated_values<V>(values: V) -> Self
+    where
+        V: IntoIterator,
+        V::IntoIter: ExactSizeIterator,
+        V::Item: AsRef<str>,
+    {
+        const MAX_CACHED: usize = 4096;
+        single_threaded(|| unsafe {
+            let values = values.into_iter();
+            let maxlen = values.len();
+            let mut robj = Robj::alloc_vector(SEXPTYPE::STRSXP, maxlen);
+            let sexp = robj.get_mut();
+            // The cached CHARSXPs are kept alive by being elements of the vector.
+            let mut cache: std::collections::HashMap<String, SEXP> =
+                std::collections::HashMap::new();
+            for (i, v) in values.take(maxlen).enumerate() {
+                let v = v.as_ref();
+                let ch = if v.is_na() {
+                    R_NaString
+                } else if let Some(&ch) = cache.get(v) {
+                    ch
+                } else {
+                    let ch = str_to_character(v);
+                    if cache.len() < MAX_CACHED {
+                        cache.insert(v.to_string(), ch);
+                    }
+                    ch
+                };
+                SET_STRING_ELT(sexp, i as R_xlen_t, ch);
+            }
+            Self { robj }
+        })
+    }
+
     /// This is a relatively expensive operation, so use a variable if using this in a loop.
     pub fn as_slice<'a>(&self) -> &'a [Rstr] {
         unsafe {
@@ -131,6 +182,16 @@ where
     }
 }
 
+impl<'a> IntoIterator for &'a Strings {
+    type Item = &'a Rstr;
+    type IntoIter = std::slice::Iter<'a, Rstr>;
+
+    /// Iterate over the elements, like `iter()`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
 impl Deref for Strings {
     type Target = [Rstr];
 
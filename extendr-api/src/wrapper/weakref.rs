@@ -0,0 +1,150 @@
+use super::*;
+use std::cell::{Cell, RefCell};
+
+type Finalizer = Box<dyn FnOnce(Robj)>;
+
+thread_local! {
+    // Rust finalizers of weak references, by an id given to each weak reference.
+    // R calls C finalizers with the key only, which may have several weak
+    // references, so the finalizer is an R closure that passes on the id.
+    static FINALIZERS: RefCell<HashMap<u64, Finalizer>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u64> = Cell::new(0);
+}
+
+/// Wrapper for weak references (WEAKREFSXP).
+///
+/// A weak reference keeps its value alive only as long as its key,
+/// which must be an environment or an external pointer, is alive.
+/// It does not keep the key alive, which makes it suitable for caches.
+///
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let key = r!(Environment::new_with_parent(global_env()));
+///     let weak = WeakRef::new(&key, r!("cached"));
+///     assert_eq!(weak.key(), Some(key));
+///     assert_eq!(weak.value(), Some(r!("cached")));
+/// }
+/// ```
+#[derive(PartialEq, Clone)]
+pub struct WeakRef {
+    pub(crate) robj: Robj,
+}
+
+impl WeakRef {
+    /// Make a weak reference from `key` to `value`.
+    pub fn new(key: &Robj, value: Robj) -> Self {
+        single_threaded(|| unsafe {
+            let sexp = R_MakeWeakRef(key.get(), value.get(), R_NilValue, Rboolean::FALSE);
+            WeakRef {
+                robj: Robj::from_sexp(sexp),
+            }
+        })
+    }
+
+    /// Make a weak reference from `key` to `value` that calls `finalizer`
+    /// with the key when the key is garbage collected, or when R exits
+    /// if `on_exit` is true.
+    /// ```
+    /// use extendr_api::prelude::*;
+    /// use std::{cell::Cell, cell::RefCell, rc::Rc};
+    /// test! {
+    ///     let finalized = Rc::new(Cell::new(false));
+    ///     let flag = finalized.clone();
+    ///     let key = r!(Environment::new_with_parent(global_env()));
+    ///     let weak = WeakRef::with_finalizer(&key, r!(1), move |_key| flag.set(true), false);
+    ///     weak.run_finalizer();
+    ///     assert!(finalized.get());
+    ///     assert_eq!(weak.value(), None);
+    ///
+    ///     // Each weak reference runs its own finalizer, whatever the order.
+    ///     let log = Rc::new(RefCell::new(Vec::new()));
+    ///     let (first, second) = (log.clone(), log.clone());
+    ///     let key = r!(Environment::new_with_parent(global_env()));
+    ///     let a = WeakRef::with_finalizer(&key, r!(1), move |_| first.borrow_mut().push("a"), false);
+    ///     let b = WeakRef::with_finalizer(&key, r!(2), move |_| second.borrow_mut().push("b"), false);
+    ///     b.run_finalizer();
+    ///     a.run_finalizer();
+    ///     assert_eq!(*log.borrow(), vec!["b", "a"]);
+    /// }
+    /// ```
+    pub fn with_finalizer<F>(key: &Robj, value: Robj, finalizer: F, on_exit: bool) -> Self
+    where
+        F: FnOnce(Robj) + 'static,
+    {
+        let id = NEXT_ID.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        });
+        FINALIZERS.with(|finalizers| finalizers.borrow_mut().insert(id, Box::new(finalizer)));
+        let on_exit = if on_exit {
+            Rboolean::TRUE
+        } else {
+            Rboolean::FALSE
+        };
+        single_threaded(|| unsafe {
+            // `.Call()` accepts an external pointer tagged "native symbol"
+            // in place of the name of a registered routine.
+            let routine: unsafe extern "C" fn(SEXP, SEXP) -> SEXP = run_finalizer;
+            let tag = Symbol::from_string("native symbol");
+            let routine = Robj::from_sexp(R_MakeExternalPtrFn(
+                std::mem::transmute(routine),
+                tag.get(),
+                R_NilValue,
+            ));
+            let finalizer = eval_string_with_params(
+                "function(key) .Call(param.0, param.1, key)",
+                &[&routine, &Robj::from(id as f64)],
+            )
+            .expect("Internal error: failed to make the weak reference finalizer");
+            let sexp = R_MakeWeakRef(key.get(), value.get(), finalizer.get(), on_exit);
+            WeakRef {
+                robj: Robj::from_sexp(sexp),
+            }
+        })
+    }
+
+    /// Get the key, or `None` if it has been garbage collected.
+    pub fn key(&self) -> Option<Robj> {
+        let key = unsafe { Robj::from_sexp(R_WeakRefKey(self.robj.get())) };
+        (!key.is_null()).then_some(key)
+    }
+
+    /// Get the value, or `None` if the key has been garbage collected.
+    pub fn value(&self) -> Option<Robj> {
+        self.key()?;
+        unsafe { Some(Robj::from_sexp(R_WeakRefValue(self.robj.get()))) }
+    }
+
+    /// Run the finalizer now, if it has not already run, and clear the reference.
+    pub fn run_finalizer(&self) {
+        single_threaded(|| unsafe { R_RunWeakRefFinalizer(self.robj.get()) })
+    }
+}
+
+// Called by the R closure finalizer with the id of the weak reference.
+unsafe extern "C" fn run_finalizer(id: SEXP, key: SEXP) -> SEXP {
+    let id = Robj::from_sexp(id).as_real().map(|id| id as u64);
+    let finalizer = id.and_then(|id| {
+        FINALIZERS
+            .try_with(|finalizers| finalizers.borrow_mut().remove(&id))
+            .ok()
+            .flatten()
+    });
+    if let Some(finalizer) = finalizer {
+        // Panics must not unwind into R.
+        let key = Robj::from_sexp(key);
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| finalizer(key)));
+    }
+    R_NilValue
+}
+
+impl std::fmt::Debug for WeakRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakRef")
+            .field("key", &self.key())
+            .field("value", &self.value())
+            .finish()
+    }
+}
@@ -0,0 +1,179 @@
+use super::*;
+
+/// Attributes that describe the shape of an object rather than decorate it.
+/// [`WithAttributes`] leaves these to the conversion of the value, as they
+/// are not valid once the length of the value changes.
+pub const STRUCTURAL_ATTRIBUTES: [&str; 5] = ["names", "dim", "dimnames", "row.names", "tsp"];
+
+// The names and values of the attributes of an object, in order.
+pub(crate) fn attribute_pairs(robj: &Robj) -> Vec<(String, Robj)> {
+    eval_string_with_params("attributes(param.0)", &[robj])
+        .ok()
+        .and_then(|attributes| List::try_from(attributes).ok())
+        .map(|list| {
+            list.iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A value converted from an R object, together with the attributes that the
+/// conversion would drop, such as `units`, `label`, `labels` or `class`.
+///
+/// The attributes are set again when converting back to an R object with
+/// `Robj::try_from()`, so that functions taking `WithAttributes<T>` can
+/// return them. [`STRUCTURAL_ATTRIBUTES`], such as `names`, are not kept.
+/// The `levels` and `factor` class of a factor are only set again on
+/// integer vectors, as other vectors can not be factors.
+/// ```
+/// use extendr_api::prelude::*;
+/// test! {
+///     let robj = R!("structure(c(a = 1.5, b = 2), units = 'cm', label = 'Height')")?;
+///     let height: WithAttributes<Vec<f64>> = robj.keep_attributes()?;
+///     assert_eq!(height.value, vec![1.5, 2.]);
+///     assert_eq!(height.get_attrib("units"), Some(&r!("cm")));
+///
+///     let doubled = height.map(|values| values.iter().map(|x| x * 2.).collect::<Vec<_>>());
+///     assert_eq!(
+///         Robj::try_from(doubled)?,
+///         R!("structure(c(3, 4), units = 'cm', label = 'Height')")?
+///     );
+///
+///     let sizes = R!("factor(c('s', 'm'), levels = c('s', 'm', 'l'))")?;
+///     let sizes: WithAttributes<Vec<i32>> = sizes.keep_attributes()?;
+///     let halves = sizes.map(|codes| codes.iter().map(|&c| c as f64 / 2.).collect::<Vec<_>>());
+///     assert_eq!(Robj::try_from(halves)?, r!([0.5, 1.]));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithAttributes<T> {
+    pub value: T,
+    /// The names and values of the attributes, in order.
+    pub attributes: Vec<(String, Robj)>,
+}
+
+impl<T> WithAttributes<T> {
+    /// A value without attributes.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Transform the value, keeping the attributes.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> WithAttributes<U> {
+        WithAttributes {
+            value: f(self.value),
+            attributes: self.attributes,
+        }
+    }
+
+    /// The value of an attribute, if it was kept.
+    pub fn get_attrib(&self, name: &str) -> Option<&Robj> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Set or replace an attribute.
+    pub fn set_attrib<V: Into<Robj>>(&mut self, name: &str, value: V) -> &mut Self {
+        let value = value.into();
+        match self.attributes.iter_mut().find(|(n, _)| n == name) {
+            Some((_, old)) => *old = value,
+            None => self.attributes.push((name.to_string(), value)),
+        }
+        self
+    }
+}
+
+fn decorating_attributes(robj: &Robj) -> Vec<(String, Robj)> {
+    attribute_pairs(robj)
+        .into_iter()
+        .filter(|(name, _)| !STRUCTURAL_ATTRIBUTES.contains(&name.as_str()))
+        .collect()
+}
+
+impl Robj {
+    /// Convert to `T`, keeping the attributes that the conversion drops.
+    /// See [`WithAttributes`].
+    pub fn keep_attributes<'a, T>(&'a self) -> Result<WithAttributes<T>>
+    where
+        T: TryFrom<&'a Robj, Error = Error>,
+    {
+        WithAttributes::try_from(self)
+    }
+}
+
+impl<'a, T> TryFrom<&'a Robj> for WithAttributes<T>
+where
+    T: TryFrom<&'a Robj, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(robj: &'a Robj) -> Result<Self> {
+        Ok(Self {
+            value: robj.try_into()?,
+            attributes: decorating_attributes(robj),
+        })
+    }
+}
+
+impl<T> TryFrom<Robj> for WithAttributes<T>
+where
+    T: TryFrom<Robj, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(robj: Robj) -> Result<Self> {
+        let attributes = decorating_attributes(&robj);
+        Ok(Self {
+            value: robj.try_into()?,
+            attributes,
+        })
+    }
+}
+
+impl<T> TryFrom<WithAttributes<T>> for Robj
+where
+    T: Into<Robj>,
+{
+    type Error = Error;
+
+    /// Convert the value and set the attributes on the result.
+    fn try_from(value: WithAttributes<T>) -> Result<Self> {
+        let mut robj: Robj = value.value.into();
+        // Only an integer vector can be a factor.
+        let drop_factor = robj.sexptype() != SEXPTYPE::INTSXP
+            && value.attributes.iter().any(|(name, class)| {
+                name == "class"
+                    && class
+                        .as_str_iter()
+                        .is_some_and(|mut classes| classes.any(|class| class == "factor"))
+            });
+        for (name, value) in value.attributes {
+            match name.as_str() {
+                "levels" if drop_factor => {}
+                "class" if drop_factor => {
+                    let class = value
+                        .as_str_iter()
+                        .map(|class| {
+                            class
+                                .filter(|&class| class != "factor" && class != "ordered")
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    if !class.is_empty() {
+                        robj.set_class(class)?;
+                    }
+                }
+                _ => {
+                    robj.set_attrib(name.as_str(), value)?;
+                }
+            }
+        }
+        Ok(robj)
+    }
+}
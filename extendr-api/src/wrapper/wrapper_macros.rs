@@ -1,5 +1,4 @@
 use super::*;
-use crate as extendr_api;
 
 pub(crate) fn make_symbol(name: &str) -> SEXP {
     let name = CString::new(name).unwrap();
@@ -155,6 +154,13 @@ make_conversions!(
 
 make_conversions!(Promise, ExpectedPromise, is_promise, "Not a Promise object");
 
+make_conversions!(
+    WeakRef,
+    ExpectedWeakRef,
+    is_weak_ref,
+    "Not a WeakRef object"
+);
+
 make_conversions!(Altrep, ExpectedAltrep, is_altrep, "Not an Altrep type");
 
 make_conversions!(S4, ExpectedS4, is_s4, "Not a S4 type");
@@ -336,7 +342,6 @@ where
     R: Clone,
 {
     fn sym_pair(self) -> (Option<Robj>, Robj) {
-        use crate as extendr_api;
         let val = self.0.as_ref();
         let nm = if val.is_empty() {
             None
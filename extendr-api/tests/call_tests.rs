@@ -27,3 +27,58 @@ fn special_test() {
         assert_eq!(add.call(pairlist!(1, 2))?, r!(3));
     }
 }
+
+#[test]
+fn call_args_test() {
+    test! {
+        // Optional arguments are left out when they are None.
+        let digits: Option<i32> = None;
+        assert_eq!(call!("round", 1.25, digits? = digits)?, r!(1.0));
+        assert_eq!(call!("round", 1.25, digits? = Some(1))?, r!(1.2));
+
+        // Lists, pairlists and vectors are spliced with their names.
+        let args = list!(1.25, digits = 1);
+        assert_eq!(call!("round", !!args)?, r!(1.2));
+        assert_eq!(call!("list", a = 1, !!pairlist!(b = 2), !!r!([3, 4]), !!())?, list!(a = 1, b = 2, 3, 4));
+
+        // The dots of an environment are forwarded unevaluated.
+        let env: Environment = R!("(function(...) environment())(x = stop('forced'), 2)")?.try_into()?;
+        let function = R!("function(x, y) y")?.as_function().unwrap();
+        assert_eq!(function.call_with(vec![CallArg::Dots(env)])?, r!(2));
+
+        // There are no dots outside of a function.
+        assert!(call!("list", ...).is_err());
+    }
+}
+
+// Reports the call stack as seen from Rust, for `call_stack_from_r`.
+#[extendr]
+fn stack_info() -> Result<Robj> {
+    let calls = sys_calls()?
+        .iter()
+        .map(|call| call.deparse())
+        .collect::<Result<Vec<String>>>()?;
+    let frames = sys_frames()?.len() as i32;
+    Ok(list!(calls = calls, frames = frames).into())
+}
+
+extendr_module! {
+    mod call_tests;
+    fn stack_info;
+}
+
+#[test]
+fn call_stack_from_r() {
+    test! {
+        // Register the module with the `DllInfo` of the test executable.
+        R_init_call_tests_extendr(unsafe { libR_sys::R_getEmbeddingDllInfo() });
+        let info = R!(r#"
+            inner <- function() .Call(getNativeSymbolInfo("wrap__stack_info", "(embedding)"))
+            outer <- function() inner()
+            outer()
+        "#)?;
+        let calls: Vec<String> = info.dollar("calls")?.as_str_iter().unwrap().map(String::from).collect();
+        assert!(calls.ends_with(&["outer()".to_string(), "inner()".to_string()]));
+        assert_eq!(info.dollar("frames")?, r!(calls.len() as i32));
+    }
+}
@@ -145,3 +145,16 @@ fn test_debug_vectors() {
         assert_eq!(format!("{:?}", r), "[42.0 - 42.0i, 0.0 + 0.0i, NA_COMPLEX]");
     }
 }
+
+#[test]
+fn test_display() {
+    test! {
+        assert_eq!(format!("{}", r!(1.5)), "[1] 1.5");
+        assert_eq!(format!("{}", r!("a")), "[1] \"a\"");
+        assert_eq!(format!("{}", sym!(x)), "x");
+        assert_eq!(format!("{:#?}", r!(1.5)), "[1] 1.5");
+        assert_eq!(format!("{:#?}", r!(list!(a = 1))), "$a\n[1] 1\n");
+        let long = format!("{}", r!(Integers::from_values(0..1000)));
+        assert_eq!(long.lines().count(), extendr_api::DISPLAY_MAX_LINES + 1);
+    }
+}
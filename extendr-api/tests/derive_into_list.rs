@@ -52,3 +52,39 @@ fn test_derive_list() {
         assert_eq!(&converted_r_borrow, &converted_r_owned);
     }
 }
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn test_derive_enum() {
+    use extendr_api::prelude::*;
+    use extendr_macros::{IntoRobj, TryFromRobj};
+
+    test! {
+        #[derive(TryFromRobj, IntoRobj, PartialEq, Debug)]
+        enum Limit {
+            Count(i32),
+            Name(String),
+            Unlimited,
+        }
+
+        // The variants are tried in order.
+        assert_eq!(Limit::try_from(r!(3))?, Limit::Count(3));
+        assert_eq!(Limit::try_from(&r!("all"))?, Limit::Name("all".into()));
+        assert_eq!(Limit::try_from(r!(NULL))?, Limit::Unlimited);
+        assert!(Limit::try_from(r!([1, 2])).is_err());
+
+        assert_eq!(Robj::from(Limit::Count(3)), r!(3));
+        assert_eq!(Robj::from(&Limit::Name("all".into())), r!("all"));
+        assert_eq!(Robj::from(Limit::Unlimited), r!(NULL));
+
+        #[derive(TryFromRobj, Debug)]
+        enum Callback {
+            Name(String),
+            Func(Function),
+        }
+
+        let callback = Callback::try_from(R!("function(x) x + 1")?)?;
+        assert!(matches!(callback, Callback::Func(_)));
+        assert!(matches!(Callback::try_from(r!("sum"))?, Callback::Name(_)));
+    }
+}
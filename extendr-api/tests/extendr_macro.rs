@@ -1,6 +1,7 @@
 use extendr_api::call;
 use extendr_api::extendr;
 use extendr_api::extendr_module;
+use extendr_api::Pairlist;
 use extendr_api::Rinternals;
 use extendr_api::Robj;
 use extendr_api::NA_INTEGER;
@@ -169,6 +170,78 @@ fn tests_with_unsuccessful_outcomes() {
     });
 }
 
+#[extendr(external)]
+fn test_external(args: Pairlist) -> i32 {
+    args.values().map(|x| x.as_integer().unwrap_or(0)).sum()
+}
+
+#[test]
+fn test_external_wrapper() {
+    unsafe {
+        test! {
+            // `.External` passes the call, headed by the routine name.
+            let call = Pairlist::from_pairs([("", r!("wrap__test_external")), ("", r!(1)), ("", r!(2)), ("", r!(3))]);
+            assert_eq!(Robj::from_sexp(wrap__test_external(call.get())), r!(6));
+
+            let call = Pairlist::from_pairs([("", r!("wrap__test_external"))]);
+            assert_eq!(Robj::from_sexp(wrap__test_external(call.get())), r!(0));
+
+            let mut funcs = Vec::new();
+            meta__test_external(&mut funcs);
+            assert!(funcs[0].external);
+        }
+    }
+}
+
+#[extendr]
+fn test_slice_sum(values: &[f64]) -> f64 {
+    values.iter().sum()
+}
+
+#[extendr]
+fn test_slice_double(values: &mut [f64]) -> f64 {
+    values.iter_mut().for_each(|x| *x *= 2.);
+    values.iter().sum()
+}
+
+#[extendr]
+fn test_array_product(point: &[i32; 2]) -> i32 {
+    point[0] * point[1]
+}
+
+#[test]
+fn test_borrowed_vector_args() {
+    use extendr_api::{global_env, sym};
+    unsafe {
+        test! {
+            assert_eq!(Robj::from_sexp(wrap__test_slice_sum(r!([1.5, 2.]).get())), r!(3.5));
+            assert_eq!(Robj::from_sexp(wrap__test_array_product(r!([3, 4]).get())), r!(12));
+
+            // A vector bound to a variable is copied before it is modified.
+            let x = r!([1., 2.]);
+            global_env().set_local(sym!(x), &x);
+            assert_eq!(Robj::from_sexp(wrap__test_slice_double(x.get())), r!(6.));
+            assert_eq!(x, r!([1., 2.]));
+        }
+    }
+}
+
+#[extendr]
+fn test_squares(n: i32) -> impl Iterator<Item = i32> {
+    (1..=n).map(|i| i * i)
+}
+
+#[test]
+fn test_iterator_return() {
+    use extendr_api::Length;
+    unsafe {
+        test! {
+            assert_eq!(Robj::from_sexp(wrap__test_squares(r!(3).get())), r!([1, 4, 9]));
+            assert_eq!(Robj::from_sexp(wrap__test_squares(r!(0).get())).len(), 0);
+        }
+    }
+}
+
 #[test]
 fn test_call_macro() {
     use extendr_api::Length;
@@ -205,6 +278,8 @@ fn test_metadata() {
         name: "val",
         arg_type: "Robj",
         default: Some("NULL"),
+        check: None,
+        validate: None,
     }];
 
     assert_eq!(
@@ -214,10 +289,14 @@ fn test_metadata() {
             rust_name: "test_metadata_1",
             mod_name: "test_metadata_1",
             r_name: "test_metadata_1",
+            symbol: "wrap__test_metadata_1",
             args,
             return_type: "i32",
             func_ptr: wrap__test_metadata_1 as *const u8,
             hidden: false,
+            external: false,
+            dispatch: None,
+            s4_method: None,
         }
     );
 }
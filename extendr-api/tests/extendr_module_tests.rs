@@ -39,3 +39,466 @@ mod adjacent_module {
         fn foo;
     }
 }
+
+mod restricted_symbols {
+    use super::*;
+    use extendr_api::prelude::*;
+
+    #[extendr]
+    fn restricted() {}
+
+    extendr_module! {
+        mod restricted_module;
+        use_dynamic_symbols = false;
+        force_symbols = true;
+        fn restricted;
+    }
+
+    #[test]
+    fn names_are_rejected() {
+        extendr_api::test! {
+            // Register the module with the `DllInfo` of the test executable.
+            R_init_restricted_module_extendr(unsafe { libR_sys::R_getEmbeddingDllInfo() });
+
+            // `force_symbols = true` rejects routines given by name...
+            assert!(R!(".Call('wrap__restricted', PACKAGE = '(embedding)')").is_err());
+            // ...but not the registered symbol.
+            assert_eq!(
+                R!(".Call(getNativeSymbolInfo('wrap__restricted', '(embedding)'))")?,
+                r!(NULL)
+            );
+
+            // `use_dynamic_symbols = false` rejects names that are not registered.
+            assert!(R!("getNativeSymbolInfo('R_init_restricted_module_extendr', '(embedding)')").is_err());
+        }
+    }
+}
+
+mod hooks {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static INITIALIZED: AtomicBool = AtomicBool::new(false);
+    static UNLOADED: AtomicBool = AtomicBool::new(false);
+
+    #[extendr]
+    fn hooked() {}
+
+    extendr_module! {
+        mod hooked_module;
+        fn hooked;
+
+        fn __init__() {
+            INITIALIZED.store(true, Ordering::SeqCst);
+        }
+
+        fn __unload__() {
+            UNLOADED.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn module_hooks_run() {
+        extendr_api::test! {
+            // `R_init_hooked_module_extendr` needs a `DllInfo` to register the
+            // functions, so the hooks it runs are called directly.
+            assert!(!INITIALIZED.load(Ordering::SeqCst));
+            run_hooked_module_init_hooks();
+            assert!(INITIALIZED.load(Ordering::SeqCst));
+
+            assert!(!UNLOADED.load(Ordering::SeqCst));
+            R_unload_hooked_module_extendr(std::ptr::null_mut());
+            assert!(UNLOADED.load(Ordering::SeqCst));
+        }
+    }
+}
+
+mod composed {
+    use super::*;
+    use std::sync::Mutex;
+
+    // The hooks that ran, in order.
+    static HOOKS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+    mod parsers {
+        use super::*;
+
+        pub mod csv {
+            use super::*;
+
+            #[extendr]
+            fn read_csv() {}
+
+            extendr_module! {
+                mod csv;
+                fn read_csv;
+
+                fn __init__() {
+                    super::super::HOOKS.lock().unwrap().push("init csv");
+                }
+
+                fn __unload__() {
+                    super::super::HOOKS.lock().unwrap().push("unload csv");
+                }
+            }
+        }
+
+        pub mod json {
+            use super::*;
+
+            #[extendr]
+            fn read_json() {}
+
+            extendr_module! {
+                mod json;
+                use mod super::csv;
+                fn read_json;
+
+                fn __init__() {
+                    super::super::HOOKS.lock().unwrap().push("init json");
+                }
+
+                fn __unload__() {
+                    super::super::HOOKS.lock().unwrap().push("unload json");
+                }
+            }
+        }
+    }
+
+    extendr_module! {
+        mod composed;
+        use mod parsers::csv;
+        use mod parsers::json;
+    }
+
+    #[test]
+    fn submodules_are_merged_once() {
+        extendr_api::test! {
+            let metadata = get_composed_metadata();
+            let count = |name: &str| metadata.functions.iter().filter(|f| f.r_name == name).count();
+            assert_eq!(count("read_csv"), 1);
+            assert_eq!(count("read_json"), 1);
+            assert_eq!(count("get_csv_metadata"), 1);
+        }
+    }
+
+    #[test]
+    fn same_named_items_are_reported() {
+        use extendr_api::metadata::{merge, Func, Impl, Metadata};
+
+        fn func(symbol: &'static str, func_ptr: *const u8) -> Func {
+            Func {
+                doc: "",
+                rust_name: "parse",
+                mod_name: "parse",
+                r_name: "parse",
+                symbol,
+                args: Vec::new(),
+                return_type: "()",
+                func_ptr,
+                hidden: false,
+                external: false,
+                dispatch: None,
+                s4_method: None,
+            }
+        }
+
+        let (a, b) = (0_u8, 0_u8);
+        let mut functions = vec![func("wrap__csv_parse", &a)];
+        let mut impls = Vec::new();
+        let module = |functions| Metadata {
+            name: "other",
+            functions,
+            impls: Vec::new(),
+        };
+
+        // The same function, e.g. from a module used twice, is not a conflict.
+        assert!(merge(
+            &mut functions,
+            &mut impls,
+            module(vec![func("wrap__csv_parse", &a)])
+        )
+        .is_ok());
+        let err = merge(
+            &mut functions,
+            &mut impls,
+            module(vec![func("wrap__json_parse", &b)]),
+        );
+        let message = err.unwrap_err().to_string();
+        assert!(message.contains("`parse` (`wrap__csv_parse` and `wrap__json_parse`)"));
+        assert_eq!(functions.len(), 1);
+
+        let parser = |func_ptr: *const u8| Impl {
+            doc: "",
+            name: "Parser",
+            methods: vec![func("wrap__Parser__parse", func_ptr)],
+            methods_only: false,
+        };
+        let with_impl = |imp: Impl| Metadata {
+            name: "other",
+            functions: Vec::new(),
+            impls: vec![imp],
+        };
+        assert!(merge(&mut functions, &mut impls, with_impl(parser(&a))).is_ok());
+        assert!(merge(&mut functions, &mut impls, with_impl(parser(&a))).is_ok());
+        let err = merge(&mut functions, &mut impls, with_impl(parser(&b)));
+        assert!(err.unwrap_err().to_string().contains("impl `Parser`"));
+        assert_eq!(impls.len(), 1);
+    }
+
+    #[test]
+    fn submodule_hooks_are_chained_once() {
+        extendr_api::test! {
+            run_composed_init_hooks();
+            R_unload_composed_extendr(std::ptr::null_mut());
+            assert_eq!(
+                *HOOKS.lock().unwrap(),
+                vec!["init csv", "init json", "unload json", "unload csv"]
+            );
+        }
+    }
+}
+
+mod conditional {
+    use super::*;
+
+    #[extendr]
+    fn always() {}
+
+    #[cfg(any())]
+    #[extendr]
+    fn never() {}
+
+    extendr_module! {
+        mod conditional;
+        #[cfg(all())]
+        fn always;
+        #[cfg(any())]
+        fn never;
+        #[cfg(any())]
+        use mod missing;
+    }
+
+    #[test]
+    fn cfg_entries_are_excluded() {
+        extendr_api::test! {
+            let metadata = get_conditional_metadata();
+            assert!(metadata.functions.iter().any(|f| f.r_name == "always"));
+            assert!(!metadata.functions.iter().any(|f| f.r_name == "never"));
+
+            let wrappers = metadata.make_r_wrappers(true, "conditional").unwrap();
+            assert!(wrappers.contains("always <- function()"));
+            assert!(!wrappers.contains("never"));
+        }
+    }
+}
+
+mod checked {
+    use super::*;
+
+    #[extendr(check_args = true)]
+    fn scale(x: Vec<f64>, by: f64, _label: Option<String>, _data: Robj) -> Vec<f64> {
+        x.iter().map(|x| x * by).collect()
+    }
+
+    #[extendr(check_args = true)]
+    fn checksum(bytes: &[u8], seed: u8) -> i32 {
+        bytes
+            .iter()
+            .fold(seed as i32, |sum, &byte| sum + byte as i32)
+    }
+
+    #[extendr]
+    fn unchecked(x: f64) -> f64 {
+        x
+    }
+
+    extendr_module! {
+        mod checked;
+        fn scale;
+        fn checksum;
+        fn unchecked;
+    }
+
+    #[test]
+    fn arguments_are_checked_in_r() {
+        extendr_api::test! {
+            let metadata = get_checked_metadata();
+            let scale = metadata.functions.iter().find(|f| f.r_name == "scale").unwrap();
+            let checks = scale.args.iter().map(|arg| arg.check).collect::<Vec<_>>();
+            assert_eq!(checks, vec![Some("numeric"), Some("number"), None, None]);
+
+            // Vectors of `u8` are raw vectors, but a single `u8` is a number.
+            let checksum = metadata.functions.iter().find(|f| f.r_name == "checksum").unwrap();
+            let checks = checksum.args.iter().map(|arg| arg.check).collect::<Vec<_>>();
+            assert_eq!(checks, vec![Some("raw"), Some("number")]);
+
+            let wrappers = metadata.make_r_wrappers(true, "checked").unwrap();
+            assert!(wrappers.contains("scale <- function(x, by, `_label`, `_data`) {\n  if (!(is.numeric(x)) stop("));
+            assert!(wrappers.contains("\"`by` must be a single number, not \""));
+            assert!(wrappers.contains("unchecked <- function(x) .Call(wrap__unchecked, x)\n"));
+            assert!(wrappers.contains("if (!(is.raw(bytes)) stop("));
+        }
+    }
+}
+
+mod validated {
+    use super::*;
+
+    #[extendr(
+        check_args = true,
+        validate(probs = "all(probs >= 0 & probs <= 1)", n = "n > 0")
+    )]
+    fn draw(probs: Vec<f64>, n: i32) -> i32 {
+        probs.len() as i32 * n
+    }
+
+    extendr_module! {
+        mod validated;
+        fn draw;
+    }
+
+    #[test]
+    fn conditions_are_checked_in_r() {
+        extendr_api::test! {
+            let metadata = get_validated_metadata();
+            let draw = &metadata.functions[0];
+            let conditions = draw.args.iter().map(|arg| arg.validate).collect::<Vec<_>>();
+            assert_eq!(conditions, vec![Some("all(probs >= 0 & probs <= 1)"), Some("n > 0")]);
+
+            // The kinds of the arguments are checked before the conditions.
+            let wrappers = metadata.make_r_wrappers(true, "validated").unwrap();
+            let number = wrappers.find("must be a single number").unwrap();
+            let positive = wrappers.find("  if (!isTRUE(n > 0)) stop(\"`n` must satisfy `n > 0`\")\n").unwrap();
+            assert!(number < positive);
+            assert!(wrappers.contains("stop(\"`probs` must satisfy `all(probs >= 0 & probs <= 1)`\")"));
+        }
+    }
+}
+
+mod s4 {
+    use super::*;
+
+    #[extendr(s4_method(generic = "show", signature = "Counter"))]
+    fn show_counter(object: Robj) -> Robj {
+        object
+    }
+
+    #[extendr(s4_method(generic = "combine", signature = "Counter, numeric"))]
+    fn combine_counter(x: Robj, _y: f64) -> Robj {
+        x
+    }
+
+    extendr_module! {
+        mod s4;
+        fn show_counter;
+        fn combine_counter;
+    }
+
+    #[test]
+    fn functions_are_registered_as_s4_methods() {
+        extendr_api::test! {
+            let metadata = get_s4_metadata();
+            let method = metadata.functions[0].s4_method.as_ref().unwrap();
+            assert_eq!((method.generic, method.signature), ("show", "Counter"));
+
+            let wrappers = metadata.make_r_wrappers(true, "s4").unwrap();
+            assert!(wrappers.contains("if (!methods::isClass(\"Counter\")) methods::setOldClass(\"Counter\")\n"));
+            assert!(wrappers.contains("methods::setMethod(\"show\", \"Counter\", show_counter)\n"));
+            assert!(wrappers.contains("if (!methods::isClass(\"numeric\")) methods::setOldClass(\"numeric\")\n"));
+            assert!(wrappers.contains("methods::setMethod(\"combine\", c(\"Counter\", \"numeric\"), combine_counter)\n"));
+            // The methods are registered after the functions are defined.
+            assert!(wrappers.find("show_counter <- function").unwrap() < wrappers.find("methods::setMethod").unwrap());
+        }
+    }
+}
+
+mod symbols {
+    use super::*;
+
+    #[extendr(symbol = "C_legacy_add")]
+    fn add(x: i32, y: i32) -> i32 {
+        x + y
+    }
+
+    #[extendr(symbol_prefix = "mypkg_")]
+    fn sub(x: i32, y: i32) -> i32 {
+        x - y
+    }
+
+    #[derive(Debug)]
+    struct Counter(i32);
+
+    #[extendr(symbol_prefix = "mypkg_")]
+    impl Counter {
+        fn get(&self) -> i32 {
+            self.0
+        }
+    }
+
+    extendr_module! {
+        mod symbols;
+        fn add;
+        fn sub;
+        impl Counter;
+    }
+
+    #[test]
+    fn symbols_can_be_renamed() {
+        extendr_api::test! {
+            unsafe {
+                assert_eq!(Robj::from_sexp(C_legacy_add(r!(1).get(), r!(2).get())), r!(3));
+                assert_eq!(Robj::from_sexp(mypkg_sub(r!(3).get(), r!(2).get())), r!(1));
+            }
+
+            let metadata = get_symbols_metadata();
+            let symbol = |name: &str| metadata.functions.iter().find(|f| f.r_name == name).unwrap().symbol;
+            assert_eq!(symbol("add"), "C_legacy_add");
+            assert_eq!(symbol("sub"), "mypkg_sub");
+            assert_eq!(symbol("get_symbols_metadata"), "wrap__get_symbols_metadata");
+            assert_eq!(metadata.impls[0].methods[0].symbol, "mypkg_Counter__get");
+
+            let wrappers = metadata.make_r_wrappers(false, "symbols").unwrap();
+            assert!(wrappers.contains(".Call(\"C_legacy_add\", x, y, PACKAGE = \"symbols\")"));
+            assert!(wrappers.contains(".Call(\"mypkg_Counter__get\", self, PACKAGE = \"symbols\")"));
+        }
+    }
+}
+
+mod generic {
+    use super::*;
+
+    /// Sum of a vector.
+    #[extendr(instances(i32, f64))]
+    fn sum_of<T: Copy + std::iter::Sum<T>>(v: Vec<T>) -> T {
+        v.into_iter().sum()
+    }
+
+    extendr_module! {
+        mod generic;
+        fn sum_of;
+    }
+
+    #[test]
+    fn instances_are_dispatched_on_type() {
+        extendr_api::test! {
+            assert_eq!(sum_of__i32(vec![1, 2]), 3);
+            unsafe {
+                assert_eq!(Robj::from_sexp(wrap__sum_of__f64(r!([1.5, 2.]).get())), r!(3.5));
+            }
+
+            let metadata = get_generic_metadata();
+            let names = metadata.functions.iter().filter(|f| !f.hidden).map(|f| f.r_name).collect::<Vec<_>>();
+            assert_eq!(names, vec!["sum_of_i32", "sum_of_f64"]);
+            let dispatch = metadata.functions[0].dispatch.as_ref().unwrap();
+            assert_eq!((dispatch.generic, dispatch.arg, dispatch.r_type), ("sum_of", "v", "integer"));
+
+            let wrappers = metadata.make_r_wrappers(true, "generic").unwrap();
+            assert!(wrappers.contains("sum_of_i32 <- function(v) .Call(wrap__sum_of__i32, v)\n"));
+            assert!(wrappers.contains(
+                "#' Sum of a vector.\nsum_of <- function(v) switch(typeof(v),\n  integer = sum_of_i32(v),\n  double = sum_of_f64(v),\n"
+            ));
+            assert!(wrappers.contains("stop(\"`v` must be of type integer or double, not \", typeof(v))"));
+        }
+    }
+}
@@ -60,3 +60,36 @@ fn test_externalptr_deref() {
         assert_eq!(extptr.y, 2);
     }
 }
+
+static RELEASED: Lazy<std::sync::Mutex<Vec<&'static str>>> =
+    Lazy::new(|| std::sync::Mutex::new(Vec::new()));
+
+struct Connection;
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        RELEASED.lock().unwrap().push("drop");
+    }
+}
+
+#[extendr(on_r_release = "close")]
+impl Connection {
+    fn close(&mut self) {
+        RELEASED.lock().unwrap().push("close");
+    }
+}
+
+#[test]
+fn test_externalptr_on_r_release() {
+    test! {
+        let robj: Robj = Connection.into();
+
+        R!("gc()").unwrap();
+        assert!(RELEASED.lock().unwrap().is_empty());
+
+        // The release hook runs before the value is dropped.
+        drop(robj);
+        R!("gc()").unwrap();
+        assert_eq!(*RELEASED.lock().unwrap(), vec!["close", "drop"]);
+    }
+}
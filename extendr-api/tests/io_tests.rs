@@ -44,3 +44,101 @@ UTF-8
         assert_eq!(res, Ok(Robj::from(1_i32)));
     }
 }
+
+#[test]
+fn test_csv_round_trip() {
+    use extendr_api::io::{CsvOptions, ReadCsv, WriteCsv};
+    use extendr_api::prelude::*;
+    test! {
+        let df = R!(r#"data.frame(
+            lgl = c(TRUE, NA, FALSE),
+            int = c(1L, NA, -3L),
+            dbl = c(0.1, NaN, 1e-20),
+            chr = c("a \"quoted\" word", NA, ""),
+            fct = factor(c("x", "y", NA))
+        )"#)?;
+
+        let mut tsv = Vec::new();
+        df.to_csv_writer(&mut tsv, &CsvOptions::tsv())?;
+        let tsv = String::from_utf8(tsv).unwrap();
+        assert_eq!(
+            tsv,
+            "lgl\tint\tdbl\tchr\tfct\n\
+             TRUE\t1\t0.1\t\"a \"\"quoted\"\" word\"\tx\n\
+             NA\tNA\tNaN\tNA\ty\n\
+             FALSE\t-3\t1e-20\t\"\"\tNA\n"
+        );
+
+        // Factors are read back as strings.
+        let read = Robj::from_csv_reader(tsv.as_bytes(), &CsvOptions::tsv())?;
+        let expected = R!(r#"local({
+            df <- {{df}}
+            df$fct <- as.character(df$fct)
+            df
+        })"#)?;
+        assert_eq!(read, expected);
+
+        // Without a header, and with another text for missing values.
+        let options = CsvOptions::csv().header(false).na("-");
+        let read = Robj::from_csv_reader("1,-\r\n2.5,b\r\n".as_bytes(), &options)?;
+        assert_eq!(read, R!("data.frame(V1 = c(1, 2.5), V2 = c(NA, 'b'))")?);
+
+        // Records must have the same number of fields.
+        assert!(Robj::from_csv_reader("a,b\n1\n".as_bytes(), &CsvOptions::csv()).is_err());
+        assert!(Robj::from_csv_reader("a\n\"1\n".as_bytes(), &CsvOptions::csv()).is_err());
+    }
+}
+
+#[test]
+fn test_data_frame_sink() {
+    use extendr_api::io::{Cell, ColumnType, DataFrameSink, RowSink};
+    use extendr_api::prelude::*;
+    test! {
+        let columns = [
+            ("lgl", ColumnType::Logical),
+            ("int", ColumnType::Integer),
+            ("dbl", ColumnType::Double),
+            ("chr", ColumnType::Character),
+        ];
+        let mut sink = DataFrameSink::with_capacity(columns, 1);
+        sink.push_row([Cell::Integer(2), Cell::Logical(true), Cell::Integer(1 << 40), Cell::Text("a")])?;
+        sink.push_row([Cell::Null; 4])?;
+        sink.push_row([Some(false), None, None, None])?;
+        assert_eq!(sink.rows(), 3);
+        let df = sink.finish()?;
+        assert_eq!(df, R!(r#"data.frame(
+            lgl = c(TRUE, NA, FALSE),
+            int = c(1L, NA, NA),
+            dbl = c(2^40, NA, NA),
+            chr = c("a", NA, NA)
+        )"#)?);
+
+        let mut sink = DataFrameSink::new(columns);
+        assert!(sink.push_cell(Cell::Text("a")).is_err());
+        let mut sink = DataFrameSink::new(columns);
+        sink.push_cell(Cell::Null)?;
+        assert!(sink.push_cell(Cell::Integer(i64::MAX)).is_err());
+        sink.push_cell(Cell::Null)?;
+        assert!(sink.end_row().is_err());
+        assert!(sink.finish().is_err());
+
+        let empty = DataFrameSink::new(columns).finish()?;
+        assert_eq!(R!("nrow({{empty}})")?, r!(0));
+    }
+}
+
+#[test]
+fn test_raw_connection() {
+    use extendr_api::{io::RawConnection, prelude::*};
+    test! {
+        let output = RawConnection::writer()?;
+        call!("writeBin", r!([1, 2, 3]), output.robj())?;
+        call!("writeLines", "text", output.robj())?;
+        let bytes = output.into_bytes()?;
+        assert_eq!(bytes.len(), 3 * 4 + 5);
+
+        let input = Robj::raw_connection(bytes)?;
+        assert_eq!(call!("readBin", input.robj(), "integer", 3)?, r!([1, 2, 3]));
+        assert_eq!(call!("readLines", input.robj())?, r!("text"));
+    }
+}
@@ -1,2 +1,6 @@
 #[cfg(feature = "either")]
 mod either;
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "registry")]
+mod registry;
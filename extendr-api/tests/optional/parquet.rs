@@ -0,0 +1,37 @@
+use extendr_api::optional::parquet::{read_parquet, write_parquet};
+use extendr_api::prelude::*;
+
+#[test]
+fn parquet_round_trip() {
+    test! {
+        let df = R!(r#"
+            data.frame(
+                lgl = c(TRUE, NA, FALSE),
+                int = c(1L, NA, 3L),
+                dbl = c(1.5, NA, -2),
+                chr = c("a", NA, "c"),
+                fct = factor(c("x", "y", NA), levels = c("y", "unused", "x")),
+                date = as.Date(c("2024-01-31", NA, "1960-05-01")),
+                time = as.POSIXct(c(0, NA, 1.5), tz = "UTC", origin = "1970-01-01")
+            )
+        "#)?;
+        let path = std::env::temp_dir().join("extendr-parquet-round-trip.parquet");
+        write_parquet(&Dataframe::<()>::try_from(&df)?, &path)?;
+        let read = read_parquet(&path)?;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(read.names().unwrap().collect::<Vec<_>>(), ["lgl", "int", "dbl", "chr", "fct", "date", "time"]);
+        assert_eq!(read.dollar("lgl")?, df.dollar("lgl")?);
+        assert_eq!(read.dollar("int")?, df.dollar("int")?);
+        assert_eq!(read.dollar("dbl")?, df.dollar("dbl")?);
+        assert_eq!(read.dollar("chr")?, df.dollar("chr")?);
+        assert_eq!(read.dollar("date")?, df.dollar("date")?);
+        assert_eq!(read.dollar("time")?, df.dollar("time")?);
+        // The levels are kept in order, including unused ones.
+        assert_eq!(read.dollar("fct")?.levels().unwrap().collect::<Vec<_>>(), ["y", "unused", "x"]);
+        assert!(R!("identical({{read}}$fct, {{df}}$fct)")?.as_bool().unwrap());
+        assert_eq!(R!("nrow({{read}})")?, r!(3));
+
+        assert!(read_parquet(std::env::temp_dir().join("extendr-no-such-file.parquet")).is_err());
+    }
+}
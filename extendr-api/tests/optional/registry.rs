@@ -0,0 +1,39 @@
+use extendr_api::prelude::*;
+
+#[extendr]
+fn registered_double(x: f64) -> f64 {
+    x * 2.
+}
+
+#[derive(Debug)]
+struct RegisteredCounter(i32);
+
+#[extendr]
+impl RegisteredCounter {
+    fn get(&self) -> i32 {
+        self.0
+    }
+}
+
+#[extendr]
+fn listed_too() {}
+
+extendr_module! {
+    mod registered;
+    registry = true;
+    fn listed_too;
+}
+
+#[test]
+fn registry_items_are_registered() {
+    test! {
+        let metadata = get_registered_metadata();
+        let count = |name: &str| metadata.functions.iter().filter(|f| f.r_name == name).count();
+        assert_eq!(count("registered_double"), 1);
+        assert_eq!(count("listed_too"), 1);
+        assert!(metadata.impls.iter().any(|imp| imp.name == "RegisteredCounter"));
+
+        let wrappers = metadata.make_r_wrappers(true, "registered").unwrap();
+        assert!(wrappers.contains("registered_double <- function(x)"));
+    }
+}
@@ -0,0 +1,25 @@
+use extendr_api::package::init_package;
+use extendr_api::prelude::*;
+
+#[test]
+fn package_state() {
+    test! {
+        assert!(package_env().is_err());
+
+        // R registers the namespace before loading the shared library of the package.
+        eval_string(r#".Internal(registerNamespace("extendrstate", new.env()))"#)?;
+        init_package("extendrstate");
+        assert_eq!(package_env()?, find_namespace("extendrstate")?);
+
+        // Values that have not been set are NULL.
+        assert_eq!(get_state::<Option<i32>>("calls")?, None);
+        set_state("calls", 1)?;
+        set_state("calls", get_state::<i32>("calls")? + 1)?;
+        assert_eq!(get_state::<i32>("calls")?, 2);
+
+        // The state is kept in the namespace.
+        let state = package_env()?.local(Symbol::from_string(".__extendr_state__"))?;
+        assert_eq!(state.as_environment().unwrap().local(sym!(calls))?, r!(2));
+        assert!(!global_env().local(sym!(calls))?.is_integer());
+    }
+}
@@ -0,0 +1,41 @@
+use extendr_api::panic_hook::{self, CallGuard};
+use extendr_api::prelude::*;
+
+#[test]
+fn test_panic_report() {
+    test! {
+        panic_hook::install();
+        let x = r!([1.0, 2.0, 3.0]);
+        let res = std::panic::catch_unwind(|| {
+            let _guard = CallGuard::enter("my_func", &[("x", x.get())]);
+            panic!("100% broken");
+        });
+        assert!(res.is_err());
+
+        let report = panic_hook::take_report().unwrap();
+        assert!(report.starts_with("in my_func(x = Doubles[3]): 100%% broken at "));
+        assert!(report.contains("panic_hook_tests.rs"));
+        assert_eq!(panic_hook::take_report(), None);
+    }
+}
+
+#[test]
+fn test_panic_backtrace() {
+    test! {
+        panic_hook::install();
+        panic_hook::set_capture_backtrace(true);
+        let res = std::panic::catch_unwind(|| {
+            let _guard = CallGuard::enter("my_func", &[]);
+            panic!("broken");
+        });
+        assert!(res.is_err());
+        panic_hook::set_capture_backtrace(false);
+
+        let backtrace = panic_hook::take_backtrace().unwrap();
+        assert!(backtrace.contains("panic_hook_tests"));
+        assert!(!backtrace.contains("core::panicking"));
+        assert!(!backtrace.contains("catch_unwind"));
+        assert_eq!(panic_hook::take_backtrace(), None);
+        panic_hook::take_report();
+    }
+}
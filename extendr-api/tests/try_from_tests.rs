@@ -142,3 +142,52 @@ fn test_try_from() {
         // test_matrix!(strings.clone(), int_ok : true, float_ok : true, bool_ok : false, str_ok: false);
     }
 }
+
+#[test]
+fn test_try_from_nullable_vectors() {
+    use extendr_api::prelude::*;
+    test! {
+        let ints = R!("c(1L, NA, 3L)")?;
+        assert_eq!(<Vec<Option<i32>>>::try_from(&ints)?, vec![Some(1), None, Some(3)]);
+        assert!(<Vec<Option<f64>>>::try_from(&ints).is_err());
+
+        let doubles = R!("c(NA, NaN, 1.5)")?;
+        let values = <Vec<Option<f64>>>::try_from(doubles.clone())?;
+        assert_eq!(values[0], None);
+        assert!(values[1].unwrap().is_nan());
+        assert_eq!(values[2], Some(1.5));
+        assert_eq!(r!(values), doubles);
+
+        let logicals = R!("c(TRUE, NA, FALSE)")?;
+        let values = <Vec<Option<bool>>>::try_from(&logicals)?;
+        assert_eq!(values, vec![Some(true), None, Some(false)]);
+        assert_eq!(r!(values), logicals);
+
+        let strings = R!("c('a', NA)")?;
+        assert_eq!(<Vec<Option<String>>>::try_from(&strings)?, vec![Some("a".to_string()), None]);
+        assert!(<Vec<Option<String>>>::try_from(&ints).is_err());
+
+        // Nullable vectors may themselves be optional.
+        assert_eq!(<Option<Vec<Option<i32>>>>::try_from(r!(NULL))?, None);
+    }
+}
+
+#[test]
+fn test_try_from_tuples_and_arrays() {
+    use extendr_api::prelude::*;
+    test! {
+        // Tuples convert from lists and from atomic vectors of the right length.
+        assert_eq!(<(f64, f64)>::try_from(&r!([1.5, 2.0]))?, (1.5, 2.0));
+        assert_eq!(<(f64, f64)>::try_from(&r!([1, 2]))?, (1.0, 2.0));
+        assert_eq!(<(i32, String)>::try_from(&list!(1, "a"))?, (1, "a".to_string()));
+        assert_eq!(<(f64,)>::try_from(&r!(3.0))?, (3.0,));
+
+        let error = <(f64, f64)>::try_from(&r!([1.0, 2.0, 3.0])).unwrap_err();
+        assert_eq!(error.to_string(), "Expected length: 2");
+        assert!(<(f64, f64)>::try_from(&r!(["a", "b"])).is_err());
+
+        assert_eq!(<[f64; 3]>::try_from(&r!([0.5, 0.25, 1.0]))?, [0.5, 0.25, 1.0]);
+        let error = <[f64; 3]>::try_from(&r!([0.5, 0.25])).unwrap_err();
+        assert_eq!(error.to_string(), "Expected length: 3");
+    }
+}
@@ -0,0 +1,181 @@
+//! Startup options of the embedded R session, and discovery of `R_HOME`.
+
+use std::ffi::CString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Errors when starting the embedded R session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    /// No R installation was found. Lists the places that were searched.
+    RHomeNotFound(Vec<String>),
+    /// A startup argument contains a NUL byte.
+    InvalidArgument(String),
+    /// R was used from a thread other than the one that started it.
+    NotMainThread,
+    /// R has been ended and can not be started again.
+    Ended,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::RHomeNotFound(searched) => write!(
+                f,
+                "Could not find R. Set the R_HOME environment variable to the R installation. Searched: {}",
+                searched.join(", ")
+            ),
+            EngineError::InvalidArgument(arg) => {
+                write!(f, "Invalid R startup argument {:?}", arg)
+            }
+            EngineError::NotMainThread => {
+                write!(f, "R can only be started and ended on the thread that started it")
+            }
+            EngineError::Ended => write!(f, "R has been ended and can not be started again"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// Configuration of the embedded R session, used by [`start_r_with`](crate::start_r_with).
+///
+/// By default, R is started with `--slave --no-save`, and `R_HOME` is found
+/// by [`find_r_home`].
+///
+/// ```no_run
+/// use extendr_engine::{start_r_with, EngineConfig};
+///
+/// let config = EngineConfig::new().arg("--vanilla").arg("--max-ppsize=100000");
+/// start_r_with(&config).expect("failed to start R");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineConfig {
+    args: Vec<String>,
+    r_home: Option<PathBuf>,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            args: vec!["--slave".to_string(), "--no-save".to_string()],
+            r_home: None,
+        }
+    }
+}
+
+impl EngineConfig {
+    /// The default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a startup argument, such as `--vanilla` or `--max-ppsize=100000`.
+    /// See `R --help` for the available arguments.
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Replace the startup arguments.
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Use the R installation in `r_home` instead of searching for one.
+    pub fn r_home<P: Into<PathBuf>>(mut self, r_home: P) -> Self {
+        self.r_home = Some(r_home.into());
+        self
+    }
+
+    /// The R installation to use.
+    pub(crate) fn resolve_r_home(&self) -> Result<PathBuf, EngineError> {
+        match &self.r_home {
+            Some(r_home) if r_home.is_dir() => Ok(r_home.clone()),
+            Some(r_home) => Err(EngineError::RHomeNotFound(vec![r_home
+                .display()
+                .to_string()])),
+            None => find_r_home(),
+        }
+    }
+
+    /// The arguments for `Rf_initialize_R`, starting with the program name.
+    pub(crate) fn c_args(&self) -> Result<Vec<CString>, EngineError> {
+        std::iter::once("R")
+            .chain(self.args.iter().map(String::as_str))
+            .map(|arg| CString::new(arg).map_err(|_| EngineError::InvalidArgument(arg.into())))
+            .collect()
+    }
+}
+
+/// A place to look for `R_HOME`, and how to read it.
+type Candidate = (&'static str, fn() -> Option<PathBuf>);
+
+/// Find the R installation to embed.
+///
+/// This uses, in order, the first existing directory of:
+/// - the `R_HOME` environment variable,
+/// - the `R_HOME` that extendr was built against,
+/// - the output of `R RHOME`, using the `R` on the `PATH`,
+/// - on Windows, the `InstallPath` of R in the registry.
+pub fn find_r_home() -> Result<PathBuf, EngineError> {
+    let mut searched = Vec::new();
+    let candidates: [Candidate; 4] = [
+        ("R_HOME environment variable", || {
+            std::env::var_os("R_HOME").map(PathBuf::from)
+        }),
+        ("build-time R_HOME", || Some(PathBuf::from(env!("R_HOME")))),
+        ("R RHOME", r_home_from_r),
+        ("Windows registry", r_home_from_registry),
+    ];
+    for (source, candidate) in candidates {
+        match candidate() {
+            Some(r_home) if is_r_home(&r_home) => return Ok(r_home),
+            Some(r_home) => searched.push(format!("{} ({})", source, r_home.display())),
+            None => searched.push(source.to_string()),
+        }
+    }
+    Err(EngineError::RHomeNotFound(searched))
+}
+
+fn is_r_home(path: &Path) -> bool {
+    !path.as_os_str().is_empty() && path.is_dir()
+}
+
+fn r_home_from_r() -> Option<PathBuf> {
+    let output = Command::new("R").arg("RHOME").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let r_home = String::from_utf8(output.stdout).ok()?;
+    Some(PathBuf::from(r_home.trim()))
+}
+
+#[cfg(windows)]
+fn r_home_from_registry() -> Option<PathBuf> {
+    // `reg query` prints lines such as
+    // `    InstallPath    REG_SZ    C:\Program Files\R\R-4.3.1`
+    ["HKLM", "HKCU"].iter().find_map(|hive| {
+        let key = format!(r"{}\SOFTWARE\R-core\R", hive);
+        let output = Command::new("reg")
+            .args(["query", &key, "/v", "InstallPath"])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.lines().find_map(|line| {
+            let (_, path) = line.trim().split_once("REG_SZ")?;
+            Some(PathBuf::from(path.trim()))
+        })
+    })
+}
+
+#[cfg(not(windows))]
+fn r_home_from_registry() -> Option<PathBuf> {
+    None
+}
@@ -40,13 +40,23 @@
 //! `with_r` is not macro-based, thus code formatter `rustfmt` and rust LSPs (Rust Analyzer, Rust Rover, etc.)
 //! works within `with_r` without any problems.
 //!
+//! Both run the code on the calling thread. As R is single-threaded, tests
+//! that run in parallel may interfere with each other. This crate's own
+//! [`test!`](crate::test!) and [`run_on_r_thread`] instead run the code on a
+//! dedicated R thread, one test at a time.
+//!
 //!
 //! ## Binaries
 //!
 //! In a binary program, one may use [`start_r`] directly in the `main`-function.
+//! Use [`start_r_with`] to pass startup arguments such as `--vanilla`, or to
+//! choose the R installation. Unless given, `R_HOME` is found with [`find_r_home`].
+//!
+//! On Unix, [`run_repl`] runs an interactive R console instead, with the input
+//! and output going through a [`Console`].
 //!
-//! There is no `end_r`, as we terminate the R process setup, when the parent
-//! process terminates.
+//! R is ended when the process terminates, or by [`end_r`]. Starting R again
+//! after that is an error, as R can only be started once per process.
 //!
 //! [`test!`]: https://docs.rs/extendr-api/latest/extendr_api/macro.test.html
 //!
@@ -62,27 +72,85 @@
 //
 
 use libR_sys::*;
+use std::ffi::CString;
 use std::os::raw;
-use std::sync::Once;
-
-// Generate mutable static strings.
-// Much more efficient than `CString`.
-// Generates asciiz.
-macro_rules! cstr_mut {
-    ($s: expr) => {
-        concat!($s, "\0").as_ptr() as *mut raw::c_char
-    };
-}
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex, Once};
+use std::thread::ThreadId;
+
+mod config;
+#[cfg(unix)]
+mod repl;
+
+pub use config::{find_r_home, EngineConfig, EngineError};
+#[cfg(unix)]
+pub use repl::{run_repl, Console, StdConsole};
 
 static START_R: Once = Once::new();
 
+// The thread that started R, which is the only one that may end it.
+static R_MAIN_THREAD: Mutex<Option<ThreadId>> = Mutex::new(None);
+
+static R_ENDED: AtomicBool = AtomicBool::new(false);
+
+/// Start the embedded R session with the default [`EngineConfig`],
+/// if it is not running yet.
+///
+/// Unlike [`try_start_r`], this may be called from any thread once R is
+/// running, which the `test!` macros and [`with_r`] rely on, as the test
+/// harness runs tests on several threads.
+///
+/// # Panics
+///
+/// If R can not be found or has been ended, see [`try_start_r`] for a
+/// version that returns an error instead.
 pub fn start_r() {
+    if let Err(err) = start(&EngineConfig::default(), false) {
+        panic!("{}", err);
+    }
+}
+
+/// Start the embedded R session with the default [`EngineConfig`],
+/// if it is not running yet.
+///
+/// Calling this again is a no-op on the thread that started R, and an
+/// [`EngineError::NotMainThread`] error on other threads.
+pub fn try_start_r() -> Result<(), EngineError> {
+    start_r_with(&EngineConfig::default())
+}
+
+/// Start the embedded R session with `config`, if it is not running yet.
+///
+/// The configuration of the first successful call is used,
+/// as R can only be started once per process.
+/// Calling this again is a no-op on the thread that started R, and an
+/// [`EngineError::NotMainThread`] error on other threads.
+pub fn start_r_with(config: &EngineConfig) -> Result<(), EngineError> {
+    start(config, true)
+}
+
+fn start(config: &EngineConfig, check_thread: bool) -> Result<(), EngineError> {
+    let args = config.c_args()?;
+    if R_ENDED.load(Ordering::Acquire) {
+        return Err(EngineError::Ended);
+    }
+    if START_R.is_completed() {
+        return if check_thread {
+            check_main_thread()
+        } else {
+            Ok(())
+        };
+    }
+    let r_home = config.resolve_r_home()?;
+
     START_R.call_once(|| {
+        *R_MAIN_THREAD.lock().unwrap_or_else(|err| err.into_inner()) =
+            Some(std::thread::current().id());
         unsafe {
-            if std::env::var("R_HOME").is_err() {
-                // env! gets the build-time R_HOME stored by libR-sys
-                std::env::set_var("R_HOME", env!("R_HOME"));
-            }
+            std::env::set_var("R_HOME", &r_home);
+
+            // R keeps pointers to the arguments, so they live until the process ends.
+            let mut argv: Vec<*mut raw::c_char> = args.into_iter().map(CString::into_raw).collect();
 
             // Due to Rf_initEmbeddedR using __libc_stack_end
             // We can't call Rf_initEmbeddedR.
@@ -90,10 +158,8 @@ pub fn start_r() {
 
             //let res = unsafe { Rf_initEmbeddedR(1, args.as_mut_ptr()) };
             // NOTE: R will crash if this is called twice in the same process.
-            Rf_initialize_R(
-                3,
-                [cstr_mut!("R"), cstr_mut!("--slave"), cstr_mut!("--no-save")].as_mut_ptr(),
-            );
+            Rf_initialize_R(argv.len() as raw::c_int, argv.as_mut_ptr());
+            std::mem::forget(argv);
 
             // In case you are curious.
             // Maybe 8MB is a bit small.
@@ -103,11 +169,39 @@ pub fn start_r() {
             setup_Rmainloop();
         }
     });
+    Ok(())
+}
+
+/// Returns [`EngineError::NotMainThread`] unless called from the thread
+/// that started R. It is not an error if R is not running.
+pub fn check_main_thread() -> Result<(), EngineError> {
+    match *R_MAIN_THREAD.lock().unwrap_or_else(|err| err.into_inner()) {
+        Some(main_thread) if main_thread != std::thread::current().id() => {
+            Err(EngineError::NotMainThread)
+        }
+        _ => Ok(()),
+    }
 }
 
 /// Close down the R interpreter. Note you won't be able to
 /// Restart it, so use with care or not at all.
-fn end_r() {
+///
+/// This is a no-op if R is not running or has already been ended, and an
+/// [`EngineError::NotMainThread`] error if called from a thread other
+/// than the one that started R.
+pub fn end_r() -> Result<(), EngineError> {
+    if !START_R.is_completed() {
+        return Ok(());
+    }
+    check_main_thread()?;
+    end();
+    Ok(())
+}
+
+fn end() {
+    if R_ENDED.swap(true, Ordering::AcqRel) {
+        return;
+    }
     unsafe {
         //Rf_endEmbeddedR(0);
         R_RunExitFinalizers();
@@ -125,10 +219,99 @@ pub fn with_r(f: impl FnOnce()) {
     // is no `end_r()` call here.
 }
 
+type Job = Box<dyn FnOnce() + Send>;
+
+// The sending end of the queue of jobs for the R thread, see `run_on_r_thread`.
+static R_THREAD: Mutex<Option<mpsc::Sender<Job>>> = Mutex::new(None);
+
+/// Run `f` on a dedicated thread that owns the embedded R session,
+/// and return its result.
+///
+/// The thread starts R on first use and runs one closure at a time,
+/// so tests that use this run one after the other, even when the test
+/// harness runs them on several threads. A panic in `f` is resumed on the
+/// calling thread.
+///
+/// ```no_run
+/// use extendr_engine::run_on_r_thread;
+///
+/// let thread_name = run_on_r_thread(|| std::thread::current().name().map(String::from));
+/// assert_eq!(thread_name.as_deref(), Some("extendr-r-main"));
+/// ```
+pub fn run_on_r_thread<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (result_sender, result_receiver) = mpsc::channel();
+    let job: Job = Box::new(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        let _ = result_sender.send(result);
+    });
+    r_thread().send(job).expect("the R thread has stopped");
+    match result_receiver.recv().expect("the R thread has stopped") {
+        Ok(result) => result,
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
+}
+
+fn r_thread() -> mpsc::Sender<Job> {
+    let mut r_thread = R_THREAD.lock().unwrap_or_else(|err| err.into_inner());
+    r_thread
+        .get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel::<Job>();
+            std::thread::Builder::new()
+                .name("extendr-r-main".to_string())
+                .spawn(move || {
+                    start_r();
+                    for job in receiver {
+                        job();
+                    }
+                })
+                .expect("failed to spawn the R thread");
+            sender
+        })
+        .clone()
+}
+
+/// Run test code that uses R on the R thread, see [`run_on_r_thread`].
+///
+/// Unlike `extendr_api::test!`, the body runs on a single thread that owns
+/// the embedded R session, and only one body runs at a time.
+/// The body may use `?` with any error type that implements
+/// [`std::error::Error`], and the test fails with its message.
+/// The body can not borrow variables from the enclosing scope.
+///
+/// ```ignore
+/// use extendr_api::prelude::*;
+///
+/// #[test]
+/// fn conversion() {
+///     extendr_engine::test! {
+///         let x: Vec<i32> = r!([1, 2]).try_into()?;
+///         assert_eq!(x, vec![1, 2]);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! test {
+    ($($body: tt)*) => {
+        $crate::run_on_r_thread(|| {
+            let body = || -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+                $($body)*
+                Ok(())
+            };
+            body().map_err(|err| err.to_string())
+        })
+        .unwrap()
+    };
+}
+
 #[ctor::dtor]
 fn shutdown_r() {
+    // The process is exiting, so the thread does not matter anymore.
     if START_R.is_completed() {
-        end_r();
+        end();
     }
 }
 
@@ -136,6 +319,35 @@ fn shutdown_r() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_r_thread() {
+        let name = run_on_r_thread(|| std::thread::current().name().map(String::from));
+        assert_eq!(name.as_deref(), Some("extendr-r-main"));
+
+        let result = std::panic::catch_unwind(|| run_on_r_thread(|| panic!("oops")));
+        assert!(result.is_err());
+
+        // The R thread survives panics.
+        assert_eq!(run_on_r_thread(|| 1 + 1), 2);
+    }
+
+    #[test]
+    fn test_engine_config() {
+        let config = EngineConfig::new().arg("bad\0arg");
+        assert_eq!(
+            start_r_with(&config),
+            Err(EngineError::InvalidArgument("bad\0arg".into()))
+        );
+
+        let config = EngineConfig::new().r_home("/no/such/r/home");
+        assert!(matches!(
+            config.resolve_r_home(),
+            Err(EngineError::RHomeNotFound(_))
+        ));
+
+        assert!(find_r_home().is_ok());
+    }
+
     #[test]
     fn test_engine() {
         // If this is the first call, it should wake up the interpreter.
@@ -144,6 +356,14 @@ mod tests {
         // This should do nothing.
         start_r();
 
+        // R may have been started by another test, on another thread.
+        let on_main_thread = check_main_thread().is_ok();
+        assert_eq!(try_start_r().is_ok(), on_main_thread);
+        let result = std::thread::spawn(try_start_r).join().unwrap();
+        assert_eq!(result, Err(EngineError::NotMainThread));
+        let result = std::thread::spawn(end_r).join().unwrap();
+        assert_eq!(result, Err(EngineError::NotMainThread));
+
         // Ending the interpreter is bad if we are running multiple threads.
         // So avoid doing this in tests.
         //end_r();
@@ -0,0 +1,134 @@
+//! An interactive R console, with input and output going through Rust.
+
+use crate::{start_r_with, EngineConfig, EngineError};
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::io::{BufRead, Write};
+use std::os::raw::{c_char, c_int, c_uchar, c_void};
+
+/// The input and output of the R console, see [`run_repl`].
+pub trait Console {
+    /// Read a line of input after showing `prompt`.
+    /// Returns `None` at the end of the input, which quits R.
+    ///
+    /// `add_to_history` is `true` if the line should be added to the
+    /// command history.
+    fn read_line(&mut self, prompt: &str, add_to_history: bool) -> Option<String>;
+
+    /// Show output of R. `is_error` is `true` for messages, warnings
+    /// and errors, which R writes to `stderr`.
+    fn write(&mut self, text: &str, is_error: bool);
+}
+
+/// A [`Console`] using the standard input and output of the process.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdConsole;
+
+impl Console for StdConsole {
+    fn read_line(&mut self, prompt: &str, _add_to_history: bool) -> Option<String> {
+        self.write(prompt, false);
+        let mut line = String::new();
+        match std::io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    }
+
+    fn write(&mut self, text: &str, is_error: bool) {
+        if is_error {
+            let _ = std::io::stderr().write_all(text.as_bytes());
+        } else {
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(text.as_bytes());
+            let _ = stdout.flush();
+        }
+    }
+}
+
+// The console callbacks of R, from Rinterface.h.
+extern "C" {
+    static mut ptr_R_ReadConsole:
+        Option<unsafe extern "C" fn(*const c_char, *mut c_uchar, c_int, c_int) -> c_int>;
+    static mut ptr_R_WriteConsole: Option<unsafe extern "C" fn(*const c_char, c_int)>;
+    static mut ptr_R_WriteConsoleEx: Option<unsafe extern "C" fn(*const c_char, c_int, c_int)>;
+    static mut R_Outputfile: *mut c_void;
+    static mut R_Consolefile: *mut c_void;
+    fn run_Rmainloop();
+}
+
+thread_local! {
+    static CONSOLE: RefCell<Option<Box<dyn Console>>> = RefCell::new(None);
+}
+
+/// Start R with `config` and run its read-eval-print loop,
+/// using `console` for input and output.
+///
+/// This returns only if R could not be started. When the user quits R,
+/// with `q()` or at the end of the input, the process exits.
+///
+/// Prompts are not shown with the `--slave` argument of the default
+/// configuration, so a console usually needs other arguments:
+///
+/// ```no_run
+/// use extendr_engine::{run_repl, EngineConfig, StdConsole};
+///
+/// let config = EngineConfig::new().args(["--interactive", "--no-save"]);
+/// let err = run_repl(&config, StdConsole);
+/// eprintln!("{:?}", err);
+/// ```
+pub fn run_repl<C: Console + 'static>(
+    config: &EngineConfig,
+    console: C,
+) -> Result<(), EngineError> {
+    start_r_with(config)?;
+    CONSOLE.with(|c| *c.borrow_mut() = Some(Box::new(console)));
+    unsafe {
+        // Without output files, R writes through the callbacks.
+        R_Outputfile = std::ptr::null_mut();
+        R_Consolefile = std::ptr::null_mut();
+        ptr_R_ReadConsole = Some(read_console);
+        ptr_R_WriteConsole = None;
+        ptr_R_WriteConsoleEx = Some(write_console);
+        run_Rmainloop();
+    }
+    Ok(())
+}
+
+unsafe extern "C" fn read_console(
+    prompt: *const c_char,
+    buf: *mut c_uchar,
+    buflen: c_int,
+    hist: c_int,
+) -> c_int {
+    let prompt = CStr::from_ptr(prompt).to_string_lossy();
+    let line = CONSOLE.with(|c| {
+        c.borrow_mut()
+            .as_mut()
+            .and_then(|console| console.read_line(&prompt, hist != 0))
+    });
+    let Some(line) = line else {
+        return 0;
+    };
+    // R expects a line ending with a newline, followed by a NUL byte.
+    let line = line.trim_end_matches(['\r', '\n']);
+    let max_len = (buflen as usize).saturating_sub(2);
+    let mut len = line.len().min(max_len);
+    while !line.is_char_boundary(len) {
+        len -= 1;
+    }
+    let buf = std::slice::from_raw_parts_mut(buf, buflen as usize);
+    buf[..len].copy_from_slice(&line.as_bytes()[..len]);
+    buf[len] = b'\n';
+    buf[len + 1] = 0;
+    1
+}
+
+unsafe extern "C" fn write_console(buf: *const c_char, buflen: c_int, otype: c_int) {
+    let bytes = std::slice::from_raw_parts(buf as *const u8, buflen as usize);
+    let text = String::from_utf8_lossy(bytes);
+    CONSOLE.with(|c| {
+        if let Some(console) = c.borrow_mut().as_mut() {
+            console.write(&text, otype != 0);
+        }
+    });
+}
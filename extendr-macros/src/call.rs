@@ -1,67 +1,144 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse::ParseStream, punctuated::Punctuated, Token};
-use syn::{parse_macro_input, parse_quote, Expr, ExprAssign, ExprPath, LitStr};
+use syn::{parse::ParseStream, Token};
+use syn::{parse_macro_input, Expr, ExprAssign, ExprPath, Ident, LitStr};
+
+use crate::list::spliced;
 
 #[derive(Debug)]
 struct Call {
     caller: LitStr,
-    pairs: Punctuated<Expr, Token![,]>,
+    args: Vec<Arg>,
+}
+
+#[derive(Debug)]
+enum Arg {
+    /// `a = 1`, `b` or `!!c`.
+    Expr(Expr),
+    /// `a? = x`, which is left out if `x` is `None`.
+    Optional(Ident, Expr),
+    /// `...`, which forwards the dots of the current environment.
+    Dots,
+}
+
+impl syn::parse::Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![...]) {
+            input.parse::<Token![...]>()?;
+            return Ok(Arg::Dots);
+        }
+
+        // Look ahead for `name? =`, which is not an expression.
+        let fork = input.fork();
+        let optional = fork.parse::<Ident>().is_ok()
+            && fork.parse::<Token![?]>().is_ok()
+            && fork.parse::<Token![=]>().is_ok()
+            && !fork.peek(Token![=]);
+        if optional {
+            let name = input.parse::<Ident>()?;
+            input.parse::<Token![?]>()?;
+            input.parse::<Token![=]>()?;
+            return Ok(Arg::Optional(name, input.parse::<Expr>()?));
+        }
+
+        Ok(Arg::Expr(input.parse::<Expr>()?))
+    }
 }
 
-// Custom parser for a call eg. call!("xyz", a=1, b, c)
+// Custom parser for a call eg. call!("xyz", a=1, b, c? = d, !!e, ...)
 impl syn::parse::Parse for Call {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut res = Self {
             caller: input.parse::<LitStr>()?,
-            pairs: Punctuated::new(),
+            args: Vec::new(),
         };
 
         while !input.is_empty() {
             input.parse::<Token![,]>()?;
-            res.pairs.push(input.parse::<Expr>()?);
+            if input.is_empty() {
+                break;
+            }
+            res.args.push(input.parse::<Arg>()?);
         }
         Ok(res)
     }
 }
 
+// The name of an argument `name = value`, if it is one.
+fn named(e: &Expr) -> Option<(String, &Expr)> {
+    if let Expr::Assign(ExprAssign { left, right, .. }) = e {
+        if let Expr::Path(ExprPath { path, .. }) = &**left {
+            if let Some(ident) = path.get_ident() {
+                return Some((ident.to_string(), right));
+            }
+        }
+    }
+    None
+}
+
 pub fn call(item: TokenStream) -> TokenStream {
     // Get a [Call] object from the input token stream.
     // This consists of a literal string followed by named or unnamed arguments
     // as in the pairlist macro.
     let call = parse_macro_input!(item as Call);
 
-    // Convert the pairs into tuples of ("name", Robj::from(value))
-    let pairs = call
-        .pairs
-        .iter()
-        .map(|e| {
-            if let Expr::Assign(ExprAssign { left, right, .. }) = e {
-                if let Expr::Path(ExprPath { path, .. }) = &**left {
-                    if let Some(ident) = path.get_ident() {
-                        let s = ident.to_string();
-                        return parse_quote!( (#s, extendr_api::Robj::from(#right)) );
-                    }
-                }
-            }
-            parse_quote!( ("", extendr_api::Robj::from(#e)) )
-        })
-        .collect::<Vec<Expr>>();
-
     // Use eval_string to convert the literal string into a callable object.
     let caller = &call.caller;
     let caller = quote!(extendr_api::functions::eval_string(#caller));
 
+    let simple = call
+        .args
+        .iter()
+        .all(|arg| matches!(arg, Arg::Expr(e) if spliced(e).is_none()));
+
     // Use the "call" method of Robj to call the function or primitive.
     // This will error if the object is not callable.
-    let res = if pairs.is_empty() {
+    let res = if call.args.is_empty() {
         quote!(
             (#caller).and_then(|caller| caller.call(extendr_api::wrapper::Pairlist::new()))
         )
-    } else {
+    } else if simple {
+        // Convert the arguments into tuples of ("name", Robj::from(value))
+        let pairs = call.args.iter().map(|arg| match arg {
+            Arg::Expr(e) => match named(e) {
+                Some((name, value)) => quote!( (#name, extendr_api::Robj::from(#value)) ),
+                None => quote!( ("", extendr_api::Robj::from(#e)) ),
+            },
+            _ => unreachable!(),
+        });
         quote!(
             (#caller).and_then(|caller| caller.call(extendr_api::wrapper::Pairlist::from_pairs(&[# ( #pairs ),*])))
         )
+    } else {
+        // Optional, spliced and forwarded arguments are collected by `call_args`.
+        let args = call.args.iter().map(|arg| match arg {
+            Arg::Expr(e) => match (named(e), spliced(e)) {
+                (Some((name, value)), _) => quote!(
+                    extendr_api::wrapper::CallArg::Value(#name, extendr_api::Robj::from(#value))
+                ),
+                (None, Some(inner)) => quote!(
+                    extendr_api::wrapper::CallArg::Splice(extendr_api::Robj::from(#inner))
+                ),
+                (None, None) => quote!(
+                    extendr_api::wrapper::CallArg::Value("", extendr_api::Robj::from(#e))
+                ),
+            },
+            Arg::Optional(name, value) => {
+                let name = name.to_string();
+                quote!(
+                    extendr_api::wrapper::CallArg::Optional(#name, (#value).map(extendr_api::Robj::from))
+                )
+            }
+            Arg::Dots => quote!(
+                extendr_api::wrapper::CallArg::Dots(extendr_api::functions::current_env())
+            ),
+        });
+        quote!(
+            (#caller).and_then(|caller| {
+                let args = extendr_api::wrapper::function::call_args(vec![# ( #args ),*])?;
+                caller.call(args)
+            })
+        )
     };
 
     TokenStream::from(res)
@@ -1,10 +1,29 @@
-use crate::{extendr_options::ExtendrOptions, wrappers};
+use crate::{
+    extendr_options::{ExtendrOptions, Instance},
+    wrappers,
+};
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::ItemFn;
+use quote::{format_ident, quote};
+use syn::{FnArg, GenericParam, ItemFn};
 
 /// Generate bindings for a single function.
 pub(crate) fn extendr_function(mut func: ItemFn, opts: &ExtendrOptions) -> TokenStream {
+    if opts.on_r_release.is_some() {
+        return syn::Error::new_spanned(
+            &func.sig.ident,
+            "`on_r_release` is only allowed in #[extendr] impl",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    if !opts.instances.is_empty() {
+        return match extendr_generic_function(func, opts) {
+            Ok(result) => result.into(),
+            Err(e) => e.into_compile_error().into(),
+        };
+    }
+
     let mut wrappers: Vec<ItemFn> = Vec::new();
 
     let res =
@@ -13,9 +32,225 @@ pub(crate) fn extendr_function(mut func: ItemFn, opts: &ExtendrOptions) -> Token
         return e.into_compile_error().into();
     };
 
+    // The metadata function is the wrapper named `meta__...`.
+    let registry_entry = wrappers
+        .iter()
+        .find(|wrapper| {
+            wrapper
+                .sig
+                .ident
+                .to_string()
+                .starts_with(wrappers::META_PREFIX)
+        })
+        .map(|meta| wrappers::registry_entry(&meta.sig.ident, "FUNCTIONS", "Func"));
+
     TokenStream::from(quote! {
         #func
 
         # ( #wrappers )*
+
+        #registry_entry
+    })
+}
+
+/// Generate bindings for the instances of a generic function, eg.
+/// `#[extendr(instances(i32, f64))] fn sum_of<T>(v: Vec<T>) -> T`.
+///
+/// Each instance `sum_of__i32` calls `sum_of::<i32>` and is wrapped as the R
+/// function `sum_of_i32`. The R function `sum_of` dispatches on the R type of
+/// the first argument whose type uses the type parameter.
+fn extendr_generic_function(
+    mut func: ItemFn,
+    opts: &ExtendrOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut type_params = func.sig.generics.type_params();
+    let param = match (type_params.next(), type_params.next()) {
+        (Some(param), None) => param.ident.clone(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &func.sig.generics,
+                "`instances` needs a function with one type parameter",
+            ))
+        }
+    };
+    if opts.external || opts.symbol.is_some() || opts.s4_method.is_some() {
+        return Err(syn::Error::new_spanned(
+            &func.sig.ident,
+            "`instances` can not be combined with `external`, `symbol` or `s4_method`",
+        ));
+    }
+
+    // Dispatch on the first argument whose type uses the type parameter.
+    let unit: syn::Type = syn::parse_quote!(());
+    let dispatch_arg = func
+        .sig
+        .inputs
+        .iter()
+        .find_map(|input| match input {
+            FnArg::Typed(pattype) => {
+                let mut ty = pattype.ty.as_ref().clone();
+                wrappers::substitute_type_param(&mut ty, &param, &unit).then_some(&pattype.pat)
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &func.sig.ident,
+                format!("`instances` needs an argument whose type uses `{}`", param),
+            )
+        })?;
+    let dispatch_arg = match dispatch_arg.as_ref() {
+        syn::Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+        pat => {
+            return Err(syn::Error::new_spanned(
+                pat,
+                "failed to translate name of argument",
+            ))
+        }
+    };
+
+    let generic_name = func.sig.ident.clone();
+    let r_name = opts
+        .r_name
+        .clone()
+        .unwrap_or_else(|| generic_name.to_string());
+
+    let mut wrappers: Vec<ItemFn> = Vec::new();
+    let mut instances = Vec::new();
+    let mut r_types: Vec<(&str, &syn::Type)> = Vec::new();
+    for ty in &opts.instances {
+        let r_type = wrappers::r_type_of(ty).ok_or_else(|| {
+            syn::Error::new_spanned(
+                ty,
+                "instances must be element types of R vectors, such as i32, f64, bool or String",
+            )
+        })?;
+        // The R function could only dispatch to one of them.
+        if let Some((_, other)) = r_types.iter().find(|(other, _)| *other == r_type) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "`{}` and `{}` are both {} vectors in R, so only one can be an instance",
+                    quote!(#other),
+                    quote!(#ty),
+                    r_type
+                ),
+            ));
+        }
+        r_types.push((r_type, ty));
+        let suffix = wrappers::type_name(ty);
+
+        // The signature of the instance, without the type parameter.
+        let mut sig = func.sig.clone();
+        sig.ident = format_ident!("{}__{}", generic_name, suffix);
+        sig.generics.params = sig
+            .generics
+            .params
+            .into_iter()
+            .filter(|param| matches!(param, GenericParam::Lifetime(_)))
+            .collect();
+        sig.generics.where_clause = None;
+        for input in sig.inputs.iter_mut() {
+            if let FnArg::Typed(pattype) = input {
+                wrappers::substitute_type_param(&mut pattype.ty, &param, ty);
+            }
+        }
+        if let syn::ReturnType::Type(_, ref mut output) = sig.output {
+            wrappers::substitute_type_param(output, &param, ty);
+        }
+
+        let instance_opts = ExtendrOptions {
+            r_name: Some(format!("{}_{}", r_name, suffix)),
+            mod_name: opts
+                .mod_name
+                .as_ref()
+                .map(|mod_name| format!("{}__{}", mod_name, suffix)),
+            instances: Vec::new(),
+            instance_of: Some(Instance {
+                generic: r_name.clone(),
+                arg: dispatch_arg.clone(),
+                r_type: r_type.to_string(),
+            }),
+            ..opts.clone()
+        };
+        wrappers::make_function_wrappers(
+            &instance_opts,
+            &mut wrappers,
+            "",
+            &func.attrs,
+            &mut sig,
+            None,
+        )?;
+
+        let args = sig.inputs.iter().filter_map(|input| match input {
+            FnArg::Typed(pattype) => match pattype.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        });
+        let call = if sig.asyncness.is_some() {
+            quote!(#generic_name::<#ty>(#(#args),*).await)
+        } else {
+            quote!(#generic_name::<#ty>(#(#args),*))
+        };
+        instances.push(quote! {
+            #[allow(non_snake_case)]
+            #sig {
+                #call
+            }
+        });
+    }
+
+    // Remove `#[default = "..."]` from the arguments of the generic function.
+    for input in func.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pattype) = input {
+            pattype
+                .attrs
+                .retain(|attr| !attr.path().is_ident("default"));
+        }
+    }
+
+    // The metadata function of the generic adds the metadata of the instances.
+    let meta_name = format_ident!("{}{}", wrappers::META_PREFIX, generic_name);
+    let instance_meta_names = wrappers
+        .iter()
+        .map(|wrapper| &wrapper.sig.ident)
+        .filter(|ident| ident.to_string().starts_with(wrappers::META_PREFIX));
+    let registry_entry = wrappers::registry_entry(&meta_name, "FUNCTIONS", "Func");
+
+    // With `conversion-bench`, convert the arguments for the first instance that accepts them.
+    let conversion_bench = if cfg!(feature = "conversion-bench") {
+        let conv_name = format_ident!("{}{}", wrappers::CONV_PREFIX, generic_name);
+        let instance_conv_names = wrappers
+            .iter()
+            .map(|wrapper| &wrapper.sig.ident)
+            .filter(|ident| ident.to_string().starts_with(wrappers::CONV_PREFIX));
+        quote! {
+            #[allow(non_snake_case)]
+            fn #conv_name(args: &[extendr_api::Robj]) -> extendr_api::Result<()> {
+                Err(extendr_api::Error::Other("no instance accepts these arguments".into()))
+                    #( .or_else(|_| #instance_conv_names(args)) )*
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        #func
+
+        #( #instances )*
+
+        #( #wrappers )*
+
+        #[allow(non_snake_case)]
+        fn #meta_name(metadata: &mut Vec<extendr_api::metadata::Func>) {
+            #( #instance_meta_names(metadata); )*
+        }
+
+        #registry_entry
+
+        #conversion_bench
     })
 }
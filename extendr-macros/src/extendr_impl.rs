@@ -134,6 +134,20 @@ pub(crate) fn extendr_impl(
         ));
     }
 
+    if opts.symbol.is_some() {
+        return Err(syn::Error::new_spanned(
+            item_impl,
+            "`symbol` is not allowed in #[extendr] impl, use `symbol_prefix`",
+        ));
+    }
+
+    if opts.impl_only && opts.on_r_release.is_some() {
+        return Err(syn::Error::new_spanned(
+            item_impl,
+            "`on_r_release` has no effect with `impl_only = true`, as the type is not wrapped",
+        ));
+    }
+
     let self_ty = item_impl.self_ty.as_ref();
     let self_ty_name = wrappers::type_name(self_ty);
     let prefix = format!("{}__", self_ty_name);
@@ -173,6 +187,7 @@ pub(crate) fn extendr_impl(
     }
 
     let meta_name = format_ident!("{}{}", wrappers::META_PREFIX, self_ty_name);
+    let registry_entry = wrappers::registry_entry(&meta_name, "IMPLS", "Impl");
 
     let conversion_impls = quote! {
         // Output conversion function for this type.
@@ -218,6 +233,23 @@ pub(crate) fn extendr_impl(
         }
     };
 
+    // With `on_r_release = "method"`, the finalizer calls `method` before dropping the value.
+    let (release_impl, new_external_ptr) = if let Some(method) = opts.on_r_release.as_ref() {
+        let method = format_ident!("{}", method);
+        (
+            quote! {
+                impl extendr_api::OnRRelease for #self_ty {
+                    fn on_r_release(&mut self) {
+                        self.#method();
+                    }
+                }
+            },
+            quote!(ExternalPtr::new_with_release(value)),
+        )
+    } else {
+        (quote!(), quote!(ExternalPtr::new(value)))
+    };
+
     let expanded = if opts.impl_only {
         TokenStream::from(quote! {
             // The impl itself copied from the source.
@@ -238,6 +270,8 @@ pub(crate) fn extendr_impl(
                     methods_only: true
                 });
             }
+
+            #registry_entry
         })
     } else {
         TokenStream::from(quote! {
@@ -249,12 +283,14 @@ pub(crate) fn extendr_impl(
 
             #conversion_impls
 
+            #release_impl
+
             // Output conversion function for this type.
             impl From<#self_ty> for Robj {
                 fn from(value: #self_ty) -> Self {
                     use extendr_api::ExternalPtr;
                     unsafe {
-                        let mut res: ExternalPtr<#self_ty> = ExternalPtr::new(value);
+                        let mut res: ExternalPtr<#self_ty> = #new_external_ptr;
                         res.set_attrib(class_symbol(), #self_ty_name).unwrap();
                         res.into()
                     }
@@ -272,6 +308,8 @@ pub(crate) fn extendr_impl(
                     methods_only: false
                 });
             }
+
+            #registry_entry
         })
     };
 
@@ -1,29 +1,77 @@
 use crate::wrappers;
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::{parse::ParseStream, parse_macro_input, Ident, Token, Type};
+use syn::{parse::ParseStream, parse_macro_input, Attribute, Ident, ItemFn, Token, Type};
 
 pub fn extendr_module(item: TokenStream) -> TokenStream {
     let module = parse_macro_input!(item as Module);
     let Module {
         modname,
         fnnames,
+        fnattrs,
         implnames,
+        implattrs,
         usenames,
+        useattrs,
+        init,
+        unload,
+        use_dynamic_symbols,
+        force_symbols,
+        panic_hook,
+        registry,
     } = module;
     let modname = modname.expect("cannot include unnamed modules");
     let modname_string = modname.to_string();
     let module_init_name = format_ident!("R_init_{}_extendr", modname);
+    let module_unload_name = format_ident!("R_unload_{}_extendr", modname);
+
+    let install_panic_hook = if panic_hook {
+        quote!(extendr_api::panic_hook::install();)
+    } else {
+        quote!()
+    };
+
+    // `fn __init__() { .. }` and `fn __unload__() { .. }` are defined locally
+    // within the hook runners and panics are turned into R errors.
+    let init_call = init.map(|init| {
+        quote! {
+            #[allow(non_snake_case)]
+            #init
+            extendr_api::handle_panic("extendr module __init__ panicked", __init__);
+        }
+    });
+    let unload_call = unload.map(|unload| {
+        quote! {
+            #[allow(non_snake_case)]
+            #unload
+            extendr_api::handle_panic("extendr module __unload__ panicked", __unload__);
+        }
+    });
+
+    // `registry = true;` adds the `#[extendr]` items of all linked crates.
+    let merge_registry = match (registry, cfg!(feature = "registry")) {
+        (true, true) => quote!(if let Err(e) =
+            extendr_api::registry::merge_into(&mut functions, &mut impls)
+        {
+            extendr_api::throw_r_error(e.to_string());
+        }),
+        (true, false) => quote!(compile_error!(
+            "`registry = true` needs the `registry` feature of extendr-api");),
+        (false, _) => quote!(),
+    };
 
     let module_metadata_name = format_ident!("get_{}_metadata", modname);
     let module_metadata_name_string = module_metadata_name.to_string();
     let wrap_module_metadata_name =
         format_ident!("{}get_{}_metadata", wrappers::WRAP_PREFIX, modname);
 
+    let wrap_module_metadata_name_string = wrap_module_metadata_name.to_string();
+
     let make_module_wrappers_name = format_ident!("make_{}_wrappers", modname);
     let make_module_wrappers_name_string = make_module_wrappers_name.to_string();
     let wrap_make_module_wrappers =
         format_ident!("{}make_{}_wrappers", wrappers::WRAP_PREFIX, modname);
+    let wrap_make_module_wrappers_string = wrap_make_module_wrappers.to_string();
 
     let fnmetanames = fnnames
         .iter()
@@ -31,11 +79,55 @@ pub fn extendr_module(item: TokenStream) -> TokenStream {
     let implmetanames = implnames
         .iter()
         .map(|id| format_ident!("{}{}", wrappers::META_PREFIX, wrappers::type_name(id)));
-    let usemetanames = usenames
+    // `use path::to::name;` refers to the module `name` defined in `path::to::name`.
+    let usemodnames = usenames
+        .iter()
+        .map(|path| path.segments.last().unwrap().ident.clone())
+        .collect::<Vec<Ident>>();
+    let usemetanames = usemodnames
         .iter()
         .map(|id| format_ident!("get_{}_metadata", id))
         .collect::<Vec<Ident>>();
 
+    // Hooks of `use`d modules are chained from the hooks of this module.
+    let init_hooks_name = format_ident!("run_{}_init_hooks", modname);
+    let unload_hooks_name = format_ident!("run_{}_unload_hooks", modname);
+    let use_init_hooks = usemodnames
+        .iter()
+        .map(|id| format_ident!("run_{}_init_hooks", id));
+    let use_unload_hooks = usemodnames
+        .iter()
+        .rev()
+        .map(|id| format_ident!("run_{}_unload_hooks", id));
+    let rev_usenames = usenames.iter().rev();
+    let rev_useattrs = useattrs.iter().rev();
+
+    // With `conversion-bench`, list the argument conversions of this and `use`d modules.
+    let conversion_benches = if cfg!(feature = "conversion-bench") {
+        let benches_name = format_ident!("{}_conversion_benches", modname);
+        let fnname_strings = fnnames.iter().map(|id| id.to_string());
+        let convnames = fnnames
+            .iter()
+            .map(|id| format_ident!("{}{}", wrappers::CONV_PREFIX, id));
+        let usebenchnames = usemodnames
+            .iter()
+            .map(|id| format_ident!("{}_conversion_benches", id));
+        quote! {
+            #[allow(non_snake_case)]
+            pub fn #benches_name() -> Vec<extendr_api::bench::ConversionBench> {
+                let mut benches = Vec::new();
+                #(
+                    #(#fnattrs)*
+                    benches.push(extendr_api::bench::ConversionBench { name: #fnname_strings, convert: #convnames });
+                )*
+                #( #(#useattrs)* benches.extend(#usenames::#usebenchnames()); )*
+                benches
+            }
+        }
+    } else {
+        quote!()
+    };
+
     TokenStream::from(quote! {
         #[no_mangle]
         #[allow(non_snake_case)]
@@ -44,12 +136,18 @@ pub fn extendr_module(item: TokenStream) -> TokenStream {
             let mut impls = Vec::new();
 
             // Pushes metadata (eg. extendr_api::metadata::Func) to functions and impl vectors.
-            #( #fnmetanames(&mut functions); )*
-            #( #implmetanames(&mut impls); )*
+            #( #(#fnattrs)* #fnmetanames(&mut functions); )*
+            #( #(#implattrs)* #implmetanames(&mut impls); )*
 
             // Extends functions and impls with the submodules metadata
-            #( functions.extend(#usenames::#usemetanames().functions); )*
-            #( impls.extend(#usenames::#usemetanames().impls); )*
+            #(
+                #(#useattrs)*
+                if let Err(e) = extendr_api::metadata::merge(&mut functions, &mut impls, #usenames::#usemetanames()) {
+                    extendr_api::throw_r_error(e.to_string());
+                }
+            )*
+
+            #merge_registry
 
             // Add this function to the list, but set hidden: true.
             functions.push(extendr_api::metadata::Func {
@@ -57,10 +155,14 @@ pub fn extendr_module(item: TokenStream) -> TokenStream {
                 rust_name: #module_metadata_name_string,
                 mod_name: #module_metadata_name_string,
                 r_name: #module_metadata_name_string,
+                symbol: #wrap_module_metadata_name_string,
                 args: Vec::new(),
                 return_type: "Metadata",
                 func_ptr: #wrap_module_metadata_name as * const u8,
                 hidden: true,
+                external: false,
+                dispatch: None,
+                s4_method: None,
             });
 
             // Add this function to the list, but set hidden: true.
@@ -69,13 +171,17 @@ pub fn extendr_module(item: TokenStream) -> TokenStream {
                 rust_name: #make_module_wrappers_name_string,
                 mod_name: #make_module_wrappers_name_string,
                 r_name: #make_module_wrappers_name_string,
+                symbol: #wrap_make_module_wrappers_string,
                 args: vec![
-                    extendr_api::metadata::Arg { name: "use_symbols", arg_type: "bool", default: None },
-                    extendr_api::metadata::Arg { name: "package_name", arg_type: "&str", default: None },
+                    extendr_api::metadata::Arg { name: "use_symbols", arg_type: "bool", default: None, check: None, validate: None },
+                    extendr_api::metadata::Arg { name: "package_name", arg_type: "&str", default: None, check: None, validate: None },
                     ],
                 return_type: "String",
                 func_ptr: #wrap_make_module_wrappers as * const u8,
                 hidden: true,
+                external: false,
+                dispatch: None,
+                s4_method: None,
             });
 
             extendr_api::metadata::Metadata {
@@ -120,7 +226,47 @@ pub fn extendr_module(item: TokenStream) -> TokenStream {
         #[no_mangle]
         #[allow(non_snake_case, clippy::not_unsafe_ptr_arg_deref)]
         pub extern "C" fn #module_init_name(info: * mut extendr_api::DllInfo) {
-            unsafe { extendr_api::register_call_methods(info, #module_metadata_name()) };
+            #install_panic_hook
+            unsafe {
+                extendr_api::register_call_methods_with_options(
+                    info,
+                    #module_metadata_name(),
+                    #use_dynamic_symbols,
+                    #force_symbols,
+                )
+            };
+            extendr_api::package::init_package(#modname_string);
+            #init_hooks_name();
+        }
+
+        #conversion_benches
+
+        #[no_mangle]
+        #[allow(non_snake_case, clippy::not_unsafe_ptr_arg_deref)]
+        pub extern "C" fn #module_unload_name(_info: * mut extendr_api::DllInfo) {
+            #unload_hooks_name();
+        }
+
+        /// Run the `__init__` hooks of the `use`d modules, then of this module.
+        /// Each module's hooks run once, even if it is used by several modules.
+        #[allow(non_snake_case)]
+        pub fn #init_hooks_name() {
+            static DONE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+            if !DONE.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                #( #(#useattrs)* #usenames::#use_init_hooks(); )*
+                #init_call
+            }
+        }
+
+        /// Run the `__unload__` hook of this module, then of the `use`d modules
+        /// in reverse order.
+        #[allow(non_snake_case)]
+        pub fn #unload_hooks_name() {
+            static DONE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+            if !DONE.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                #unload_call
+                #( #(#rev_useattrs)* #rev_usenames::#use_unload_hooks(); )*
+            }
         }
     })
 }
@@ -129,8 +275,17 @@ pub fn extendr_module(item: TokenStream) -> TokenStream {
 struct Module {
     modname: Option<Ident>,
     fnnames: Vec<Ident>,
+    fnattrs: Vec<Vec<Attribute>>,
     implnames: Vec<Type>,
-    usenames: Vec<Ident>,
+    implattrs: Vec<Vec<Attribute>>,
+    usenames: Vec<syn::Path>,
+    useattrs: Vec<Vec<Attribute>>,
+    init: Option<ItemFn>,
+    unload: Option<ItemFn>,
+    use_dynamic_symbols: bool,
+    force_symbols: bool,
+    panic_hook: bool,
+    registry: bool,
 }
 
 // Custom parser for the module.
@@ -140,10 +295,62 @@ impl syn::parse::Parse for Module {
         let mut res = Self {
             modname: None,
             fnnames: Vec::new(),
+            fnattrs: Vec::new(),
             implnames: Vec::new(),
+            implattrs: Vec::new(),
             usenames: Vec::new(),
+            useattrs: Vec::new(),
+            init: None,
+            unload: None,
+            use_dynamic_symbols: false,
+            force_symbols: false,
+            panic_hook: false,
+            registry: false,
         };
         while !input.is_empty() {
+            // `fn`, `impl` and `use` entries may be conditional, eg. `#[cfg(feature = "gpu")]`.
+            let attrs = input.call(Attribute::parse_outer)?;
+            for attr in &attrs {
+                if !attr.path().is_ident("cfg") {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        "only #[cfg(...)] is supported in extendr_module!",
+                    ));
+                }
+            }
+            let is_entry =
+                input.peek(Token![fn]) || input.peek(Token![impl]) || input.peek(Token![use]);
+            if !attrs.is_empty() && (!is_entry || peek_hook(input).is_some()) {
+                return Err(syn::Error::new(
+                    attrs[0].span(),
+                    "#[cfg(...)] is only supported on fn, impl and use entries",
+                ));
+            }
+
+            // `fn __init__() { .. }` or `fn __unload__() { .. }` hooks have a body.
+            if let Some(hook) = peek_hook(input) {
+                let func: ItemFn = input.parse()?;
+                if !func.sig.inputs.is_empty() {
+                    return Err(syn::Error::new(
+                        func.sig.inputs.span(),
+                        "module hooks take no arguments",
+                    ));
+                }
+                let slot = if hook == "__init__" {
+                    &mut res.init
+                } else {
+                    &mut res.unload
+                };
+                if slot.is_some() {
+                    return Err(syn::Error::new(
+                        func.sig.ident.span(),
+                        format!("only one {} allowed", hook),
+                    ));
+                }
+                *slot = Some(func);
+                continue;
+            }
+
             if let Ok(kmod) = input.parse::<Token![mod]>() {
                 let name: Ident = input.parse()?;
                 if res.modname.is_some() {
@@ -152,12 +359,37 @@ impl syn::parse::Parse for Module {
                 res.modname = Some(name);
             } else if input.parse::<Token![fn]>().is_ok() {
                 res.fnnames.push(input.parse()?);
+                res.fnattrs.push(attrs);
             } else if input.parse::<Token![impl]>().is_ok() {
                 res.implnames.push(input.parse()?);
+                res.implattrs.push(attrs);
             } else if input.parse::<Token![use]>().is_ok() {
-                res.usenames.push(input.parse()?);
+                // `use name;` or `use mod path::to::name;`
+                let _ = input.parse::<Option<Token![mod]>>()?;
+                res.usenames.push(input.call(syn::Path::parse_mod_style)?);
+                res.useattrs.push(attrs);
+            } else if input.peek(Ident) && input.peek2(Token![=]) {
+                // Registration options, eg. `use_dynamic_symbols = false;`
+                let name: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                let value: syn::LitBool = input.parse()?;
+                match name.to_string().as_str() {
+                    "use_dynamic_symbols" => res.use_dynamic_symbols = value.value,
+                    "force_symbols" => res.force_symbols = value.value,
+                    "panic_hook" => res.panic_hook = value.value,
+                    "registry" => res.registry = value.value,
+                    _ => {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            "expected use_dynamic_symbols, force_symbols, panic_hook or registry",
+                        ))
+                    }
+                }
             } else {
-                return Err(syn::Error::new(input.span(), "expected mod, fn or impl"));
+                return Err(syn::Error::new(
+                    input.span(),
+                    "expected mod, fn, impl or use",
+                ));
             }
 
             input.parse::<Token![;]>()?;
@@ -168,3 +400,16 @@ impl syn::parse::Parse for Module {
         Ok(res)
     }
 }
+
+// Check for `fn __init__` or `fn __unload__` without consuming any input.
+fn peek_hook(input: ParseStream) -> Option<String> {
+    let fork = input.fork();
+    fork.parse::<Token![fn]>().ok()?;
+    let name: Ident = fork.parse().ok()?;
+    let name = name.to_string();
+    if (name == "__init__" || name == "__unload__") && fork.peek(syn::token::Paren) {
+        Some(name)
+    } else {
+        None
+    }
+}
@@ -1,11 +1,35 @@
-use syn::{meta::ParseNestedMeta, Lit, LitBool};
+use syn::{meta::ParseNestedMeta, parse::Parse, Lit, LitBool, Token, Type};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct ExtendrOptions {
     pub r_name: Option<String>,
     pub mod_name: Option<String>,
     pub use_rng: bool,
     pub impl_only: bool,
+    pub on_r_release: Option<String>,
+    pub external: bool,
+    pub min_r: Option<String>,
+    pub check_args: bool,
+    pub symbol: Option<String>,
+    pub symbol_prefix: Option<String>,
+    pub instances: Vec<Type>,
+    /// Conditions on arguments, as pairs of the argument name and an R expression.
+    pub validate: Vec<(syn::Ident, String)>,
+    /// The generic and signature of `s4_method(generic = "show", signature = "MyClass")`.
+    pub s4_method: Option<(String, String)>,
+    /// Set for the wrappers of the instances of a generic function, not parsed.
+    pub instance_of: Option<Instance>,
+}
+
+/// An instance of a generic function, which its R function dispatches to.
+#[derive(Debug, Clone)]
+pub(crate) struct Instance {
+    /// The R name of the generic function.
+    pub generic: String,
+    /// The argument whose R type selects the instance.
+    pub arg: String,
+    /// The R type of that argument, as given by `typeof()`.
+    pub r_type: String,
 }
 
 impl ExtendrOptions {
@@ -16,8 +40,85 @@ impl ExtendrOptions {
     /// - `r_name = "name"` which specifies the name of the wrapper on the R-side.
     /// - `use_rng = bool` ensures the RNG-state is pulled and pushed
     /// - `impl_only = bool` create wrappers for impl functions only
+    /// - `on_r_release = "method"` calls `&mut self` method when R finalizes the object (impl only)
+    /// - `external` or `external = bool` generates a `.External` wrapper (functions only)
+    /// - `min_r = "4.2"` raises an R error if R is older than this version
+    /// - `check_args = bool` checks the types of arguments in the generated R wrapper
+    /// - `symbol = "name"` sets the exact name of the C wrapper (functions only)
+    /// - `symbol_prefix = "prefix_"` replaces the `wrap__` prefix of the C wrappers
+    /// - `instances(i32, f64)` generates a wrapper for each type of a generic function
+    /// - `validate(x = "x > 0")` checks that R conditions on arguments are true in the R wrapper
+    /// - `s4_method(generic = "show", signature = "MyClass")` registers the function as an S4 method
     ///
     pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::parse::Result<()> {
+        // `external` may be given without a value.
+        if meta.path.is_ident("external") && !meta.input.peek(syn::Token![=]) {
+            self.external = true;
+            return Ok(());
+        }
+
+        // `instances(i32, f64)` lists types rather than a value.
+        if meta.path.is_ident("instances") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            let types = content.parse_terminated(Type::parse, Token![,])?;
+            if types.is_empty() {
+                return Err(meta.error("`instances` must list at least one type"));
+            }
+            self.instances = types.into_iter().collect();
+            return Ok(());
+        }
+
+        // `validate(x = "x > 0", ...)` lists conditions on arguments.
+        if meta.path.is_ident("validate") {
+            return meta.parse_nested_meta(|arg| {
+                let name = arg
+                    .path
+                    .get_ident()
+                    .ok_or_else(|| arg.error("expected the name of an argument"))?
+                    .clone();
+                if self.validate.iter().any(|(other, _)| *other == name) {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        format!("`{}` has more than one condition in `validate`", name),
+                    ));
+                }
+                match arg.value()?.parse() {
+                    Ok(Lit::Str(condition)) => {
+                        self.validate.push((name, condition.value()));
+                        Ok(())
+                    }
+                    _ => Err(arg.error("the condition must be a string literal of R code")),
+                }
+            });
+        }
+
+        // `s4_method(generic = "show", signature = "MyClass")`
+        if meta.path.is_ident("s4_method") {
+            let (mut generic, mut signature) = (None, None);
+            meta.parse_nested_meta(|item| {
+                let value = match item.value()?.parse() {
+                    Ok(Lit::Str(litstr)) => litstr.value(),
+                    _ => return Err(item.error("expected a string literal")),
+                };
+                if item.path.is_ident("generic") {
+                    generic = Some(value);
+                } else if item.path.is_ident("signature") {
+                    signature = Some(value);
+                } else {
+                    return Err(item.error("expected `generic` or `signature`"));
+                }
+                Ok(())
+            })?;
+            return match (generic, signature) {
+                (Some(generic), Some(signature)) if !signature.trim().is_empty() => {
+                    self.s4_method = Some((generic, signature));
+                    Ok(())
+                }
+                _ => Err(meta.error("`s4_method` needs a `generic` and a `signature`")),
+            };
+        }
+
         let value = meta.value()?;
         let path = meta
             .path
@@ -57,7 +158,109 @@ impl ExtendrOptions {
                     Err(value.error("`impl_only` must be `true` or `false`"))
                 }
             }
+            "on_r_release" => {
+                if let Ok(Lit::Str(litstr)) = value.parse() {
+                    self.on_r_release = Some(litstr.value());
+                    Ok(())
+                } else {
+                    Err(value.error("`on_r_release` must be a string literal"))
+                }
+            }
+            "external" => {
+                if let Ok(LitBool { value, .. }) = value.parse() {
+                    self.external = value;
+                    Ok(())
+                } else {
+                    Err(value.error("`external` must be `true` or `false`"))
+                }
+            }
+            "check_args" => {
+                if let Ok(LitBool { value, .. }) = value.parse() {
+                    self.check_args = value;
+                    Ok(())
+                } else {
+                    Err(value.error("`check_args` must be `true` or `false`"))
+                }
+            }
+            "symbol" => {
+                self.symbol = Some(parse_symbol(value, "symbol")?);
+                Ok(())
+            }
+            "symbol_prefix" => {
+                self.symbol_prefix = Some(parse_symbol(value, "symbol_prefix")?);
+                Ok(())
+            }
+            "min_r" => {
+                let version = match value.parse() {
+                    Ok(Lit::Str(litstr)) => litstr,
+                    _ => return Err(value.error("`min_r` must be a string literal")),
+                };
+                let parts = version
+                    .value()
+                    .split('.')
+                    .map(|part| part.parse::<u32>().ok())
+                    .collect::<Option<Vec<_>>>();
+                if matches!(parts, Some(parts) if parts.len() == 2 || parts.len() == 3) {
+                    self.min_r = Some(version.value());
+                    Ok(())
+                } else {
+                    Err(syn::Error::new_spanned(
+                        version,
+                        "`min_r` must be a version such as \"4.2\" or \"4.2.1\"",
+                    ))
+                }
+            }
             _ => Err(syn::Error::new_spanned(meta.path, "Unexpected key")),
         }
     }
 }
+
+// The reserved words of R (see `?Reserved`), which can not name a symbol.
+const R_RESERVED_WORDS: &[&str] = &[
+    "if",
+    "else",
+    "repeat",
+    "while",
+    "function",
+    "for",
+    "in",
+    "next",
+    "break",
+    "TRUE",
+    "FALSE",
+    "NULL",
+    "Inf",
+    "NaN",
+    "NA",
+    "NA_integer_",
+    "NA_real_",
+    "NA_character_",
+    "NA_complex_",
+];
+
+// Parse a string literal that must be a valid C identifier and not an R keyword.
+fn parse_symbol(value: syn::parse::ParseStream, key: &str) -> syn::Result<String> {
+    let symbol = match value.parse() {
+        Ok(Lit::Str(litstr)) => litstr,
+        _ => return Err(value.error(format!("`{}` must be a string literal", key))),
+    };
+    let name = symbol.value();
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        Err(syn::Error::new_spanned(
+            symbol,
+            format!("`{}` must be a valid C identifier", key),
+        ))
+    } else if R_RESERVED_WORDS.contains(&name.as_str()) {
+        Err(syn::Error::new_spanned(
+            symbol,
+            format!("`{}` must not be a reserved word of R", key),
+        ))
+    } else {
+        Ok(name)
+    }
+}
@@ -83,6 +83,120 @@ use syn::{parse_macro_input, Item};
 /// There is also [`macro@extendr_module`], which is used for defining what rust
 /// wrapped items should be visible to the surrounding R-package.
 ///
+/// Functions with `#[extendr(external)]` use the `.External` interface instead of
+/// `.Call`: they take a single argument that receives all the arguments of the R
+/// call as a pairlist, so they can be called with any number of arguments.
+///```dont_run
+/// #[extendr(external)]
+/// fn count_args(args: Pairlist) -> usize {
+///     args.len()
+/// }
+/// ```
+///
+/// An `async fn` returns a promise of the `promises` package, which is settled
+/// once the future completes on the R event loop. Its arguments must be owned.
+///```dont_run
+/// #[extendr]
+/// async fn add_later(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+/// ```
+///
+/// Arguments of type `&[T]` and `&[T; N]`, for the element types of R vectors
+/// such as `f64`, `i32` or `Rint`, borrow the data of the R vector without
+/// copying it. With `&mut [T]` and `&mut [T; N]`, the vector is copied first if
+/// it may be bound to a variable, so that changes are not visible from R.
+///```dont_run
+/// #[extendr]
+/// fn normalize(values: &mut [f64]) -> f64 {
+///     let total: f64 = values.iter().sum();
+///     values.iter_mut().for_each(|x| *x /= total);
+///     values.iter().map(|x| x * x).sum()
+/// }
+/// ```
+///
+/// Arguments that are tuples, such as `(f64, f64)`, accept an atomic vector or a
+/// list of that length, and arrays, such as `[f64; 3]`, a vector of that length;
+/// other lengths are an error.
+///```dont_run
+/// #[extendr]
+/// fn distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+///     (to.0 - from.0).hypot(to.1 - from.1)
+/// }
+/// ```
+///
+/// A function returning `impl Iterator<Item = T>` is collected into an R vector,
+/// or a list if the items are `Robj`, when it is called from R.
+///```dont_run
+/// #[extendr]
+/// fn squares(n: i32) -> impl Iterator<Item = i32> {
+///     (1..=n).map(|i| i * i)
+/// }
+/// ```
+///
+/// `#[extendr(min_r = "4.2")]` raises an R error when the function is called
+/// from a version of R older than 4.2, before its arguments are converted.
+///```dont_run
+/// #[extendr(min_r = "4.2")]
+/// fn needs_new_r() {}
+/// ```
+///
+/// The C wrapper of a function is called `wrap__{name}`. To avoid clashes
+/// when several crates are linked into one package, `#[extendr(symbol_prefix = "mypkg_")]`
+/// replaces the `wrap__` prefix, and on functions `#[extendr(symbol = "C_name")]`
+/// sets the whole name, for example to keep the name of an existing `.Call` routine.
+///```dont_run
+/// #[extendr(symbol = "C_legacy_add")]
+/// fn add(x: i32, y: i32) -> i32 {
+///     x + y
+/// }
+/// ```
+///
+/// A generic function with one type parameter is exported with
+/// `#[extendr(instances(...))]`, which wraps an instance for each listed type,
+/// e.g. `sum_of_i32` and `sum_of_f64` in R, and generates the R function
+/// `sum_of`, which calls the instance for the type of the first argument that
+/// uses the type parameter (`integer` for `i32`, `double` for `f64` etc.).
+///```dont_run
+/// #[extendr(instances(i32, f64))]
+/// fn sum_of<T: Copy + std::iter::Sum<T>>(v: Vec<T>) -> T {
+///     v.into_iter().sum()
+/// }
+/// ```
+///
+/// With `#[extendr(check_args = true)]`, the generated R wrapper checks that
+/// arguments of common types, such as `f64`, `&str`, `Vec<i32>` or `List`,
+/// are the right kind of R object before calling Rust, and gives an R error
+/// that names the argument otherwise.
+///```dont_run
+/// #[extendr(check_args = true)]
+/// fn repeat_string(s: &str, times: usize) -> String {
+///     s.repeat(times)
+/// }
+/// ```
+///
+/// `#[extendr(validate(...))]` gives R conditions on arguments, which the
+/// generated R wrapper checks after the kinds of the arguments, raising an R
+/// error such as ``"`n` must satisfy `n > 0`"`` unless a condition is `TRUE`.
+///```dont_run
+/// #[extendr(validate(probs = "all(probs >= 0 & probs <= 1)", n = "n > 0"))]
+/// fn expected_counts(probs: &[f64], n: i32) -> Vec<f64> {
+///     probs.iter().map(|p| p * n as f64).collect()
+/// }
+/// ```
+///
+/// `#[extendr(s4_method(generic = "show", signature = "MyClass"))]` registers the
+/// function as an S4 method of the generic when the R wrappers are evaluated,
+/// with `methods::setMethod()`. Classes of the signature, which may list several
+/// separated by commas, that are not defined are declared with `setOldClass()`.
+/// The generic must be exported with `exportMethods()` in the `NAMESPACE`.
+///```dont_run
+/// #[extendr(s4_method(generic = "show", signature = "Counter"))]
+/// fn show_counter(object: &Counter) {
+///     rprintln!("<Counter {}>", object.count);
+/// }
+/// ```
+///
 #[proc_macro_attribute]
 pub fn extendr(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut opts = extendr_options::ExtendrOptions::default();
@@ -110,6 +224,79 @@ pub fn extendr(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     impl MyTrait;
 /// }
 /// ```
+///
+/// Modules can be split across files or crates. `use mod path::to::name;` includes
+/// the module `name` defined by `extendr_module!` in `path::to::name` (`use name;`
+/// is short for `use mod name;`). Its functions and impls, and those of the modules it
+/// includes, are registered by this module; items included twice are registered once.
+///```dont_run
+/// extendr_module! {
+///     mod name;
+///     use mod parsers::csv;
+///     use mod other_crate::exports;
+/// }
+/// ```
+///
+/// `fn`, `impl` and `use` entries may be conditional with `#[cfg(...)]`, which
+/// excludes them from registration and the generated R wrappers when the condition
+/// does not hold:
+///```dont_run
+/// extendr_module! {
+///     mod name;
+///     fn my_func1;
+///     #[cfg(feature = "gpu")]
+///     fn gpu_func;
+/// }
+/// ```
+///
+/// The symbol lookup of the package library can be restricted, as CRAN
+/// requires for release packages, with options that are passed on to
+/// `R_useDynamicSymbols` and `R_forceSymbols` (both default to `false`):
+///```dont_run
+/// extendr_module! {
+///     mod name;
+///     use_dynamic_symbols = false;
+///     force_symbols = true;
+///     fn my_func1;
+/// }
+/// ```
+///
+/// A module may also declare hooks with a body. `__init__` runs at the end of
+/// `R_init_<name>_extendr`, and `__unload__` runs in `R_unload_<name>_extendr`,
+/// which R calls when the package library is unloaded (e.g. `library.dynam.unload()` in `.onUnload`).
+/// Forward `R_unload_<pkg>` to it from `entrypoint.c` in the same way as `R_init_<pkg>`.
+///```dont_run
+/// extendr_module! {
+///     mod name;
+///     fn my_func1;
+///
+///     fn __init__() {
+///         setup_logger();
+///     }
+///
+///     fn __unload__() {
+///         flush_logger();
+///     }
+/// }
+/// ```
+///
+/// With the `registry` feature of extendr-api, `registry = true;` also registers
+/// the `#[extendr]` functions and impls of every crate linked into the package,
+/// for packages whose Rust side is a workspace of several crates
+/// (see `extendr_api::registry`):
+///```dont_run
+/// use parsers as _;
+///
+/// extendr_module! {
+///     mod name;
+///     registry = true;
+/// }
+/// ```
+///
+/// With `panic_hook = true;`, the extendr panic hook (see `extendr_api::panic_hook`)
+/// is installed when the library is loaded: panics in exported functions are reported
+/// in the R error with the function name, its arguments and the panic location, instead of on stderr.
+/// Set `EXTENDR_PANIC_LOG` to a file path to also log them with a Rust backtrace.
 /// Outputs:
 ///
 /// ```dont_run
@@ -139,6 +326,13 @@ pub fn pairlist(item: TokenStream) -> TokenStream {
 /// ```ignore
 ///     assert_eq!(list!(a=1, 2, 3), List::from_pairs(&[("a", 1), ("", 2), ("", 3)]));
 /// ```
+///
+/// An entry `!!x` splices the elements of the list or vector `x`
+/// into the new list, keeping their names.
+/// ```ignore
+///     let other = list!(c=3, 4);
+///     assert_eq!(list!(1, b=2, !!other), list!(1, b=2, c=3, 4));
+/// ```
 #[proc_macro]
 pub fn list(item: TokenStream) -> TokenStream {
     list::list(item)
@@ -152,6 +346,20 @@ pub fn list(item: TokenStream) -> TokenStream {
 ///     assert_eq!(call!("`+`", 1, 2), r!(3));
 ///     assert_eq!(call!("list", 1, 2), r!([r!(1), r!(2)]));
 /// ```
+///
+/// Arguments are passed as R passes them:
+///
+/// - `name? = x` passes `name = x` if the `Option` `x` is `Some`, and leaves it
+///   out if it is `None`, so that the function uses its default.
+/// - `!!x` splices the elements of the list, pairlist or vector `x` as arguments,
+///   keeping their names.
+/// - `...` forwards the arguments matched to `...` in the current R environment.
+///
+/// ```ignore
+///     let digits: Option<i32> = None;
+///     assert_eq!(call!("round", 1.25, digits? = digits), r!(1.0));
+///     assert_eq!(call!("round", !!list!(1.25, digits = 1)), r!(1.2));
+/// ```
 #[proc_macro]
 pub fn call(item: TokenStream) -> TokenStream {
     call::call(item)
@@ -220,6 +428,27 @@ pub fn Rraw(item: TokenStream) -> TokenStream {
 /// See [`IntoRobj`] for converting arbitrary Rust types into R type by using
 /// R's list / `List`.
 ///
+/// # Enums
+/// On an enum, each variant must hold one value or be a unit variant. The
+/// variants are tried in order, and the first whose value converts from the R
+/// object is used, with unit variants matching `NULL`. This allows arguments
+/// of exported functions that are eg. "a string or a function" or "a number or `NULL`":
+/// ```ignore
+/// #[derive(TryFromRobj)]
+/// enum Callback {
+///     Name(String),
+///     Func(Function),
+/// }
+///
+/// #[derive(TryFromRobj)]
+/// enum Limit {
+///     Value(f64),
+///     Unlimited,
+/// }
+///
+/// #[extendr]
+/// fn run(callback: Callback, limit: Limit) { }
+/// ```
 #[proc_macro_derive(TryFromRobj)]
 pub fn derive_try_from_robj(item: TokenStream) -> TokenStream {
     match list_struct::derive_try_from_robj(item) {
@@ -260,6 +489,9 @@ pub fn derive_try_from_robj(item: TokenStream) -> TokenStream {
 /// instantiation of a rust type, by an R list with fields corresponding to
 /// said type.
 ///
+/// On an enum, each variant must hold one value or be a unit variant, which
+/// converts to `NULL`, and the value of the variant is converted.
+///
 /// # Details
 ///
 /// Note, the `From<Struct> for Robj` behaviour is different from what is obtained by applying the standard `#[extendr]` macro
@@ -327,11 +559,18 @@ pub fn impl_try_from_robj_tuples(input: TokenStream) -> TokenStream {
     TokenStream::from_iter((start..=end).map(|n| {
         let types: Vec<_> = (0..n).map(|i| quote::format_ident!("T{}", i)).collect();
         let indices = 0..n;
-        let element_extraction = indices.map(|idx| {
+        let element_extraction = indices.clone().map(|idx| {
             quote! {
                 (&list.elt(#idx)?).try_into()?
             }
         });
+        // elements of atomic vectors, with R's 1-based `[[`
+        let vector_extraction = indices.map(|idx| {
+            let r_idx = idx as i32 + 1;
+            quote! {
+                (&robj.index(#r_idx)?).try_into()?
+            }
+        });
 
         TokenStream::from(quote! {
             impl<#(#types),*> TryFrom<&Robj> for (#(#types,)*)
@@ -341,12 +580,20 @@ pub fn impl_try_from_robj_tuples(input: TokenStream) -> TokenStream {
                 type Error = Error;
 
                 fn try_from(robj: &Robj) -> Result<Self> {
+                    if robj.is_vector_atomic() {
+                        if robj.len() != #n {
+                            return Err(Error::ExpectedLength(#n));
+                        }
+                        return Ok((
+                            #(#vector_extraction,)*
+                        ));
+                    }
                     let list: List = robj.try_into()?;
                     if list.len() != #n {
                         return Err(Error::ExpectedLength(#n));
                     }
                     Ok((
-                        #(#element_extraction),*
+                        #(#element_extraction,)*
                     ))
                 }
             }
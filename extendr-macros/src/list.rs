@@ -1,9 +1,29 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::parse_macro_input;
+use syn::{parse_macro_input, Expr, ExprUnary, UnOp};
 
 use crate::pairs::Pairs;
 
+// If `expr` is `!!inner`, return `inner`, which is spliced into the list.
+pub(crate) fn spliced(expr: &Expr) -> Option<&Expr> {
+    if let Expr::Unary(ExprUnary {
+        op: UnOp::Not(_),
+        expr,
+        ..
+    }) = expr
+    {
+        if let Expr::Unary(ExprUnary {
+            op: UnOp::Not(_),
+            expr,
+            ..
+        }) = &**expr
+        {
+            return Some(expr);
+        }
+    }
+    None
+}
+
 pub fn list(item: TokenStream) -> TokenStream {
     let list = parse_macro_input!(item as Pairs);
 
@@ -11,6 +31,21 @@ pub fn list(item: TokenStream) -> TokenStream {
 
     if nv.is_empty() {
         TokenStream::from(quote!(extendr_api::wrapper::List::default()))
+    } else if nv.iter().any(|(n, v)| n.is_empty() && spliced(v).is_some()) {
+        let entries: Vec<proc_macro2::TokenStream> = nv
+            .iter()
+            .map(|(n, v)| match spliced(v) {
+                Some(inner) if n.is_empty() => quote!(
+                    extendr_api::wrapper::list::ListEntry::Splice(extendr_api::Robj::from(#inner))
+                ),
+                _ => quote!(
+                    extendr_api::wrapper::list::ListEntry::Value(#n, extendr_api::Robj::from(#v))
+                ),
+            })
+            .collect();
+        TokenStream::from(quote!(extendr_api::wrapper::list::list_from_entries(
+            vec![# ( #entries ),*]
+        )))
     } else {
         let values: Vec<proc_macro2::TokenStream> = nv
             .iter()
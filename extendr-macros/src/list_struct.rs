@@ -1,16 +1,126 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{Data, DeriveInput};
+use syn::{Data, DataEnum, DeriveInput, Fields, Ident};
+
+// A variant of an enum: one that holds a single value, or a unit variant for `NULL`.
+fn enum_variants(data: &DataEnum) -> syn::parse::Result<Vec<(&Ident, bool)>> {
+    data.variants
+        .iter()
+        .map(|variant| match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok((&variant.ident, true)),
+            Fields::Unit => Ok((&variant.ident, false)),
+            _ => Err(syn::Error::new_spanned(
+                variant,
+                "Only variants with one unnamed field, or unit variants, are supported",
+            )),
+        })
+        .collect()
+}
+
+/// Implementation of the TryFromRobj macro for enums, which tries each variant in turn.
+fn derive_try_from_robj_enum(
+    enum_name: &Ident,
+    data: &DataEnum,
+) -> syn::parse::Result<TokenStream> {
+    let variants = enum_variants(data)?;
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            enum_name,
+            "The enum must have variants",
+        ));
+    }
+
+    let attempts = variants.iter().map(|(variant, has_value)| {
+        let variant_str = variant.to_string();
+        if *has_value {
+            quote!(
+                match value.clone().try_into() {
+                    Ok(inner) => return Ok(#enum_name::#variant(inner)),
+                    Err(e) => errors.push(format!("{}: {}", #variant_str, e)),
+                }
+            )
+        } else {
+            quote!(
+                if value.is_null() {
+                    return Ok(#enum_name::#variant);
+                }
+                errors.push(format!("{}: expected NULL", #variant_str));
+            )
+        }
+    });
+    let enum_str = enum_name.to_string();
+
+    Ok(TokenStream::from(quote!(
+        impl std::convert::TryFrom<&extendr_api::Robj> for #enum_name {
+            type Error = extendr_api::Error;
+
+            fn try_from(value: &extendr_api::Robj) -> extendr_api::Result<Self> {
+                let mut errors: Vec<String> = Vec::new();
+                #(#attempts)*
+                Err(extendr_api::Error::Other(format!(
+                    "could not convert to `{}`: {}",
+                    #enum_str,
+                    errors.join("; ")
+                )))
+            }
+        }
+
+        impl std::convert::TryFrom<extendr_api::Robj> for #enum_name {
+            type Error = extendr_api::Error;
+
+            fn try_from(value: extendr_api::Robj) -> extendr_api::Result<Self> {
+                Self::try_from(&value)
+            }
+        }
+    )))
+}
+
+/// Implementation of the IntoRobj macro for enums, which converts the value of the variant.
+fn derive_into_robj_enum(enum_name: &Ident, data: &DataEnum) -> syn::parse::Result<TokenStream> {
+    let variants = enum_variants(data)?;
+    let arms: Vec<TokenStream2> = variants
+        .iter()
+        .map(|(variant, has_value)| {
+            if *has_value {
+                quote!(#enum_name::#variant(inner) => inner.into())
+            } else {
+                quote!(#enum_name::#variant => extendr_api::Robj::from(()))
+            }
+        })
+        .collect();
+
+    Ok(TokenStream::from(quote!(
+        impl std::convert::From<&#enum_name> for extendr_api::Robj {
+            fn from(value: &#enum_name) -> Self {
+                match value {
+                    #(#arms),*
+                }
+            }
+        }
+        impl std::convert::From<#enum_name> for extendr_api::Robj {
+            fn from(value: #enum_name) -> Self {
+                match value {
+                    #(#arms),*
+                }
+            }
+        }
+    )))
+}
 
 /// Implementation of the TryFromRobj macro. Refer to the documentation there
 pub fn derive_try_from_robj(item: TokenStream) -> syn::parse::Result<TokenStream> {
     // Parse the tokens into a Struct
     let ast = syn::parse::<DeriveInput>(item)?;
-    let inside = if let Data::Struct(inner) = ast.data {
-        inner
-    } else {
-        return Err(syn::Error::new_spanned(ast, "Only struct is supported"));
+    let inside = match ast.data {
+        Data::Struct(inner) => inner,
+        Data::Enum(inner) => return derive_try_from_robj_enum(&ast.ident, &inner),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ast,
+                "Only struct and enum are supported",
+            ))
+        }
     };
     let struct_name = ast.ident;
 
@@ -53,10 +163,15 @@ pub fn derive_try_from_robj(item: TokenStream) -> syn::parse::Result<TokenStream
 pub fn derive_into_robj(item: TokenStream) -> syn::parse::Result<TokenStream> {
     // Parse the tokens into a Struct
     let ast = syn::parse::<DeriveInput>(item)?;
-    let inside = if let Data::Struct(inner) = ast.data {
-        inner
-    } else {
-        return Err(syn::Error::new_spanned(ast, "Only struct is supported"));
+    let inside = match ast.data {
+        Data::Struct(inner) => inner,
+        Data::Enum(inner) => return derive_into_robj_enum(&ast.ident, &inner),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ast,
+                "Only struct and enum are supported",
+            ))
+        }
     };
     let struct_name = ast.ident;
 
@@ -20,6 +20,11 @@
 //! See [`WRAP_PREFIX`], and note that `prefix` is set specifically for methods in
 //! `extendr`-impl blocks, while for functions have no prefix.
 //!
+//! `#[extendr(symbol_prefix = "...")]` replaces [`WRAP_PREFIX`], to avoid clashes
+//! between crates linked into the same package, and `#[extendr(symbol = "...")]`
+//! sets the whole name of a function's wrapper, e.g. to keep a legacy `.Call` name.
+//! The name is recorded in the `symbol` field of the metadata.
+//!
 //! [`R_NilValue`]: https://extendr.github.io/libR-sys/libR_sys/static.R_NilValue.html
 //! [`SEXP`]: https://extendr.github.io/libR-sys/libR_sys/type.SEXP.html
 
@@ -31,6 +36,8 @@ use crate::extendr_options::ExtendrOptions;
 
 pub const META_PREFIX: &str = "meta__";
 pub const WRAP_PREFIX: &str = "wrap__";
+pub const CONV_PREFIX: &str = "conv__";
+pub const REGISTRY_PREFIX: &str = "registry__";
 
 // Generate wrappers for a specific function.
 pub(crate) fn make_function_wrappers(
@@ -56,17 +63,49 @@ pub(crate) fn make_function_wrappers(
     };
 
     let mod_name = sanitize_identifier(mod_name);
-    let wrap_name = format_ident!("{}{}{}", WRAP_PREFIX, prefix, mod_name);
+    let wrap_name = if let Some(symbol) = opts.symbol.as_ref() {
+        format_ident!("{}", symbol)
+    } else {
+        let wrap_prefix = opts.symbol_prefix.as_deref().unwrap_or(WRAP_PREFIX);
+        format_ident!("{}{}{}", wrap_prefix, prefix, mod_name)
+    };
     let meta_name = format_ident!("{}{}{}", META_PREFIX, prefix, mod_name);
 
     let rust_name_str = format!("{}", rust_name);
     let c_name_str = format!("{}", mod_name);
+    let symbol_str = wrap_name.to_string();
     let doc_string = get_doc_string(attrs);
     let return_type_string = get_return_type(sig);
 
     let inputs = &mut sig.inputs;
     let has_self = matches!(inputs.iter().next(), Some(FnArg::Receiver(_)));
 
+    // `.External` routines receive the whole call as a pairlist, which is
+    // passed on to the single argument of the function.
+    if opts.external {
+        if self_ty.is_some() {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                "`external` is only supported on functions",
+            ));
+        }
+        if inputs.len() != 1 {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                "`external` functions take a single argument receiving the argument pairlist",
+            ));
+        }
+    }
+
+    // `async fn`s return a promise, which outlives the call and cannot borrow `self`.
+    let is_async = sig.asyncness.is_some();
+    if is_async && self_ty.is_some() {
+        return Err(syn::Error::new_spanned(
+            sig.asyncness,
+            "`async` is only supported on functions",
+        ));
+    }
+
     let call_name = if has_self {
         let is_mut = match inputs.iter().next() {
             Some(FnArg::Receiver(ref receiver)) => receiver.mutability.is_some(),
@@ -120,11 +159,72 @@ pub(crate) fn make_function_wrappers(
     let actual_args: Punctuated<Expr, Token![,]> =
         inputs.iter().filter_map(translate_actual).collect();
 
+    // skip the routine name at the head of the `.External` call
+    let external_args = if opts.external {
+        quote!(#( let #sexp_args = extendr_api::CDR(#sexp_args); )*)
+    } else {
+        quote!()
+    };
+    let external = opts.external;
+
+    // instances of generic functions are called by an R function that dispatches on type
+    let dispatch = match opts.instance_of.as_ref() {
+        Some(instance) => {
+            let (generic, arg, r_type) = (&instance.generic, &instance.arg, &instance.r_type);
+            quote!(Some(extendr_api::metadata::Dispatch {
+                generic: #generic,
+                arg: #arg,
+                r_type: #r_type,
+            }))
+        }
+        None => quote!(None),
+    };
+
+    let s4_method = match opts.s4_method.as_ref() {
+        Some(_) if self_ty.is_some() => {
+            return Err(syn::Error::new_spanned(
+                &rust_name,
+                "`s4_method` is only supported on functions",
+            ))
+        }
+        Some((generic, signature)) => quote!(Some(extendr_api::metadata::S4Method {
+            generic: #generic,
+            signature: #signature,
+        })),
+        None => quote!(None),
+    };
+
+    // raise an R error before converting the arguments if R is too old
+    let min_r_check = opts
+        .min_r
+        .as_ref()
+        .map(|min_r| quote!(extendr_api::version::check_min_r_version(#r_name_str, #min_r)?;))
+        .unwrap_or_default();
+
+    // names of the arguments, for panic reports
+    let sexp_arg_names = sexp_args.iter().map(|x| x.to_string());
+
     let meta_args: Vec<Expr> = inputs
         .iter_mut()
-        .map(|input| translate_meta_arg(input, self_ty))
+        .map(|input| translate_meta_arg(input, self_ty, opts))
         .collect::<syn::Result<Vec<Expr>>>()?;
 
+    // every condition of `validate(...)` must name an argument
+    for (name, _) in &opts.validate {
+        let found = inputs.iter().any(|input| match input {
+            FnArg::Typed(pattype) => {
+                translate_only_alias(&pattype.pat).is_ok_and(|pat| pat == name)
+            }
+            FnArg::Receiver(_) => false,
+        });
+        if !found {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!("`{}` is not an argument of this function", name),
+            ));
+        }
+    }
+
     // Generate wrappers for rust functions to be called from R.
     // Example:
     // ```
@@ -182,7 +282,42 @@ pub(crate) fn make_function_wrappers(
         }
     };
 
-    let return_type_conversion = if return_is_ref_self {
+    // `bool` and `Rbool` returns use R's shared TRUE/FALSE/NA objects,
+    // as the result goes straight back to R.
+    let return_is_logical_scalar = match sig.output {
+        syn::ReturnType::Type(_, ref return_type) => match return_type.as_ref() {
+            Type::Path(path) => path.path.is_ident("bool") || path.path.is_ident("Rbool"),
+            _ => false,
+        },
+        syn::ReturnType::Default => false,
+    };
+
+    // `impl Iterator<Item = T>` returns are collected into an R vector
+    let return_is_iterator = match sig.output {
+        syn::ReturnType::Type(_, ref return_type) => match return_type.as_ref() {
+            Type::ImplTrait(impl_trait) => impl_trait.bounds.iter().any(|bound| match bound {
+                syn::TypeParamBound::Trait(bound) => bound.path.segments.last().is_some_and(|x| {
+                    matches!(
+                        x.ident.to_string().as_str(),
+                        "Iterator" | "ExactSizeIterator" | "DoubleEndedIterator"
+                    )
+                }),
+                _ => false,
+            }),
+            _ => false,
+        },
+        syn::ReturnType::Default => false,
+    };
+
+    let return_type_conversion = if is_async {
+        quote!(extendr_api::future::spawn_promise(#call_name(#actual_args)))
+    } else if return_is_iterator {
+        quote!(Ok(extendr_api::Robj::from(
+            #call_name(#actual_args).collect::<Vec<_>>()
+        )))
+    } else if return_is_logical_scalar {
+        quote!(Ok(extendr_api::shared_logical_scalar(#call_name(#actual_args))))
+    } else if return_is_ref_self {
         // instead of converting &Self / &mut Self, pass on the passed
         // ExternalPtr<Self>
         quote!(
@@ -222,7 +357,14 @@ pub(crate) fn make_function_wrappers(
                 Box<dyn std::any::Any + Send>
             > = unsafe {
                 std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || -> std::result::Result<extendr_api::Robj, extendr_api::Error> {
+                    let _panic_guard = extendr_api::panic_hook::CallGuard::enter(
+                        #r_name_str,
+                        &[#((#sexp_arg_names, #sexp_args)),*],
+                    );
+                    #min_r_check
+                    #external_args
                     #(#convert_args)*
+                    extendr_api::gc_stress::gc_point();
                     #return_type_conversion
                 }))
             };
@@ -233,28 +375,37 @@ pub(crate) fn make_function_wrappers(
             // any obj created in above unsafe scope, which are not moved into wrap_result_state are now dropped
             match wrap_result_state {
                 Ok(Ok(zz)) => {
+                    extendr_api::gc_stress::gc_point();
                     return unsafe { zz.get() };
                 }
                 // any conversion error bubbled from #actual_args conversions of incoming args from R.
                 Ok(Err(conversion_err)) => {
                     let err_string = conversion_err.to_string();
                     drop(conversion_err); // try_from=true errors contain Robj, this must be dropped to not leak
-                    extendr_api::throw_r_error(&err_string);
+                    extendr_api::throw_r_condition(
+                        err_string,
+                        std::any::type_name::<extendr_api::Error>(),
+                        None,
+                    );
                 }
                 // any panic (induced by user func code or if user func yields a Result-Err as return value)
                 Err(unwind_err) => {
-                    drop(unwind_err); //did not notice any difference if dropped or not.
-                    // It should be possible to downcast the unwind_err Any type to the error
-                    // included in panic. The advantage would be the panic cause could be included
-                    // in the R terminal error message and not only via std-err.
-                    // but it should be handled in a separate function and not in-lined here.
-                    let err_string = format!("User function panicked: {}", #r_name_str);
-                    // cannot use throw_r_error here for some reason.
-                    // handle_panic() exports err string differently than throw_r_error.
-                    extendr_api::handle_panic(err_string.as_str(), || panic!());
+                    let err_string = match extendr_api::panic_hook::take_raw_report() {
+                        // the extendr panic hook recorded the panic message
+                        Some(report) => format!("User function panicked: {}\n{}", #r_name_str, report),
+                        // otherwise include the panic message, if it is a string
+                        None => match extendr_api::panic_hook::panic_message(&*unwind_err) {
+                            Some(message) => format!("User function panicked: {}\n{}", #r_name_str, message),
+                            None => format!("User function panicked: {}", #r_name_str),
+                        },
+                    };
+                    drop(unwind_err);
+                    // a returned `Err` records its type before panicking
+                    let rust_type = extendr_api::error::take_panic_error_type().unwrap_or("panic");
+                    let backtrace = extendr_api::panic_hook::take_backtrace();
+                    extendr_api::throw_r_condition(err_string, rust_type, backtrace);
                 }
             }
-            unreachable!("internal extendr error, this should never happen.")
         }
     ));
 
@@ -271,17 +422,86 @@ pub(crate) fn make_function_wrappers(
                 rust_name: #rust_name_str,
                 r_name: #r_name_str,
                 mod_name: #c_name_str,
+                symbol: #symbol_str,
                 args: args,
                 return_type: #return_type_string,
                 func_ptr: #wrap_name as * const u8,
                 hidden: false,
+                external: #external,
+                dispatch: #dispatch,
+                s4_method: #s4_method,
             })
         }
     ));
 
+    // With `conversion-bench`, generate a function that only converts the arguments.
+    if cfg!(feature = "conversion-bench") && self_ty.is_none() {
+        let conv_name = format_ident!("{}{}{}", CONV_PREFIX, prefix, mod_name);
+        let num_args = inputs.len();
+        let conv_types = inputs.iter().filter_map(|input| match input {
+            FnArg::Typed(ref pattype) => Some(elide_lifetimes(pattype.ty.as_ref())),
+            FnArg::Receiver(_) => None,
+        });
+        let indices = 0..num_args;
+        wrappers.push(parse_quote!(
+            #[allow(non_snake_case)]
+            fn #conv_name(args: &[extendr_api::Robj]) -> extendr_api::Result<()> {
+                use extendr_api::robj::*;
+                if args.len() != #num_args {
+                    return Err(extendr_api::Error::ExpectedLength(#num_args));
+                }
+                #( let _: #conv_types = args[#indices].clone().try_into()?; )*
+                Ok(())
+            }
+        ));
+    }
+
     Ok(())
 }
 
+// Replace named lifetimes by `'_` so that argument types can be used in a `let`.
+fn elide_lifetimes(ty: &Type) -> Type {
+    struct Elide;
+    impl syn::visit_mut::VisitMut for Elide {
+        fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+            if lifetime.ident != "static" {
+                lifetime.ident = Ident::new("_", lifetime.ident.span());
+            }
+        }
+    }
+    let mut ty = ty.clone();
+    syn::visit_mut::VisitMut::visit_type_mut(&mut Elide, &mut ty);
+    ty
+}
+
+// Replace the type parameter `param` in `ty` by `replacement`, e.g. to instantiate
+// a generic signature. Returns true if `ty` used `param`.
+pub(crate) fn substitute_type_param(ty: &mut Type, param: &Ident, replacement: &Type) -> bool {
+    struct Substitute<'a> {
+        param: &'a Ident,
+        replacement: &'a Type,
+        found: bool,
+    }
+    impl syn::visit_mut::VisitMut for Substitute<'_> {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            match ty {
+                Type::Path(syn::TypePath { qself: None, path }) if path.is_ident(self.param) => {
+                    *ty = self.replacement.clone();
+                    self.found = true;
+                }
+                _ => syn::visit_mut::visit_type_mut(self, ty),
+            }
+        }
+    }
+    let mut substitute = Substitute {
+        param,
+        replacement,
+        found: false,
+    };
+    syn::visit_mut::VisitMut::visit_type_mut(&mut substitute, ty);
+    substitute.found
+}
+
 // Extract doc strings from attributes.
 pub fn get_doc_string(attrs: &[syn::Attribute]) -> String {
     let mut res = String::new();
@@ -329,6 +549,27 @@ pub fn mangled_type_name(type_: &Type) -> String {
     res
 }
 
+/// With the `registry` feature, add the metadata function `meta_name` to the
+/// distributed slice `slice` of `extendr_api::registry`.
+pub fn registry_entry(
+    meta_name: &Ident,
+    slice: &str,
+    metadata_type: &str,
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "registry") {
+        return quote!();
+    }
+    let entry_name = format_ident!("{}{}", REGISTRY_PREFIX, meta_name);
+    let slice = format_ident!("{}", slice);
+    let metadata_type = format_ident!("{}", metadata_type);
+    quote! {
+        #[extendr_api::registry::linkme::distributed_slice(extendr_api::registry::#slice)]
+        #[linkme(crate = extendr_api::registry::linkme)]
+        #[allow(non_upper_case_globals)]
+        static #entry_name: fn(&mut Vec<extendr_api::metadata::#metadata_type>) = #meta_name;
+    }
+}
+
 /// Return a simplified type name that will be meaningful to R. Defaults to a digest.
 // For example:
 // & Fred -> Fred
@@ -357,6 +598,79 @@ pub fn type_name(type_: &Type) -> String {
     }
 }
 
+// The kind of R object that an argument of this type must be, checked by the
+// R wrapper with `check_args = true`. See `metadata::Arg::check`.
+// Types that convert from several kinds of object, such as `Robj` or
+// `Option<T>`, are not checked.
+fn r_arg_check(type_: &Type) -> Option<&'static str> {
+    const NUMBERS: &[&str] = &[
+        "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32", "f64",
+        "Rint", "Rfloat",
+    ];
+    match type_ {
+        Type::Reference(syn::TypeReference { elem, .. })
+        | Type::Group(syn::TypeGroup { elem, .. })
+        | Type::Paren(syn::TypeParen { elem, .. }) => r_arg_check(elem),
+        Type::Slice(syn::TypeSlice { elem, .. }) => r_vector_check(elem),
+        Type::Path(syn::TypePath { path, .. }) => {
+            let segment = path.segments.last()?;
+            let name = segment.ident.to_string();
+            match name.as_str() {
+                name if NUMBERS.contains(&name) => Some("number"),
+                "bool" | "Rbool" => Some("flag"),
+                "str" | "String" | "Rstr" => Some("string"),
+                "Integers" | "Doubles" => Some("numeric"),
+                "Logicals" => Some("logical"),
+                "Strings" => Some("character"),
+                "List" => Some("list"),
+                "Function" => Some("function"),
+                "Environment" => Some("environment"),
+                "Vec" => match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+                        syn::GenericArgument::Type(elem) => r_vector_check(elem),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// The R type, as given by `typeof()`, of vectors with elements of this type,
+// for dispatching to the instances of a generic function.
+pub(crate) fn r_type_of(type_: &Type) -> Option<&'static str> {
+    let name = match type_ {
+        Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?.ident.to_string(),
+        _ => return None,
+    };
+    match name.as_str() {
+        "i8" | "i16" | "i32" | "Rint" => Some("integer"),
+        "i64" | "isize" | "u32" | "u64" | "usize" | "f32" | "f64" | "Rfloat" => Some("double"),
+        "u8" => Some("raw"),
+        "bool" | "Rbool" => Some("logical"),
+        "String" | "Rstr" => Some("character"),
+        "Rcplx" | "c64" => Some("complex"),
+        _ => None,
+    }
+}
+
+// The kind of R vector with elements of this type.
+fn r_vector_check(elem: &Type) -> Option<&'static str> {
+    // A single `u8` is a number, but vectors of them are raw vectors.
+    if r_type_of(elem) == Some("raw") {
+        return Some("raw");
+    }
+    match r_arg_check(elem)? {
+        "number" => Some("numeric"),
+        "flag" => Some("logical"),
+        "string" => Some("character"),
+        _ => None,
+    }
+}
+
 // Generate a list of arguments for the wrapper. All arguments are SEXP for .Call in R.
 pub fn translate_formal(input: &FnArg, self_ty: Option<&syn::Type>) -> syn::Result<FnArg> {
     match input {
@@ -402,7 +716,11 @@ fn translate_only_alias(pat: &syn::Pat) -> Result<&Ident, syn::Error> {
 }
 
 // Generate code to make a metadata::Arg.
-fn translate_meta_arg(input: &mut FnArg, self_ty: Option<&syn::Type>) -> syn::Result<Expr> {
+fn translate_meta_arg(
+    input: &mut FnArg,
+    self_ty: Option<&syn::Type>,
+    opts: &ExtendrOptions,
+) -> syn::Result<Expr> {
     match input {
         // function argument.
         FnArg::Typed(ref mut pattype) => {
@@ -418,11 +736,21 @@ fn translate_meta_arg(input: &mut FnArg, self_ty: Option<&syn::Type>) -> syn::Re
             } else {
                 quote!(None)
             };
+            let check = match opts.check_args.then(|| r_arg_check(ty)).flatten() {
+                Some(check) => quote!(Some(#check)),
+                None => quote!(None),
+            };
+            let validate = match opts.validate.iter().find(|(name, _)| name == pat_ident) {
+                Some((_, condition)) => quote!(Some(#condition)),
+                None => quote!(None),
+            };
             Ok(parse_quote! {
                 extendr_api::metadata::Arg {
                     name: #name_string,
                     arg_type: #type_string,
-                    default: #default
+                    default: #default,
+                    check: #check,
+                    validate: #validate
                 }
             })
         }
@@ -446,7 +774,9 @@ fn translate_meta_arg(input: &mut FnArg, self_ty: Option<&syn::Type>) -> syn::Re
                 extendr_api::metadata::Arg {
                     name: "self",
                     arg_type: #type_string,
-                    default: None
+                    default: None,
+                    check: None,
+                    validate: None
                 }
             })
         }
@@ -464,6 +794,15 @@ fn translate_to_robj(input: &FnArg) -> syn::Result<syn::Stmt> {
             if let syn::Pat::Ident(ref ident) = pat {
                 let varname = format_ident!("_{}_robj", ident.ident);
                 let ident = &ident.ident;
+                if borrowed_vector(&pattype.ty) == Some(true) {
+                    // copy objects that are visible elsewhere before lending them mutably
+                    return Ok(parse_quote! {
+                        let mut #varname = {
+                            let robj = extendr_api::robj::Robj::from_sexp(#ident);
+                            if robj.is_shared() { robj.duplicate() } else { robj }
+                        };
+                    });
+                }
                 // TODO: these do not need protection, as they come from R
                 Ok(parse_quote! { let #varname = extendr_api::robj::Robj::from_sexp(#ident); })
             } else {
@@ -487,9 +826,26 @@ fn translate_actual(input: &FnArg) -> Option<Expr> {
             let pat = &pattype.pat.as_ref();
             if let syn::Pat::Ident(ref ident) = pat {
                 let varname = format_ident!("_{}_robj", ident.ident);
-                Some(parse_quote! {
-                    #varname.try_into()?
-                })
+                // slices and arrays borrow the data of the R vector
+                let borrow = match borrowed_vector(&pattype.ty) {
+                    Some(true) => quote!(&mut #varname),
+                    Some(false) => quote!(&#varname),
+                    None => return Some(parse_quote! { #varname.try_into()? }),
+                };
+                match pattype.ty.as_ref() {
+                    Type::Reference(syn::TypeReference {
+                        elem, mutability, ..
+                    }) => match elem.as_ref() {
+                        Type::Array(syn::TypeArray { elem, len, .. }) => Some(parse_quote! {{
+                            let slice: &#mutability [#elem] = (#borrow).try_into()?;
+                            slice
+                                .try_into()
+                                .map_err(|_| extendr_api::Error::ExpectedLength(#len))?
+                        }}),
+                        _ => Some(parse_quote! { (#borrow).try_into()? }),
+                    },
+                    _ => unreachable!(),
+                }
             } else {
                 None
             }
@@ -501,6 +857,20 @@ fn translate_actual(input: &FnArg) -> Option<Expr> {
     }
 }
 
+// Whether an argument of this type is a `&[T]`, `&mut [T]`, `&[T; N]` or
+// `&mut [T; N]` borrowed from the R vector, and if so, whether it is mutable.
+fn borrowed_vector(type_: &Type) -> Option<bool> {
+    match type_ {
+        Type::Reference(syn::TypeReference {
+            elem, mutability, ..
+        }) => match elem.as_ref() {
+            Type::Slice(_) | Type::Array(_) => Some(mutability.is_some()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 // Get a single named literal from a list of attributes.
 // eg. #[default="xyz"]
 // Remove the attribute from the list.
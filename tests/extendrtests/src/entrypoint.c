@@ -6,3 +6,9 @@ void R_init_extendrtests_extendr(void *dll);
 void R_init_extendrtests(void *dll) {
   R_init_extendrtests_extendr(dll);
 }
+
+void R_unload_extendrtests_extendr(void *dll);
+
+void R_unload_extendrtests(void *dll) {
+  R_unload_extendrtests_extendr(dll);
+}